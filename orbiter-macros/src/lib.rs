@@ -0,0 +1,400 @@
+//! Procedural macros for the `orbiter` crate.
+//!
+//! Use them through the re-exports of the `orbiter` crate.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, parse_quote, Attribute, Error, Ident, Item, ItemFn, ItemMod, Result};
+
+/// Declares the entry points of an Orbiter plugin.
+///
+/// This attribute is placed on an inline module. The items of the module are
+/// marked with the following attributes:
+///
+/// - `#[init]`: function called when Orbiter loads the DLL. It takes a
+///   `&mut InstanceHandle` and returns either nothing or a `Result<(), E>`
+///   where `E` implements `Display`. Errors are written to `Orbiter.log`.
+/// - `#[exit]`: function called when Orbiter unloads the DLL, with the same
+///   signature as the `init` function. The loaded `Config` files are saved
+///   after it.
+/// - `#[module]`: type implementing `ModuleCallbacks` and `Default`. It is
+///   registered automatically after the `init` function succeeded.
+/// - `#[vessel_init]` and `#[vessel_exit]`: vessel class entry points, called
+///   when Orbiter creates and destroys a vessel of the class. They take a
+///   `&mut Vessel` (and the flight model, an `i32`, for `vessel_init`) and
+///   return either nothing or a `Result<(), E>` like the `init` function.
+///   The vessel keeps the default behavior of Orbiter, configured by its class
+///   file, and can be customized with the setters of `VesselTrait`.
+///
+/// All these items are optional, but `#[vessel_init]` and `#[vessel_exit]`
+/// must be used together.
+///
+/// **You must use this attribute at most once in your crate, and not together
+/// with the `init!` macro.**
+///
+/// # Examples
+///
+/// ```no_run
+/// #[orbiter::plugin]
+/// mod plugin {
+///     use orbiter::*;
+///
+///     #[init]
+///     fn init(_instance: &mut InstanceHandle) -> Result<(), String> {
+///         println!("Hello from Rust!");
+///         Ok(())
+///     }
+///
+///     #[module]
+///     #[derive(Default)]
+///     struct MyModule;
+///
+///     impl ModuleCallbacks for MyModule {}
+/// }
+/// # fn main() {}
+/// ```
+///
+/// A vessel class is declared with its two entry points:
+///
+/// ```no_run
+/// #[orbiter::plugin]
+/// mod plugin {
+///     use orbiter::*;
+///
+///     #[vessel_init]
+///     fn vessel_init(vessel: &mut Vessel, _flight_model: i32) {
+///         vessel.set_attitude_mode(AttitudeMode::Rotational);
+///     }
+///
+///     #[vessel_exit]
+///     fn vessel_exit(_vessel: &mut Vessel) {}
+/// }
+/// # fn main() {}
+/// ```
+#[proc_macro_attribute]
+pub fn plugin(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = TokenStream2::from(args);
+    let input = parse_macro_input!(input as ItemMod);
+
+    match expand_plugin(args, input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum Role {
+    Init,
+    Exit,
+    Module,
+    VesselInit,
+    VesselExit,
+}
+
+impl Role {
+    fn from_attribute(attr: &Attribute) -> Result<Option<Self>> {
+        let role = if attr.path.is_ident("init") {
+            Self::Init
+        } else if attr.path.is_ident("exit") {
+            Self::Exit
+        } else if attr.path.is_ident("module") {
+            Self::Module
+        } else if attr.path.is_ident("vessel_init") {
+            Self::VesselInit
+        } else if attr.path.is_ident("vessel_exit") {
+            Self::VesselExit
+        } else {
+            return Ok(None);
+        };
+
+        if !attr.tokens.is_empty() {
+            return Err(Error::new_spanned(
+                &attr.tokens,
+                format!("`#[{}]` does not take arguments", role.name()),
+            ));
+        }
+
+        Ok(Some(role))
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Init => "init",
+            Self::Exit => "exit",
+            Self::Module => "module",
+            Self::VesselInit => "vessel_init",
+            Self::VesselExit => "vessel_exit",
+        }
+    }
+}
+
+#[derive(Default)]
+struct Plugin {
+    init: Option<Ident>,
+    exit: Option<Ident>,
+    modules: Vec<Ident>,
+    vessel_init: Option<Ident>,
+    vessel_exit: Option<Ident>,
+}
+
+fn expand_plugin(args: TokenStream2, mut input: ItemMod) -> Result<TokenStream2> {
+    if !args.is_empty() {
+        return Err(Error::new_spanned(
+            args,
+            "`#[orbiter::plugin]` does not take arguments",
+        ));
+    }
+
+    let items = match &mut input.content {
+        Some((_, items)) => items,
+        None => return Err(Error::new_spanned(
+            &input,
+            "`#[orbiter::plugin]` must be placed on an inline module, e.g. `mod plugin { ... }`",
+        )),
+    };
+
+    let mut plugin = Plugin::default();
+    for item in items.iter_mut() {
+        if let Some((role, span)) = take_role(item)? {
+            register_item(&mut plugin, role, span, item)?;
+        }
+    }
+
+    match (&plugin.vessel_init, &plugin.vessel_exit) {
+        (Some(ident), None) => {
+            return Err(Error::new_spanned(
+                ident,
+                "a `#[vessel_exit]` function is required with `#[vessel_init]`",
+            ))
+        }
+        (None, Some(ident)) => {
+            return Err(Error::new_spanned(
+                ident,
+                "a `#[vessel_init]` function is required with `#[vessel_exit]`",
+            ))
+        }
+        _ => (),
+    }
+
+    let mod_ident = &input.ident;
+
+    let init_call = plugin.init.as_ref().map(|ident| {
+        quote_spanned! {ident.span()=>
+            match ::orbiter::catch_panic("Plugin initialization", || ::orbiter::PluginResult::into_plugin_result(#mod_ident::#ident(&mut instance))) {
+                Some(Ok(())) => (),
+                Some(Err(err)) => {
+                    ::orbiter::write_log(&format!("Plugin initialization failed: {}", err));
+                    return;
+                }
+                None => return,
+            }
+        }
+    });
+    let module_registrations = plugin.modules.iter().map(|ident| {
+        quote_spanned! {ident.span()=>
+            ::orbiter::catch_panic("Module registration", || {
+                instance.register_module(<#mod_ident::#ident as ::std::default::Default>::default());
+            });
+        }
+    });
+    let exit_call = plugin.exit.as_ref().map(|ident| {
+        quote_spanned! {ident.span()=>
+            if let Some(Err(err)) = ::orbiter::catch_panic("Plugin cleanup", || ::orbiter::PluginResult::into_plugin_result(#mod_ident::#ident(&mut instance))) {
+                ::orbiter::write_log(&format!("Plugin cleanup failed: {}", err));
+            }
+        }
+    });
+    let vessel_entry_points = match (&plugin.vessel_init, &plugin.vessel_exit) {
+        (Some(init), Some(exit)) => {
+            let init_call = quote_spanned! {init.span()=>
+                if let Some(Err(err)) = ::orbiter::catch_panic("Vessel initialization", || ::orbiter::PluginResult::into_plugin_result(#mod_ident::#init(&mut vessel, flight_model))) {
+                    ::orbiter::write_log(&format!("Vessel initialization failed: {}", err));
+                }
+            };
+            let exit_call = quote_spanned! {exit.span()=>
+                if let Some(Err(err)) = ::orbiter::catch_panic("Vessel cleanup", || ::orbiter::PluginResult::into_plugin_result(#mod_ident::#exit(&mut vessel))) {
+                    ::orbiter::write_log(&format!("Vessel cleanup failed: {}", err));
+                }
+            };
+            quote! {
+                // This is called when Orbiter creates a vessel of this class
+                #[no_mangle]
+                pub unsafe extern "C" fn ovcInit(
+                    vessel: ::orbiter::OBJHANDLE,
+                    flight_model: ::std::os::raw::c_int,
+                ) -> ::orbiter::VESSEL {
+                    let mut vessel = ::orbiter::Vessel::create_instance(vessel, flight_model);
+                    #init_call
+                    ::orbiter::VesselTrait::vessel_handle(&vessel)
+                }
+
+                // This is called when Orbiter destroys a vessel of this class
+                #[no_mangle]
+                pub unsafe extern "C" fn ovcExit(vessel: ::orbiter::VESSEL) {
+                    let mut vessel = ::orbiter::Vessel::from_instance(vessel);
+                    #exit_call
+                    vessel.destroy_instance();
+                }
+            }
+        }
+        _ => TokenStream2::new(),
+    };
+
+    Ok(quote! {
+        #input
+
+        // This is a required symbol that returns the date of build
+        #[no_mangle]
+        pub extern "C" fn ModuleDate() -> *const ::std::os::raw::c_char {
+            ::orbiter::get_module_date()
+        }
+
+        // This is called when the module is loaded by Orbiter
+        #[no_mangle]
+        pub unsafe extern "C" fn InitModule(module: ::orbiter::HINSTANCE) {
+            #[allow(unused_mut, unused_variables)]
+            let mut instance = ::orbiter::InstanceHandle::from(module);
+            instance.track_vessel_deletions();
+            #init_call
+            #(#module_registrations)*
+        }
+
+        // This is called before the module is unloaded by Orbiter
+        #[no_mangle]
+        pub extern "C" fn ExitModule(module: ::orbiter::HINSTANCE) {
+            #[allow(unused_mut, unused_variables)]
+            let mut instance = ::orbiter::InstanceHandle::from(module);
+            #exit_call
+            ::orbiter::catch_panic("Configuration saving", ::orbiter::config::save_loaded);
+        }
+
+        #vessel_entry_points
+    })
+}
+
+/// Removes the plugin attribute of an item, if any.
+fn take_role(item: &mut Item) -> Result<Option<(Role, Span)>> {
+    let attrs = match item {
+        Item::Fn(item) => &mut item.attrs,
+        Item::Struct(item) => &mut item.attrs,
+        Item::Enum(item) => &mut item.attrs,
+        _ => return Ok(None),
+    };
+
+    let mut result = None;
+    let mut index = 0;
+    while index < attrs.len() {
+        match Role::from_attribute(&attrs[index])? {
+            Some(role) => {
+                let attr = attrs.remove(index);
+                if result.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "an item can only have one plugin attribute",
+                    ));
+                }
+                result = Some((role, attr.span()));
+            }
+            None => index += 1,
+        }
+    }
+
+    Ok(result)
+}
+
+fn register_item(plugin: &mut Plugin, role: Role, span: Span, item: &mut Item) -> Result<()> {
+    match (role, item) {
+        (Role::Module, Item::Struct(item)) => {
+            make_visible(&mut item.vis);
+            plugin.modules.push(item.ident.clone());
+            Ok(())
+        }
+        (Role::Module, Item::Enum(item)) => {
+            make_visible(&mut item.vis);
+            plugin.modules.push(item.ident.clone());
+            Ok(())
+        }
+        (Role::Module, _) => Err(Error::new(
+            span,
+            "`#[module]` must be placed on a struct or an enum",
+        )),
+        (role, Item::Fn(item)) => {
+            let expected_args = match role {
+                Role::VesselInit => 2,
+                _ => 1,
+            };
+            check_function(item, role, expected_args)?;
+            make_visible(&mut item.vis);
+
+            let slot = match role {
+                Role::Init => &mut plugin.init,
+                Role::Exit => &mut plugin.exit,
+                Role::VesselInit => &mut plugin.vessel_init,
+                Role::VesselExit => &mut plugin.vessel_exit,
+                Role::Module => unreachable!(),
+            };
+            if slot.is_some() {
+                return Err(Error::new(
+                    span,
+                    format!("only one `#[{}]` function is allowed", role.name()),
+                ));
+            }
+            *slot = Some(item.sig.ident.clone());
+            Ok(())
+        }
+        (role, _) => Err(Error::new(
+            span,
+            format!("`#[{}]` must be placed on a function", role.name()),
+        )),
+    }
+}
+
+fn check_function(item: &ItemFn, role: Role, expected_args: usize) -> Result<()> {
+    let sig = &item.sig;
+    if let Some(asyncness) = &sig.asyncness {
+        return Err(Error::new_spanned(
+            asyncness,
+            format!("`#[{}]` functions cannot be async", role.name()),
+        ));
+    }
+    if !sig.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &sig.generics,
+            format!("`#[{}]` functions cannot be generic", role.name()),
+        ));
+    }
+    if let Some(variadic) = &sig.variadic {
+        return Err(Error::new_spanned(
+            variadic,
+            format!("`#[{}]` functions cannot be variadic", role.name()),
+        ));
+    }
+    if sig.inputs.len() != expected_args {
+        let message = match role {
+            Role::Init | Role::Exit => {
+                "expected a single `&mut InstanceHandle` argument".to_owned()
+            }
+            Role::VesselInit => "expected `(&mut Vessel, i32)` arguments".to_owned(),
+            Role::VesselExit => "expected a single `&mut Vessel` argument".to_owned(),
+            Role::Module => unreachable!(),
+        };
+        let span = if sig.inputs.is_empty() {
+            sig.paren_token.span
+        } else {
+            sig.inputs.span()
+        };
+        return Err(Error::new(span, message));
+    }
+
+    Ok(())
+}
+
+fn make_visible(vis: &mut syn::Visibility) {
+    if let syn::Visibility::Inherited = vis {
+        *vis = parse_quote!(pub(super));
+    }
+}
@@ -6,7 +6,7 @@ macro_rules! error {
     ($($args:tt)+) => ({
         let msg = format!($($args)*);
         println!("cargo:warning={}", msg);
-        panic!(msg);
+        panic!("{}", msg);
     })
 }
 
@@ -14,6 +14,20 @@ fn main() -> Result<(), std::io::Error> {
     const ORBITER_DIR_ENV: &str = "ORBITER_DIR";
     const ORBITER_SDK_ENV: &str = "ORBITER_SDK";
 
+    // Build date is required by Orbiter in the ModuleDate callback
+    // To make sure the current date is used, clean rebuild before
+    // sending the module to the end-user.
+    let now = Utc::now();
+    let date = now.format("%b %e %Y");
+    println!("cargo:rustc-env=ORBITER_DATE={}", date);
+
+    // Other hosts only build the pure Rust parts, e.g. to run unit tests,
+    // without the C API
+    if env::var("CARGO_CFG_WINDOWS").is_err() {
+        println!("cargo:rerun-if-changed=build.rs");
+        return Ok(());
+    }
+
     // Check target triple for MSVC 32-bit
     if env::var("TARGET").unwrap() != "i686-pc-windows-msvc" {
         error!("Orbiter plugins must use the `i686-pc-windows-msvc` target");
@@ -57,8 +71,14 @@ fn main() -> Result<(), std::io::Error> {
     let header_files = ["src/cpp/types.h"];
     let cpp_files = [
         "src/cpp/main.cpp",
+        "src/cpp/atmosphere.cpp",
+        "src/cpp/base.cpp",
+        "src/cpp/celestial.cpp",
+        "src/cpp/config.cpp",
         "src/cpp/module.cpp",
+        "src/cpp/nav.cpp",
         "src/cpp/object.cpp",
+        "src/cpp/terrain.cpp",
         "src/cpp/vessel.cpp",
     ];
 
@@ -90,16 +110,9 @@ fn main() -> Result<(), std::io::Error> {
     println!("cargo:rustc-link-lib=Orbitersdk");
     println!("cargo:rustc-link-search={}", lib_path.to_string_lossy());
 
-    // Build date is required by Orbiter in the ModuleDate callback
-    // To make sure the current date is used, clean rebuild before
-    // sending the module to the end-user.
-    let now = Utc::now();
-    let date = now.format("%b %e %Y");
-    println!("cargo:rustc-env=ORBITER_DATE={}", date);
-
     // Check if statically linking with the C runtime
     let is_static_crt =
-        env::var("CARGO_CFG_TARGET_FEATURE").map_or(false, |val| val.contains("crt-static"));
+        env::var("CARGO_CFG_TARGET_FEATURE").is_ok_and(|val| val.contains("crt-static"));
     if !is_static_crt {
         println!("cargo:warning=Your module does not link statically with the C runtime.");
         println!("cargo:warning=This means that the end-user will need to install Visual C++ redistributables.");
@@ -0,0 +1,272 @@
+use crate::{oapic_VECTOR3, Planet, Vector3, OBJHANDLE};
+use std::os::raw::c_int;
+
+/// Horizon reference frame in Orbiter's `REFFRAME` enumeration
+const FRAME_HORIZON: c_int = 3;
+
+/// Local horizon frame for `oapiGetWindVector`, which has its own numbering
+const WIND_FRAME_HORIZON: c_int = 0;
+
+/// Physical constants of the atmosphere of a planet
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AtmosphereConstants {
+    /// Pressure at mean radius, in Pa
+    pub surface_pressure: f64,
+    /// Density at mean radius, in kg/m³
+    pub surface_density: f64,
+    /// Specific gas constant, in J/(K kg)
+    pub gas_constant: f64,
+    /// Ratio of specific heats
+    pub gamma: f64,
+    /// Altitude above which the atmosphere is neglected, in m
+    pub altitude_limit: f64,
+}
+
+/// State of the atmosphere at a given point
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AtmosphereState {
+    /// Temperature, in K
+    pub temperature: f64,
+    /// Pressure, in Pa
+    pub pressure: f64,
+    /// Density, in kg/m³
+    pub density: f64,
+    /// Speed of sound, in m/s
+    ///
+    /// Zero above the altitude limit of the atmosphere, where the temperature
+    /// is zero.
+    pub speed_of_sound: f64,
+}
+
+impl AtmosphereState {
+    fn new(params: &oapic_ATMPARAM, constants: &AtmosphereConstants) -> Self {
+        Self {
+            temperature: params.T,
+            pressure: params.p,
+            density: params.rho,
+            speed_of_sound: (constants.gamma * constants.gas_constant * params.T).sqrt(),
+        }
+    }
+
+    /// Returns the Mach number of an airspeed (in m/s), or `None` where the
+    /// speed of sound is zero.
+    fn mach(&self, airspeed: f64) -> Option<f64> {
+        if self.speed_of_sound > 0.0 {
+            Some(airspeed / self.speed_of_sound)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the dynamic pressure at an airspeed (in m/s), in Pa.
+    fn dynamic_pressure(&self, airspeed: f64) -> f64 {
+        0.5 * self.density * airspeed * airspeed
+    }
+}
+
+impl Planet {
+    /// Returns the constants of the atmosphere of the planet, or `None` if the
+    /// planet has no atmosphere.
+    pub fn atmosphere_constants(&self) -> Option<AtmosphereConstants> {
+        let constants = unsafe { oapic_oapiGetPlanetAtmConstants(self.handle).as_ref()? };
+        Some(AtmosphereConstants {
+            surface_pressure: constants.p0,
+            surface_density: constants.rho0,
+            gas_constant: constants.R,
+            gamma: constants.gamma,
+            altitude_limit: constants.altlimit,
+        })
+    }
+
+    /// Returns the state of the atmosphere at an altitude (in m) above the
+    /// given coordinates.
+    ///
+    /// Returns `None` if the planet has no atmosphere.
+    pub fn atmosphere_at(
+        &self,
+        altitude: f64,
+        latitude: f64,
+        longitude: f64,
+    ) -> Option<AtmosphereState> {
+        let constants = self.atmosphere_constants()?;
+        let mut params = oapic_ATMPARAM::default();
+        unsafe {
+            oapic_oapiGetPlanetAtmParams(self.handle, altitude, longitude, latitude, &mut params)
+        };
+        Some(AtmosphereState::new(&params, &constants))
+    }
+
+    /// Returns the wind relative to the ground at an altitude (in m) above the
+    /// given coordinates, in m/s.
+    ///
+    /// The vector is expressed in the local horizon frame, with x pointing
+    /// east, y up and z north, like [`VesselAtmosphere::wind`]. Returns `None`
+    /// if the planet has no atmosphere.
+    ///
+    /// [`VesselAtmosphere::wind`]: ./struct.VesselAtmosphere.html#structfield.wind
+    pub fn wind_at(&self, altitude: f64, latitude: f64, longitude: f64) -> Option<Vector3> {
+        self.atmosphere_constants()?;
+        let mut wind = oapic_VECTOR3::new();
+        let valid = unsafe {
+            oapic_oapiGetWindVector(
+                self.handle,
+                longitude,
+                latitude,
+                altitude,
+                WIND_FRAME_HORIZON,
+                &mut wind,
+            )
+        };
+        if valid {
+            Some(Vector3::from(wind))
+        } else {
+            None
+        }
+    }
+}
+
+/// Atmospheric conditions experienced by a vessel
+///
+/// Returned by [`VesselTrait::atmosphere`].
+///
+/// [`VesselTrait::atmosphere`]: ./trait.VesselTrait.html#method.atmosphere
+#[derive(PartialEq)]
+pub struct VesselAtmosphere {
+    /// Planet of the atmosphere
+    pub planet: Planet,
+    /// State of the atmosphere at the position of the vessel
+    pub state: AtmosphereState,
+    /// Speed of the vessel relative to the air, in m/s
+    pub airspeed: f64,
+    /// Mach number of the vessel, or `None` where the speed of sound is zero,
+    /// i.e. above the altitude limit of the atmosphere
+    pub mach: Option<f64>,
+    /// Dynamic pressure, in Pa
+    pub dynamic_pressure: f64,
+    /// Wind relative to the ground, in m/s
+    ///
+    /// The vector is expressed in the local horizon frame of the vessel, with
+    /// x pointing east, y up and z north.
+    pub wind: Vector3,
+}
+
+impl VesselAtmosphere {
+    pub(crate) fn from_vessel(vessel: OBJHANDLE) -> Option<Self> {
+        let mut params = oapic_ATMPARAM::default();
+        let mut planet = std::ptr::null_mut();
+        unsafe { oapic_oapiGetAtm(vessel, &mut params, &mut planet) };
+        if planet.is_null() {
+            return None;
+        }
+        let planet = Planet { handle: planet };
+        let state = AtmosphereState::new(&params, &planet.atmosphere_constants()?);
+
+        let mut airspeed = oapic_VECTOR3::new();
+        let mut groundspeed = oapic_VECTOR3::new();
+        unsafe {
+            oapic_oapiGetAirspeedVector(vessel, FRAME_HORIZON, &mut airspeed);
+            oapic_oapiGetGroundspeedVector(vessel, FRAME_HORIZON, &mut groundspeed);
+        }
+        let airspeed = Vector3::from(airspeed);
+        let wind = Vector3::from(groundspeed) - airspeed;
+        let airspeed = airspeed.norm();
+
+        Some(Self {
+            planet,
+            mach: state.mach(airspeed),
+            dynamic_pressure: state.dynamic_pressure(airspeed),
+            airspeed,
+            state,
+            wind,
+        })
+    }
+}
+
+#[repr(C)]
+#[allow(non_snake_case)]
+#[derive(Default)]
+struct oapic_ATMPARAM {
+    T: f64,
+    p: f64,
+    rho: f64,
+}
+
+#[repr(C)]
+#[allow(non_snake_case, dead_code)]
+struct oapic_ATMCONST {
+    p0: f64,
+    rho0: f64,
+    R: f64,
+    gamma: f64,
+    C: f64,
+    O2pp: f64,
+    altlimit: f64,
+    radlimit: f64,
+    horizonalt: f64,
+    color0: oapic_VECTOR3,
+}
+
+#[cfg_attr(windows, link(name = "orbiter_c"))]
+extern "C" {
+    fn oapic_oapiGetPlanetAtmConstants(handle: OBJHANDLE) -> *const oapic_ATMCONST;
+    fn oapic_oapiGetPlanetAtmParams(
+        handle: OBJHANDLE,
+        alt: f64,
+        lng: f64,
+        lat: f64,
+        params: *mut oapic_ATMPARAM,
+    );
+    fn oapic_oapiGetAtm(vessel: OBJHANDLE, params: *mut oapic_ATMPARAM, planet: *mut OBJHANDLE);
+    fn oapic_oapiGetAirspeedVector(vessel: OBJHANDLE, frame: c_int, speed: *mut oapic_VECTOR3);
+    fn oapic_oapiGetGroundspeedVector(vessel: OBJHANDLE, frame: c_int, speed: *mut oapic_VECTOR3);
+    fn oapic_oapiGetWindVector(
+        planet: OBJHANDLE,
+        lng: f64,
+        lat: f64,
+        alt: f64,
+        frame: c_int,
+        wind: *mut oapic_VECTOR3,
+    ) -> bool;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Constants of the atmosphere of the Earth
+    fn earth() -> AtmosphereConstants {
+        AtmosphereConstants {
+            surface_pressure: 101.4e3,
+            surface_density: 1.293,
+            gas_constant: 286.91,
+            gamma: 1.4,
+            altitude_limit: 2.5e6,
+        }
+    }
+
+    #[test]
+    fn sea_level_state() {
+        let params = oapic_ATMPARAM {
+            T: 288.15,
+            p: 101_325.0,
+            rho: 1.225,
+        };
+        let state = AtmosphereState::new(&params, &earth());
+        assert_eq!(state.temperature, 288.15);
+        assert_eq!(state.pressure, 101_325.0);
+        assert_eq!(state.density, 1.225);
+        assert!((state.speed_of_sound - 340.2).abs() < 0.5);
+
+        assert!((state.mach(680.4).unwrap() - 2.0).abs() < 0.01);
+        assert!((state.dynamic_pressure(100.0) - 6125.0).abs() < 1e-9);
+        assert_eq!(state.dynamic_pressure(0.0), 0.0);
+    }
+
+    #[test]
+    fn no_mach_number_in_vacuum() {
+        let state = AtmosphereState::new(&oapic_ATMPARAM::default(), &earth());
+        assert_eq!(state.speed_of_sound, 0.0);
+        assert_eq!(state.mach(7800.0), None);
+        assert_eq!(state.dynamic_pressure(7800.0), 0.0);
+    }
+}
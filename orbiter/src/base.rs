@@ -0,0 +1,189 @@
+use crate::win::minwindef::DWORD;
+use crate::{GeoPosition, NavTransmitter, ObjectTrait, Planet, NAVHANDLE, OBJHANDLE};
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int};
+
+/// A surface base
+///
+/// i.e. a spaceport, with landing pads and runways.
+#[derive(PartialEq, Eq, Hash)]
+pub struct SurfaceBase {
+    pub(crate) handle: OBJHANDLE,
+}
+
+impl ObjectTrait for SurfaceBase {
+    fn handle(&self) -> OBJHANDLE {
+        self.handle
+    }
+}
+
+impl SurfaceBase {
+    /// Returns the planet the base is located on.
+    pub fn planet(&self) -> Planet {
+        Planet {
+            handle: unsafe { oapic_oapiGetBasePlanet(self.handle) },
+        }
+    }
+
+    /// Returns the position of the base in the equatorial frame of its planet.
+    pub fn equatorial_position(&self) -> GeoPosition {
+        let mut position = GeoPosition::new(0.0, 0.0, 0.0);
+        unsafe {
+            oapic_oapiGetBaseEquPos(
+                self.handle,
+                &mut position.longitude,
+                &mut position.latitude,
+                &mut position.radius,
+            )
+        };
+        position
+    }
+
+    /// Returns the number of landing pads of the base.
+    pub fn pad_count(&self) -> usize {
+        unsafe { oapic_oapiGetBasePadCount(self.handle) as usize }
+    }
+
+    /// Returns a landing pad of the base, or `None` if the index is out of range.
+    pub fn pad(&self, index: usize) -> Option<LandingPad> {
+        let mut position = GeoPosition::new(0.0, 0.0, 0.0);
+        let mut status = 0;
+        let index_raw = index as DWORD;
+        unsafe {
+            if !oapic_oapiGetBasePadEquPos(
+                self.handle,
+                index_raw,
+                &mut position.longitude,
+                &mut position.latitude,
+                &mut position.radius,
+            ) || !oapic_oapiGetBasePadStatus(self.handle, index_raw, &mut status)
+            {
+                return None;
+            }
+        }
+
+        Some(LandingPad {
+            index,
+            position,
+            status: PadStatus::from_raw(status),
+            transmitter: NavTransmitter::from(unsafe {
+                oapic_oapiGetBasePadNav(self.handle, index_raw)
+            }),
+        })
+    }
+
+    /// Returns all the landing pads of the base.
+    pub fn pads(&self) -> Vec<LandingPad> {
+        (0..self.pad_count())
+            .filter_map(|index| self.pad(index))
+            .collect()
+    }
+
+    /// Returns the VTOL transmitters of the landing pads of the base.
+    ///
+    /// The VOR and ILS transmitters of a base are not exposed by the Orbiter
+    /// API. They can only be found by tuning the navigation radios of a vessel.
+    pub fn transmitters(&self) -> Vec<NavTransmitter> {
+        self.pads()
+            .into_iter()
+            .filter_map(|pad| pad.transmitter)
+            .collect()
+    }
+}
+
+impl Planet {
+    /// Returns the surface bases of the planet.
+    pub fn bases(&self) -> Vec<SurfaceBase> {
+        let count = unsafe { oapic_oapiGetBaseCount(self.handle) };
+        (0..count)
+            .map(|index| unsafe { oapic_oapiGetBaseByIndex(self.handle, index) })
+            .filter(|handle| !handle.is_null())
+            .map(|handle| SurfaceBase { handle })
+            .collect()
+    }
+
+    /// Retrieves a surface base of the planet by its name.
+    pub fn base_by_name(&self, name: &str) -> Option<SurfaceBase> {
+        let name = CString::new(name).unwrap();
+        let handle = unsafe { oapic_oapiGetBaseByName(self.handle, name.as_ptr()) };
+        if handle.is_null() {
+            None
+        } else {
+            Some(SurfaceBase { handle })
+        }
+    }
+}
+
+/// Occupancy status of a landing pad
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PadStatus {
+    /// The pad is available
+    Free,
+    /// A vessel is landed on the pad
+    Occupied,
+    /// A vessel is cleared for landing on the pad
+    Cleared,
+}
+
+impl PadStatus {
+    fn from_raw(value: c_int) -> Option<Self> {
+        match value {
+            0 => Some(Self::Free),
+            1 => Some(Self::Occupied),
+            2 => Some(Self::Cleared),
+            _ => None,
+        }
+    }
+}
+
+/// A landing pad of a surface base
+///
+/// This is a snapshot of the pad when it was retrieved with
+/// [`SurfaceBase::pad`].
+///
+/// [`SurfaceBase::pad`]: ./struct.SurfaceBase.html#method.pad
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LandingPad {
+    /// Index of the pad in its base
+    pub index: usize,
+    /// Position of the pad in the equatorial frame of the planet
+    pub position: GeoPosition,
+    /// Occupancy status of the pad, or `None` if Orbiter returns an unknown
+    /// status
+    pub status: Option<PadStatus>,
+    /// VTOL transmitter of the pad, if any
+    pub transmitter: Option<NavTransmitter>,
+}
+
+#[cfg_attr(windows, link(name = "orbiter_c"))]
+extern "C" {
+    fn oapic_oapiGetBasePlanet(base: OBJHANDLE) -> OBJHANDLE;
+    fn oapic_oapiGetBaseEquPos(base: OBJHANDLE, lng: *mut f64, lat: *mut f64, rad: *mut f64);
+    fn oapic_oapiGetBasePadCount(base: OBJHANDLE) -> DWORD;
+    fn oapic_oapiGetBasePadEquPos(
+        base: OBJHANDLE,
+        pad: DWORD,
+        lng: *mut f64,
+        lat: *mut f64,
+        rad: *mut f64,
+    ) -> bool;
+    fn oapic_oapiGetBasePadStatus(base: OBJHANDLE, pad: DWORD, status: *mut c_int) -> bool;
+    fn oapic_oapiGetBasePadNav(base: OBJHANDLE, pad: DWORD) -> NAVHANDLE;
+    fn oapic_oapiGetBaseCount(planet: OBJHANDLE) -> DWORD;
+    fn oapic_oapiGetBaseByIndex(planet: OBJHANDLE, index: DWORD) -> OBJHANDLE;
+    fn oapic_oapiGetBaseByName(planet: OBJHANDLE, name: *const c_char) -> OBJHANDLE;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pad_statuses() {
+        assert_eq!(PadStatus::from_raw(0), Some(PadStatus::Free));
+        assert_eq!(PadStatus::from_raw(1), Some(PadStatus::Occupied));
+        assert_eq!(PadStatus::from_raw(2), Some(PadStatus::Cleared));
+        assert_eq!(PadStatus::from_raw(3), None);
+        assert_eq!(PadStatus::from_raw(-1), None);
+    }
+}
@@ -0,0 +1,192 @@
+use crate::win::minwindef::DWORD;
+use crate::{Object, ObjectTrait, OBJHANDLE};
+
+/// Gravitational constant used by Orbiter, in m³ kg⁻¹ s⁻²
+pub const GRAVITATIONAL_CONSTANT: f64 = 6.67259e-11;
+
+/// Common methods for celestial bodies (stars and planets)
+pub trait CelestialBodyTrait: ObjectTrait {
+    /// Returns the gravitational parameter (μ = GM) of the body in m³/s².
+    fn gravitational_parameter(&self) -> f64 {
+        GRAVITATIONAL_CONSTANT * self.mass()
+    }
+
+    /// Returns the sidereal rotation period of the body in seconds.
+    fn rotation_period(&self) -> f64 {
+        unsafe { oapic_oapiGetPlanetPeriod(self.handle()) }
+    }
+
+    /// Returns the obliquity of the rotation axis in radians.
+    ///
+    /// This is the angle between the rotation axis of the body and the
+    /// ecliptic normal.
+    fn obliquity(&self) -> f64 {
+        unsafe { oapic_oapiGetPlanetObliquity(self.handle()) }
+    }
+
+    /// Returns the equatorial radius of the body in meters.
+    ///
+    /// Orbiter models celestial bodies as spheres, so this is the same value
+    /// as [`size`].
+    ///
+    /// [`size`]: ./trait.ObjectTrait.html#method.size
+    fn equatorial_radius(&self) -> f64 {
+        self.size()
+    }
+
+    /// Returns the body this body orbits, or `None` for the root of the
+    /// system (usually the central star).
+    fn parent(&self) -> Option<Object> {
+        let handle = unsafe { oapic_oapiGetGbodyParent(self.handle()) };
+        Object::from(handle)
+    }
+
+    /// Returns the bodies orbiting this body, e.g. the moons of a planet.
+    fn children(&self) -> Vec<Planet> {
+        (0..)
+            .map(|index| unsafe { oapic_oapiGetGbodyChild(self.handle(), index) })
+            .take_while(|handle| !handle.is_null())
+            .filter_map(|handle| match Object::from(handle) {
+                Some(Object::Planet(planet)) => Some(planet),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the period of the current osculating orbit around the parent
+    /// body, in seconds.
+    ///
+    /// Returns `None` if the body has no parent or if it is not on a closed
+    /// orbit.
+    fn orbital_period(&self) -> Option<f64> {
+        let parent = self.parent()?;
+        let mu = GRAVITATIONAL_CONSTANT * (parent.mass() + self.mass());
+        let radius = self.relative_position(&parent).norm();
+        let speed = self.relative_velocity(&parent).norm();
+        osculating_period(mu, radius, speed)
+    }
+}
+
+/// Returns the period (in s) of an orbit from the gravitational parameter
+/// (in m³/s²), and the distance (in m) and speed (in m/s) relative to the
+/// central body, or `None` on an open orbit.
+fn osculating_period(mu: f64, radius: f64, speed: f64) -> Option<f64> {
+    let energy = speed * speed / 2.0 - mu / radius;
+    if energy >= 0.0 {
+        return None;
+    }
+    let semi_major_axis = -mu / (2.0 * energy);
+    Some(2.0 * std::f64::consts::PI * (semi_major_axis.powi(3) / mu).sqrt())
+}
+
+/// Returns the object as a celestial body, if it is a star or a planet.
+pub(crate) fn as_celestial(object: &Object) -> Option<&dyn CelestialBodyTrait> {
+    match object {
+        Object::Star(star) => Some(star),
+        Object::Planet(planet) => Some(planet),
+        _ => None,
+    }
+}
+
+/// A star
+#[derive(PartialEq, Eq, Hash)]
+pub struct Star {
+    pub(crate) handle: OBJHANDLE,
+}
+
+impl ObjectTrait for Star {
+    fn handle(&self) -> OBJHANDLE {
+        self.handle
+    }
+}
+
+impl CelestialBodyTrait for Star {}
+
+/// A planet
+///
+/// Used for all celestial bodies that are not stars, including moons, comets, etc.
+#[derive(PartialEq, Eq, Hash)]
+pub struct Planet {
+    pub(crate) handle: OBJHANDLE,
+}
+
+impl ObjectTrait for Planet {
+    fn handle(&self) -> OBJHANDLE {
+        self.handle
+    }
+}
+
+impl CelestialBodyTrait for Planet {}
+
+impl Planet {
+    /// Checks if the planet has an atmosphere.
+    pub fn has_atmosphere(&self) -> bool {
+        unsafe { oapic_oapiPlanetHasAtmosphere(self.handle) }
+    }
+
+    /// Returns the harmonic coefficients of the gravity field of the planet.
+    ///
+    /// The first element is J2, the second one is J3, etc. The vector is empty
+    /// if the planet uses a spherical gravity field, or if the user disabled
+    /// nonspherical gravity sources in the launchpad.
+    pub fn gravity_harmonics(&self) -> Vec<f64> {
+        let count = unsafe { oapic_oapiGetPlanetJCoeffCount(self.handle) };
+        (0..count)
+            .map(|index| unsafe { oapic_oapiGetPlanetJCoeff(self.handle, index) })
+            .collect()
+    }
+
+    /// Returns the J2 coefficient of the gravity field of the planet, if
+    /// available.
+    pub fn j2(&self) -> Option<f64> {
+        self.gravity_harmonics().first().copied()
+    }
+}
+
+#[cfg_attr(windows, link(name = "orbiter_c"))]
+extern "C" {
+    fn oapic_oapiGetPlanetPeriod(handle: OBJHANDLE) -> f64;
+    fn oapic_oapiGetPlanetObliquity(handle: OBJHANDLE) -> f64;
+    fn oapic_oapiGetGbodyParent(handle: OBJHANDLE) -> OBJHANDLE;
+    fn oapic_oapiGetGbodyChild(handle: OBJHANDLE, index: DWORD) -> OBJHANDLE;
+    fn oapic_oapiPlanetHasAtmosphere(handle: OBJHANDLE) -> bool;
+    fn oapic_oapiGetPlanetJCoeffCount(handle: OBJHANDLE) -> DWORD;
+    fn oapic_oapiGetPlanetJCoeff(handle: OBJHANDLE, n: DWORD) -> f64;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Mass of the Earth, in kg
+    const EARTH_MASS: f64 = 5.9722e24;
+
+    #[test]
+    fn gravitational_parameter_of_the_earth() {
+        let mu = GRAVITATIONAL_CONSTANT * EARTH_MASS;
+        assert!((mu / 3.986004418e14 - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn circular_and_elliptic_periods() {
+        let mu = GRAVITATIONAL_CONSTANT * EARTH_MASS;
+        let radius = 7000e3;
+        let circular = (mu / radius).sqrt();
+        let period = osculating_period(mu, radius, circular).unwrap();
+        assert!((period - 2.0 * std::f64::consts::PI * (radius.powi(3) / mu).sqrt()).abs() < 1e-6);
+
+        // The periapsis of an orbit reaching 3 times farther out
+        let speed = (mu * (2.0 / radius - 1.0 / (2.0 * radius))).sqrt();
+        let elliptic = osculating_period(mu, radius, speed).unwrap();
+        assert!((elliptic / period - 8.0_f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn open_orbits_have_no_period() {
+        let mu = GRAVITATIONAL_CONSTANT * EARTH_MASS;
+        let radius = 7000e3;
+        let escape = (2.0 * mu / radius).sqrt();
+        assert_eq!(osculating_period(mu, radius, escape), None);
+        assert_eq!(osculating_period(mu, radius, 2.0 * escape), None);
+    }
+}
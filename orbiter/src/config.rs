@@ -0,0 +1,939 @@
+//! Typed module configuration files.
+//!
+//! Orbiter configuration files are plain text files made of `KEY = value`
+//! lines. Lines starting with `;` are comments. This module maps such files
+//! to and from any struct implementing serde's [`Serialize`] and
+//! [`Deserialize`] traits.
+//!
+//! Only flat structs are supported. Each field can be a boolean (written as
+//! `TRUE` or `FALSE`), a number, a character, a string, an `Option`, a unit
+//! enum variant (written as its name) or a sequence of these types (written
+//! as space-separated values, like Orbiter vectors).
+//!
+//! [`Serialize`]: https://docs.rs/serde/1/serde/trait.Serialize.html
+//! [`Deserialize`]: https://docs.rs/serde/1/serde/trait.Deserialize.html
+
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+use serde::ser::{self, Impossible, Serialize};
+use std::cell::{Ref, RefCell, RefMut};
+use std::ffi::{CStr, CString};
+use std::fmt::Display;
+use std::os::raw::{c_char, c_int, c_void};
+use std::rc::Rc;
+use std::str::FromStr;
+
+thread_local! {
+    /// Configurations to write back when Orbiter unloads the DLL
+    static LOADED: RefCell<Vec<Rc<dyn SaveOnExit>>> = RefCell::new(Vec::new());
+}
+
+/// A configuration struct backed by a file in Orbiter's `Config` directory.
+///
+/// Keys missing from the file take the value found in `T::default()`. Every
+/// loaded configuration is written back to its file by `ExitModule`, after
+/// the `exit` function of [`init!`] or [`plugin`], so it is saved even if the
+/// module holding it was already destroyed. Call [`save`] to write it
+/// earlier, e.g. when the user changes a setting.
+///
+/// A `Config` is a shared handle: its clones refer to the same value, which
+/// is accessed with [`borrow`] and [`borrow_mut`]. Load each file only once,
+/// otherwise the last configuration saved at exit wins.
+///
+/// # Examples
+///
+/// ```no_run
+/// use orbiter::config::Config;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Default, Serialize, Deserialize)]
+/// #[serde(rename_all = "PascalCase")]
+/// struct Settings {
+///     show_hud: bool,
+///     max_range: f64,
+/// }
+///
+/// let settings = Config::<Settings>::load("MyModule.cfg").unwrap();
+/// settings.borrow_mut().show_hud = true;
+/// settings.save().unwrap();
+/// ```
+///
+/// [`save`]: #method.save
+/// [`borrow`]: #method.borrow
+/// [`borrow_mut`]: #method.borrow_mut
+/// [`init!`]: ../macro.init.html
+/// [`plugin`]: ../attr.plugin.html
+pub struct Config<T: Serialize + DeserializeOwned + Default + 'static> {
+    file: Rc<ConfigFile<T>>,
+}
+
+struct ConfigFile<T> {
+    name: String,
+    value: RefCell<T>,
+}
+
+impl<T: Serialize + DeserializeOwned + Default + 'static> Config<T> {
+    /// Loads a configuration file.
+    ///
+    /// The file name is relative to Orbiter's `Config` directory. If the file
+    /// does not exist, the default configuration is used.
+    pub fn load(file_name: &str) -> Result<Self, Error> {
+        let defaults = serialize_items(&T::default())?;
+        let c_file_name = CString::new(file_name).map_err(Error::custom)?;
+
+        let file = unsafe {
+            oapic_oapiOpenFile(c_file_name.as_ptr(), FILE_IN_ZEROONFAIL, PATH_ROOT_CONFIG)
+        };
+        let value = if file.is_null() {
+            T::default()
+        } else {
+            let lookup = |key: &str| read_item(file, key).or_else(|| find_item(&defaults, key));
+            let result = T::deserialize(ConfigDeserializer { lookup: &lookup });
+            unsafe { oapic_oapiCloseFile(file, FILE_IN_ZEROONFAIL) };
+            result?
+        };
+
+        let file = Rc::new(ConfigFile {
+            name: file_name.to_owned(),
+            value: RefCell::new(value),
+        });
+        LOADED.with(|loaded| loaded.borrow_mut().push(file.clone()));
+        Ok(Self { file })
+    }
+
+    /// Writes the configuration to its file, replacing the previous content.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the configuration is mutably borrowed.
+    pub fn save(&self) -> Result<(), Error> {
+        self.file.save()
+    }
+
+    /// Returns the name of the file, relative to Orbiter's `Config` directory.
+    pub fn file_name(&self) -> &str {
+        &self.file.name
+    }
+
+    /// Immutably borrows the configuration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the configuration is mutably borrowed.
+    pub fn borrow(&self) -> Ref<'_, T> {
+        self.file.value.borrow()
+    }
+
+    /// Mutably borrows the configuration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the configuration is already borrowed.
+    pub fn borrow_mut(&self) -> RefMut<'_, T> {
+        self.file.value.borrow_mut()
+    }
+}
+
+impl<T: Serialize + DeserializeOwned + Default + 'static> Clone for Config<T> {
+    fn clone(&self) -> Self {
+        Self {
+            file: self.file.clone(),
+        }
+    }
+}
+
+trait SaveOnExit {
+    fn save(&self) -> Result<(), Error>;
+    fn name(&self) -> &str;
+}
+
+impl<T: Serialize> SaveOnExit for ConfigFile<T> {
+    fn save(&self) -> Result<(), Error> {
+        let items = serialize_items(&*self.value.borrow())?;
+        let c_file_name = CString::new(self.name.as_str()).map_err(Error::custom)?;
+
+        let file = unsafe { oapic_oapiOpenFile(c_file_name.as_ptr(), FILE_OUT, PATH_ROOT_CONFIG) };
+        if file.is_null() {
+            return Err(Error::custom(format!(
+                "unable to open {} for writing",
+                self.name
+            )));
+        }
+        for (key, value) in items {
+            let key = CString::new(key).map_err(Error::custom)?;
+            let value = CString::new(value).map_err(Error::custom)?;
+            unsafe { oapic_oapiWriteItem_string(file, key.as_ptr(), value.as_ptr()) };
+        }
+        unsafe { oapic_oapiCloseFile(file, FILE_OUT) };
+
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Writes back all the loaded configurations. Called by `ExitModule`.
+#[doc(hidden)]
+pub fn save_loaded() {
+    for file in LOADED.with(|loaded| loaded.take()) {
+        if let Err(err) = file.save() {
+            crate::write_log(&format!("Unable to save {}: {}", file.name(), err));
+        }
+    }
+}
+
+/// Parses a struct from the content of an Orbiter configuration file.
+///
+/// Keys are matched case-insensitively, like Orbiter does.
+///
+/// # Examples
+///
+/// ```
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Settings {
+///     enabled: bool,
+///     offset: Vec<f64>,
+/// }
+///
+/// let settings: Settings = orbiter::config::from_str("ENABLED = TRUE\nOFFSET = 0 1.5 -2").unwrap();
+/// assert!(settings.enabled);
+/// assert_eq!(settings.offset, vec![0.0, 1.5, -2.0]);
+/// ```
+pub fn from_str<T: DeserializeOwned>(text: &str) -> Result<T, Error> {
+    let items = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with(';'))
+        .map(|line| match line.find('=') {
+            Some(index) => Ok((
+                line[..index].trim().to_owned(),
+                line[index + 1..].trim().to_owned(),
+            )),
+            None => Err(Error::custom(format!(
+                "expected `KEY = value`, found `{}`",
+                line
+            ))),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let lookup = |key: &str| find_item(&items, key);
+    T::deserialize(ConfigDeserializer { lookup: &lookup })
+}
+
+/// Writes a struct in the Orbiter configuration file format.
+///
+/// Fields set to `None` are omitted.
+pub fn to_string<T: Serialize>(value: &T) -> Result<String, Error> {
+    let mut result = String::new();
+    for (key, value) in serialize_items(value)? {
+        result.push_str(&key);
+        result.push_str(" = ");
+        result.push_str(&value);
+        result.push('\n');
+    }
+    Ok(result)
+}
+
+/// Error while reading or writing a configuration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Error(String);
+
+impl Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl de::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self::custom(msg)
+    }
+}
+
+impl ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self::custom(msg)
+    }
+}
+
+fn find_item(items: &[(String, String)], key: &str) -> Option<String> {
+    items
+        .iter()
+        .find(|(item, _)| item.eq_ignore_ascii_case(key))
+        .map(|(_, value)| value.clone())
+}
+
+fn read_item(file: FILEHANDLE, key: &str) -> Option<String> {
+    let key = CString::new(key).ok()?;
+    let mut buffer = vec![0; 1024];
+    let found = unsafe {
+        oapic_oapiReadItem_string(file, key.as_ptr(), buffer.as_mut_ptr(), buffer.len() as i32)
+    };
+    if found {
+        let value = unsafe { CStr::from_ptr(buffer.as_ptr()) };
+        Some(value.to_string_lossy().trim().to_owned())
+    } else {
+        None
+    }
+}
+
+fn serialize_items<T: Serialize>(value: &T) -> Result<Vec<(String, String)>, Error> {
+    let mut items = Vec::new();
+    value.serialize(ConfigSerializer { items: &mut items })?;
+    Ok(items)
+}
+
+/// Deserializes the top-level struct, one field per item.
+struct ConfigDeserializer<'a> {
+    lookup: &'a dyn Fn(&str) -> Option<String>,
+}
+
+impl<'de, 'a> de::Deserializer<'de> for ConfigDeserializer<'a> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Error> {
+        Err(Error::custom(
+            "configuration files can only be read into structs",
+        ))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_map(ItemAccess {
+            lookup: self.lookup,
+            fields: fields.iter(),
+            value: None,
+        })
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+struct ItemAccess<'a> {
+    lookup: &'a dyn Fn(&str) -> Option<String>,
+    fields: std::slice::Iter<'static, &'static str>,
+    value: Option<String>,
+}
+
+impl<'de, 'a> de::MapAccess<'de> for ItemAccess<'a> {
+    type Error = Error;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        for field in &mut self.fields {
+            if let Some(value) = (self.lookup)(field) {
+                self.value = Some(value);
+                return seed.deserialize(field.into_deserializer()).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let value = self.value.take().unwrap_or_default();
+        seed.deserialize(ValueDeserializer(&value))
+    }
+}
+
+/// Deserializes the value of a single item.
+struct ValueDeserializer<'a>(&'a str);
+
+impl<'a> ValueDeserializer<'a> {
+    fn parse<T: FromStr>(&self) -> Result<T, Error>
+    where
+        T::Err: Display,
+    {
+        self.0
+            .parse()
+            .map_err(|err| Error::custom(format!("invalid value `{}`: {}", self.0, err)))
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                visitor.$visit(self.parse()?)
+            }
+        )*
+    };
+}
+
+impl<'de, 'a> de::Deserializer<'de> for ValueDeserializer<'a> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_str(self.0)
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if self.0.eq_ignore_ascii_case("TRUE") {
+            visitor.visit_bool(true)
+        } else if self.0.eq_ignore_ascii_case("FALSE") {
+            visitor.visit_bool(false)
+        } else {
+            Err(Error::custom(format!(
+                "invalid value `{}`: expected TRUE or FALSE",
+                self.0
+            )))
+        }
+    }
+
+    deserialize_parsed! {
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if self.0.is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_seq(ValueSeqAccess(self.0.split_whitespace()))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_enum(self.0.into_deserializer())
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Error> {
+        Err(Error::custom("nested maps are not supported"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Error> {
+        Err(Error::custom("nested structs are not supported"))
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        i128 u128 str string bytes byte_buf identifier
+    }
+}
+
+struct ValueSeqAccess<'a>(std::str::SplitWhitespace<'a>);
+
+impl<'de, 'a> de::SeqAccess<'de> for ValueSeqAccess<'a> {
+    type Error = Error;
+
+    fn next_element_seed<T: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        self.0
+            .next()
+            .map(|value| seed.deserialize(ValueDeserializer(value)))
+            .transpose()
+    }
+}
+
+/// Serializes the top-level struct, one item per field.
+struct ConfigSerializer<'a> {
+    items: &'a mut Vec<(String, String)>,
+}
+
+impl<'a> ser::SerializeStruct for ConfigSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        if let Some(value) = value.serialize(ValueSerializer)? {
+            self.items.push((key.to_owned(), value));
+        }
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+macro_rules! serialize_unsupported {
+    ($($method:ident($($arg:ty),*) -> $ret:ty,)*) => {
+        $(
+            fn $method(self, $(_: $arg),*) -> Result<$ret, Error> {
+                Err(Error::custom("configuration files can only be written from structs"))
+            }
+        )*
+    };
+}
+
+impl<'a> ser::Serializer for ConfigSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Impossible<(), Error>;
+    type SerializeTuple = Impossible<(), Error>;
+    type SerializeTupleStruct = Impossible<(), Error>;
+    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeMap = Impossible<(), Error>;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Impossible<(), Error>;
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self, Error> {
+        Ok(self)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, _value: &T) -> Result<(), Error> {
+        Err(Error::custom(
+            "configuration files can only be written from structs",
+        ))
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<(), Error> {
+        Err(Error::custom(
+            "configuration files can only be written from structs",
+        ))
+    }
+
+    serialize_unsupported! {
+        serialize_bool(bool) -> (),
+        serialize_i8(i8) -> (),
+        serialize_i16(i16) -> (),
+        serialize_i32(i32) -> (),
+        serialize_i64(i64) -> (),
+        serialize_u8(u8) -> (),
+        serialize_u16(u16) -> (),
+        serialize_u32(u32) -> (),
+        serialize_u64(u64) -> (),
+        serialize_f32(f32) -> (),
+        serialize_f64(f64) -> (),
+        serialize_char(char) -> (),
+        serialize_str(&str) -> (),
+        serialize_bytes(&[u8]) -> (),
+        serialize_none() -> (),
+        serialize_unit() -> (),
+        serialize_unit_struct(&'static str) -> (),
+        serialize_unit_variant(&'static str, u32, &'static str) -> (),
+        serialize_seq(Option<usize>) -> Self::SerializeSeq,
+        serialize_tuple(usize) -> Self::SerializeTuple,
+        serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct,
+        serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant,
+        serialize_map(Option<usize>) -> Self::SerializeMap,
+        serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant,
+    }
+}
+
+/// Serializes the value of a single item.
+///
+/// Returns `None` when the item should be omitted.
+struct ValueSerializer;
+
+macro_rules! serialize_display {
+    ($($method:ident($ty:ty),)*) => {
+        $(
+            fn $method(self, value: $ty) -> Result<Option<String>, Error> {
+                Ok(Some(value.to_string()))
+            }
+        )*
+    };
+}
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Option<String>;
+    type Error = Error;
+    type SerializeSeq = ValueSeqSerializer;
+    type SerializeTuple = ValueSeqSerializer;
+    type SerializeTupleStruct = ValueSeqSerializer;
+    type SerializeTupleVariant = Impossible<Option<String>, Error>;
+    type SerializeMap = Impossible<Option<String>, Error>;
+    type SerializeStruct = Impossible<Option<String>, Error>;
+    type SerializeStructVariant = Impossible<Option<String>, Error>;
+
+    fn serialize_bool(self, value: bool) -> Result<Option<String>, Error> {
+        Ok(Some(if value { "TRUE" } else { "FALSE" }.to_owned()))
+    }
+
+    serialize_display! {
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+    }
+
+    fn serialize_bytes(self, _value: &[u8]) -> Result<Option<String>, Error> {
+        Err(Error::custom("byte arrays are not supported"))
+    }
+
+    fn serialize_none(self) -> Result<Option<String>, Error> {
+        Ok(None)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Option<String>, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Option<String>, Error> {
+        Ok(Some(String::new()))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Option<String>, Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Option<String>, Error> {
+        Ok(Some(variant.to_owned()))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Option<String>, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Option<String>, Error> {
+        Err(Error::custom("only unit enum variants are supported"))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<ValueSeqSerializer, Error> {
+        Ok(ValueSeqSerializer(Vec::new()))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<ValueSeqSerializer, Error> {
+        Ok(ValueSeqSerializer(Vec::new()))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<ValueSeqSerializer, Error> {
+        Ok(ValueSeqSerializer(Vec::new()))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(Error::custom("only unit enum variants are supported"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(Error::custom("nested maps are not supported"))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        Err(Error::custom("nested structs are not supported"))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(Error::custom("only unit enum variants are supported"))
+    }
+}
+
+struct ValueSeqSerializer(Vec<String>);
+
+impl ValueSeqSerializer {
+    fn push<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        match value.serialize(ValueSerializer)? {
+            Some(value) if !value.is_empty() && !value.contains(char::is_whitespace) => {
+                self.0.push(value);
+                Ok(())
+            }
+            _ => Err(Error::custom(
+                "sequence elements must be non-empty values without whitespace",
+            )),
+        }
+    }
+}
+
+impl ser::SerializeSeq for ValueSeqSerializer {
+    type Ok = Option<String>;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Option<String>, Error> {
+        Ok(Some(self.0.join(" ")))
+    }
+}
+
+impl ser::SerializeTuple for ValueSeqSerializer {
+    type Ok = Option<String>;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Option<String>, Error> {
+        Ok(Some(self.0.join(" ")))
+    }
+}
+
+impl ser::SerializeTupleStruct for ValueSeqSerializer {
+    type Ok = Option<String>;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Option<String>, Error> {
+        Ok(Some(self.0.join(" ")))
+    }
+}
+
+#[allow(clippy::upper_case_acronyms)]
+type FILEHANDLE = *mut c_void;
+
+// FileAccessMode
+const FILE_OUT: c_int = 1;
+const FILE_IN_ZEROONFAIL: c_int = 3;
+
+// PathRoot
+const PATH_ROOT_CONFIG: c_int = 1;
+
+#[cfg_attr(windows, link(name = "orbiter_c"))]
+extern "C" {
+    fn oapic_oapiOpenFile(fname: *const c_char, mode: c_int, root: c_int) -> FILEHANDLE;
+    fn oapic_oapiCloseFile(file: FILEHANDLE, mode: c_int);
+    fn oapic_oapiReadItem_string(
+        file: FILEHANDLE,
+        item: *const c_char,
+        string: *mut c_char,
+        n: c_int,
+    ) -> bool;
+    fn oapic_oapiWriteItem_string(file: FILEHANDLE, item: *const c_char, string: *const c_char);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Units {
+        Metric,
+        Imperial,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(default, rename_all = "PascalCase")]
+    struct Settings {
+        show_hud: bool,
+        max_range: f64,
+        retries: u32,
+        hotkey: char,
+        name: String,
+        units: Units,
+        offset: Vec<f64>,
+        target: Option<String>,
+    }
+
+    impl Default for Settings {
+        fn default() -> Self {
+            Self {
+                show_hud: true,
+                max_range: 50e3,
+                retries: 3,
+                hotkey: 'K',
+                name: "Default".to_owned(),
+                units: Units::Metric,
+                offset: vec![0.0, 1.0, 0.0],
+                target: None,
+            }
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let settings = Settings {
+            show_hud: false,
+            max_range: 1.5e-3,
+            retries: 0,
+            hotkey: 'Z',
+            name: "My vessel".to_owned(),
+            units: Units::Imperial,
+            offset: vec![-2.0, 0.25, 1e10],
+            target: Some("ISS".to_owned()),
+        };
+        let text = to_string(&settings).unwrap();
+        assert!(text.starts_with("ShowHud = FALSE\nMaxRange = 0.0015\n"));
+        assert!(text.contains("Units = Imperial\n"));
+        assert_eq!(from_str::<Settings>(&text).unwrap(), settings);
+
+        let defaults = Settings::default();
+        let text = to_string(&defaults).unwrap();
+        assert!(!text.contains("Target"));
+        assert_eq!(from_str::<Settings>(&text).unwrap(), defaults);
+    }
+
+    #[test]
+    fn missing_keys() {
+        let settings: Settings = from_str("; Only the range\n\nMaxRange = 20\n").unwrap();
+        assert_eq!(
+            settings,
+            Settings {
+                max_range: 20.0,
+                ..Settings::default()
+            }
+        );
+        assert_eq!(from_str::<Settings>("").unwrap(), Settings::default());
+
+        // Without defaults, only options can be missing
+        #[derive(Debug, Deserialize)]
+        struct Required {
+            _range: f64,
+        }
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Optional {
+            range: Option<f64>,
+        }
+        assert!(from_str::<Required>("").is_err());
+        assert_eq!(from_str::<Optional>("").unwrap(), Optional { range: None });
+    }
+
+    #[test]
+    fn case_insensitive_keys() {
+        let text = "SHOWHUD = false\nmaxrange = 10\nUnItS = Imperial\n";
+        let settings: Settings = from_str(text).unwrap();
+        assert!(!settings.show_hud);
+        assert_eq!(settings.max_range, 10.0);
+        assert_eq!(settings.units, Units::Imperial);
+    }
+
+    #[test]
+    fn invalid_content() {
+        assert!(from_str::<Settings>("MaxRange").is_err());
+        assert!(from_str::<Settings>("MaxRange = far").is_err());
+        assert!(from_str::<Settings>("ShowHud = 1").is_err());
+        assert!(from_str::<Settings>("Units = Nautical").is_err());
+    }
+}
@@ -0,0 +1,27 @@
+#include "orbitersdk.h"
+#include "types.h"
+
+extern "C"
+{
+    const ATMCONST* oapic_oapiGetPlanetAtmConstants(OBJHANDLE hPlanet) { return oapiGetPlanetAtmConstants(hPlanet); }
+    void oapic_oapiGetPlanetAtmParams(OBJHANDLE hPlanet, double alt, double lng, double lat, ATMPARAM* prm) {
+        oapiGetPlanetAtmParams(hPlanet, alt, lng, lat, prm);
+    }
+    void oapic_oapiGetAtm(OBJHANDLE hVessel, ATMPARAM* prm, OBJHANDLE* hAtmRef) { oapiGetAtm(hVessel, prm, hAtmRef); }
+    void oapic_oapiGetAirspeedVector(OBJHANDLE hVessel, int frame, oapic_VECTOR3* speedvec) {
+        VECTOR3 result;
+        oapiGetAirspeedVector(hVessel, (REFFRAME)frame, &result);
+        convert(result, speedvec);
+    }
+    void oapic_oapiGetGroundspeedVector(OBJHANDLE hVessel, int frame, oapic_VECTOR3* speedvec) {
+        VECTOR3 result;
+        oapiGetGroundspeedVector(hVessel, (REFFRAME)frame, &result);
+        convert(result, speedvec);
+    }
+    bool oapic_oapiGetWindVector(OBJHANDLE hPlanet, double lng, double lat, double alt, int frame, oapic_VECTOR3* wind) {
+        VECTOR3 result;
+        bool valid = oapiGetWindVector(hPlanet, lng, lat, alt, frame, &result);
+        convert(result, wind);
+        return valid;
+    }
+}
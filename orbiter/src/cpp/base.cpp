@@ -0,0 +1,16 @@
+#include "orbitersdk.h"
+
+extern "C"
+{
+    OBJHANDLE oapic_oapiGetBasePlanet(OBJHANDLE hBase) { return oapiGetBasePlanet(hBase); }
+    void oapic_oapiGetBaseEquPos(OBJHANDLE hBase, double* lng, double* lat, double* rad) { oapiGetBaseEquPos(hBase, lng, lat, rad); }
+    DWORD oapic_oapiGetBasePadCount(OBJHANDLE hBase) { return oapiGetBasePadCount(hBase); }
+    bool oapic_oapiGetBasePadEquPos(OBJHANDLE hBase, DWORD pad, double* lng, double* lat, double* rad) {
+        return oapiGetBasePadEquPos(hBase, pad, lng, lat, rad);
+    }
+    bool oapic_oapiGetBasePadStatus(OBJHANDLE hBase, DWORD pad, int* status) { return oapiGetBasePadStatus(hBase, pad, status); }
+    NAVHANDLE oapic_oapiGetBasePadNav(OBJHANDLE hBase, DWORD pad) { return oapiGetBasePadNav(hBase, pad); }
+    DWORD oapic_oapiGetBaseCount(OBJHANDLE hPlanet) { return oapiGetBaseCount(hPlanet); }
+    OBJHANDLE oapic_oapiGetBaseByIndex(OBJHANDLE hPlanet, DWORD index) { return oapiGetBaseByIndex(hPlanet, index); }
+    OBJHANDLE oapic_oapiGetBaseByName(OBJHANDLE hPlanet, char* name) { return oapiGetBaseByName(hPlanet, name); }
+}
@@ -0,0 +1,34 @@
+#include "orbitersdk.h"
+#include "types.h"
+
+extern "C"
+{
+    double oapic_oapiGetPlanetPeriod(OBJHANDLE hPlanet) { return oapiGetPlanetPeriod(hPlanet); }
+    double oapic_oapiGetPlanetObliquity(OBJHANDLE hPlanet) { return oapiGetPlanetObliquity(hPlanet); }
+    OBJHANDLE oapic_oapiGetGbodyParent(OBJHANDLE hBody) { return oapiGetGbodyParent(hBody); }
+    OBJHANDLE oapic_oapiGetGbodyChild(OBJHANDLE hBody, DWORD i) { return oapiGetGbodyChild(hBody, i); }
+    bool oapic_oapiPlanetHasAtmosphere(OBJHANDLE hPlanet) { return oapiPlanetHasAtmosphere(hPlanet); }
+    DWORD oapic_oapiGetPlanetJCoeffCount(OBJHANDLE hPlanet) { return oapiGetPlanetJCoeffCount(hPlanet); }
+    double oapic_oapiGetPlanetJCoeff(OBJHANDLE hPlanet, DWORD n) { return oapiGetPlanetJCoeff(hPlanet, n); }
+    double oapic_oapiGetPlanetCurrentRotation(OBJHANDLE hPlanet) { return oapiGetPlanetCurrentRotation(hPlanet); }
+    void oapic_oapiGlobalToEqu(OBJHANDLE hObj, const oapic_VECTOR3* glob, double* lng, double* lat, double* rad) {
+        oapiGlobalToEqu(hObj, convert(glob), lng, lat, rad);
+    }
+    void oapic_oapiEquToGlobal(OBJHANDLE hObj, double lng, double lat, double rad, oapic_VECTOR3* glob) {
+        VECTOR3 result;
+        oapiEquToGlobal(hObj, lng, lat, rad, &result);
+        convert(result, glob);
+    }
+    void oapic_oapiGlobalToLocal(OBJHANDLE hObj, const oapic_VECTOR3* glob, oapic_VECTOR3* loc) {
+        VECTOR3 global = convert(glob);
+        VECTOR3 result;
+        oapiGlobalToLocal(hObj, &global, &result);
+        convert(result, loc);
+    }
+    void oapic_oapiLocalToGlobal(OBJHANDLE hObj, const oapic_VECTOR3* loc, oapic_VECTOR3* glob) {
+        VECTOR3 local = convert(loc);
+        VECTOR3 result;
+        oapiLocalToGlobal(hObj, &local, &result);
+        convert(result, glob);
+    }
+}
@@ -0,0 +1,18 @@
+#include "orbitersdk.h"
+#include <cstring>
+
+extern "C"
+{
+    FILEHANDLE oapic_oapiOpenFile(char* fname, int mode, int root) { return oapiOpenFile(fname, (FileAccessMode)mode, (PathRoot)root); }
+    void oapic_oapiCloseFile(FILEHANDLE f, int mode) { oapiCloseFile(f, (FileAccessMode)mode); }
+    bool oapic_oapiReadItem_string(FILEHANDLE f, char* item, char* string, int n) {
+        char buffer[1024];
+        if (!oapiReadItem_string(f, item, buffer)) {
+            return false;
+        }
+        strncpy(string, buffer, n - 1);
+        string[n - 1] = '\0';
+        return true;
+    }
+    void oapic_oapiWriteItem_string(FILEHANDLE f, char* item, char* string) { oapiWriteItem_string(f, item, string); }
+}
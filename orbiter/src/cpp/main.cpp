@@ -11,4 +11,8 @@ extern "C"
     int oapic_oapiGetModuleVersion() { return oapiGetModuleVersion(); }
     HINSTANCE oapic_oapiGetOrbiterInstance() { return oapiGetOrbiterInstance(); }
     char* oapic_oapiDebugString() { return oapiDebugString(); }
+    void oapic_oapiWriteLog(char* line) { oapiWriteLog(line); }
+    double oapic_oapiGetSimMJD() { return oapiGetSimMJD(); }
+    double oapic_oapiGetTimeAcceleration() { return oapiGetTimeAcceleration(); }
+    void oapic_oapiSetTimeAcceleration(double warp) { oapiSetTimeAcceleration(warp); }
 }
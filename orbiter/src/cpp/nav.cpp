@@ -0,0 +1,64 @@
+#include "orbitersdk.h"
+#include "types.h"
+
+extern "C"
+{
+    // Flattened version of NAVDATA
+    typedef struct oapic_NAVDATA
+    {
+        DWORD type;
+        OBJHANDLE hObj;
+        double lng;
+        double lat;
+        double appdir;
+        DWORD index;
+    } oapic_NAVDATA;
+}
+
+extern "C"
+{
+    DWORD oapic_oapiGetNavType(NAVHANDLE hNav) { return oapiGetNavType(hNav); }
+    float oapic_oapiGetNavFreq(NAVHANDLE hNav) { return oapiGetNavFreq(hNav); }
+    DWORD oapic_oapiGetNavChannel(NAVHANDLE hNav) { return oapiGetNavChannel(hNav); }
+    float oapic_oapiGetNavRange(NAVHANDLE hNav) { return oapiGetNavRange(hNav); }
+    void oapic_oapiGetNavPos(NAVHANDLE hNav, oapic_VECTOR3* pos) {
+        VECTOR3 result;
+        oapiGetNavPos(hNav, &result);
+        convert(result, pos);
+    }
+    double oapic_oapiGetNavSignal(NAVHANDLE hNav, const oapic_VECTOR3* gpos) { return oapiGetNavSignal(hNav, convert(gpos)); }
+    int oapic_oapiGetNavDescr(NAVHANDLE hNav, char* descr, int maxlen) { return oapiGetNavDescr(hNav, descr, maxlen); }
+    bool oapic_oapiGetNavData(NAVHANDLE hNav, oapic_NAVDATA* data) {
+        NAVDATA result;
+        if (!oapiGetNavData(hNav, &result)) return false;
+
+        data->type = result.type;
+        switch (result.type) {
+        case TRANSMITTER_VOR:
+            data->hObj = result.vor.hPlanet;
+            data->lng = result.vor.lng;
+            data->lat = result.vor.lat;
+            break;
+        case TRANSMITTER_VTOL:
+            data->hObj = result.vtol.hBase;
+            data->index = result.vtol.npad;
+            break;
+        case TRANSMITTER_ILS:
+            data->hObj = result.ils.hBase;
+            data->appdir = result.ils.appdir;
+            break;
+        case TRANSMITTER_IDS: {
+            data->hObj = result.ids.hVessel;
+            VESSEL* vessel = oapiGetVesselInterface(result.ids.hVessel);
+            for (DWORD i = 0; i < vessel->DockCount(); i++) {
+                if (vessel->GetDockHandle(i) == result.ids.hDock) data->index = i;
+            }
+            break;
+        }
+        case TRANSMITTER_XPDR:
+            data->hObj = result.xpdr.hVessel;
+            break;
+        }
+        return true;
+    }
+}
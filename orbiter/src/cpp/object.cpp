@@ -30,4 +30,47 @@ extern "C"
         oapiGetRelativeVel(hObj, hRef, &result);
         convert(result, vel);
     };
+    void oapic_oapiGetRotationMatrix(OBJHANDLE hObj, oapic_MATRIX3* mat) {
+        MATRIX3 result;
+        oapiGetRotationMatrix(hObj, &result);
+        convert(result, mat);
+    }
+
+    int oapic_world_snapshot(
+        int capacity,
+        OBJHANDLE* handles,
+        int* types,
+        char* names,
+        int name_len,
+        double* masses,
+        double* sizes,
+        oapic_VECTOR3* positions,
+        oapic_VECTOR3* velocities,
+        oapic_MATRIX3* rotations)
+    {
+        int count = oapiGetObjectCount();
+        if (count > capacity) {
+            return count;
+        }
+
+        for (int i = 0; i < count; i++) {
+            OBJHANDLE hObj = oapiGetObjectByIndex(i);
+            handles[i] = hObj;
+            types[i] = oapiGetObjectType(hObj);
+            oapiGetObjectName(hObj, names + i * name_len, name_len);
+            masses[i] = oapiGetMass(hObj);
+            sizes[i] = oapiGetSize(hObj);
+
+            VECTOR3 vec;
+            oapiGetGlobalPos(hObj, &vec);
+            convert(vec, positions + i);
+            oapiGetGlobalVel(hObj, &vec);
+            convert(vec, velocities + i);
+
+            MATRIX3 mat;
+            oapiGetRotationMatrix(hObj, &mat);
+            convert(mat, rotations + i);
+        }
+        return count;
+    }
 }
@@ -0,0 +1,19 @@
+#include "orbitersdk.h"
+
+extern "C"
+{
+    double oapic_oapiSurfaceElevation(OBJHANDLE hPlanet, double lng, double lat) { return oapiSurfaceElevation(hPlanet, lng, lat); }
+}
+
+extern "C"
+{
+    void oapic_surface_elevation_grid(OBJHANDLE hPlanet, double lat, double lng, double dlat, double dlng, DWORD rows, DWORD cols, double* elev) {
+        for (DWORD i = 0; i < rows; i++) {
+            for (DWORD j = 0; j < cols; j++) {
+                double l = lng + j * dlng;
+                if (l > PI) l -= PI2;
+                elev[i * cols + j] = oapiSurfaceElevation(hPlanet, l, lat + i * dlat);
+            }
+        }
+    }
+}
@@ -10,6 +10,13 @@ extern "C"
         double y;
         double z;
     } oapic_VECTOR3;
+
+    typedef struct oapic_MATRIX3
+    {
+        double m11, m12, m13;
+        double m21, m22, m23;
+        double m31, m32, m33;
+    } oapic_MATRIX3;
 }
 
 inline void convert(VECTOR3& from, oapic_VECTOR3* to)
@@ -18,3 +25,21 @@ inline void convert(VECTOR3& from, oapic_VECTOR3* to)
     to->y = from.y;
     to->z = from.z;
 }
+
+inline VECTOR3 convert(const oapic_VECTOR3* from)
+{
+    return _V(from->x, from->y, from->z);
+}
+
+inline void convert(MATRIX3& from, oapic_MATRIX3* to)
+{
+    to->m11 = from.m11;
+    to->m12 = from.m12;
+    to->m13 = from.m13;
+    to->m21 = from.m21;
+    to->m22 = from.m22;
+    to->m23 = from.m23;
+    to->m31 = from.m31;
+    to->m32 = from.m32;
+    to->m33 = from.m33;
+}
@@ -1,12 +1,17 @@
 #include "orbitersdk.h"
+#include "types.h"
 
 extern "C"
 {
     VESSEL* oapic_oapiGetVesselInterface(OBJHANDLE hVessel) { return oapiGetVesselInterface(hVessel); }
+    VESSEL* oapic_VESSEL_Create(OBJHANDLE hVessel, int fmodel) { return new VESSEL4(hVessel, fmodel); }
+    void oapic_VESSEL_Destroy(VESSEL* hVessel) { delete hVessel; }
+    bool oapic_oapiIsVessel(OBJHANDLE hVessel) { return oapiIsVessel(hVessel); }
     OBJHANDLE oapic_oapiGetFocusObject() { return oapiGetFocusObject(); }
     OBJHANDLE oapic_oapiSetFocusObject(OBJHANDLE hVessel) { return oapiSetFocusObject(hVessel); }
     double oapic_oapiGetEmptyMass(OBJHANDLE hVessel) { return oapiGetEmptyMass(hVessel); }
     void oapic_oapiSetEmptyMass(OBJHANDLE hVessel, double mass) { oapiSetEmptyMass(hVessel, mass); }
+    bool oapic_oapiGetAltitude(OBJHANDLE hVessel, int mode, double* alt) { return oapiGetAltitude(hVessel, (AltitudeMode)mode, alt) != FALSE; }
     double oapic_oapiGetFuelMass(OBJHANDLE hVessel) { return oapiGetFuelMass(hVessel); }
     double oapic_oapiGetMaxFuelMass(OBJHANDLE hVessel) { return oapiGetMaxFuelMass(hVessel); }
 }
@@ -14,4 +19,54 @@ extern "C"
 extern "C"
 {
     OBJHANDLE oapic_VESSEL_GetHandle(VESSEL* hVessel) { return hVessel->GetHandle(); }
+    const char* oapic_VESSEL_GetClassName(VESSEL* hVessel) { return hVessel->GetClassName(); }
+    OBJHANDLE oapic_VESSEL_GetGravityRef(VESSEL* hVessel) { return hVessel->GetGravityRef(); }
+    void oapic_VESSEL_Local2Global(VESSEL* hVessel, const oapic_VECTOR3* local, oapic_VECTOR3* global) {
+        VECTOR3 result;
+        hVessel->Local2Global(convert(local), result);
+        convert(result, global);
+    }
+    void oapic_VESSEL_Global2Local(VESSEL* hVessel, const oapic_VECTOR3* global, oapic_VECTOR3* local) {
+        VECTOR3 result;
+        hVessel->Global2Local(convert(global), result);
+        convert(result, local);
+    }
+    void oapic_VESSEL_GlobalRot(VESSEL* hVessel, const oapic_VECTOR3* rloc, oapic_VECTOR3* rglob) {
+        VECTOR3 result;
+        hVessel->GlobalRot(convert(rloc), result);
+        convert(result, rglob);
+    }
+    void oapic_VESSEL_GetAngularVel(VESSEL* hVessel, oapic_VECTOR3* avel) {
+        VECTOR3 result;
+        hVessel->GetAngularVel(result);
+        convert(result, avel);
+    }
+    void oapic_VESSEL_InitNavRadios(VESSEL* hVessel, DWORD nnav) { hVessel->InitNavRadios(nnav); }
+    DWORD oapic_VESSEL_GetNavCount(VESSEL* hVessel) { return hVessel->GetNavCount(); }
+    bool oapic_VESSEL_SetNavRecv(VESSEL* hVessel, DWORD n, DWORD ch) { return hVessel->SetNavRecv(n, ch); }
+    DWORD oapic_VESSEL_GetNavRecv(VESSEL* hVessel, DWORD n) { return hVessel->GetNavRecv(n); }
+    float oapic_VESSEL_GetNavRecvFreq(VESSEL* hVessel, DWORD n) { return hVessel->GetNavRecvFreq(n); }
+    NAVHANDLE oapic_VESSEL_GetNavSource(VESSEL* hVessel, DWORD n) { return hVessel->GetNavSource(n); }
+    bool oapic_VESSEL_EnableTransponder(VESSEL* hVessel, bool enable) { return hVessel->EnableTransponder(enable); }
+    NAVHANDLE oapic_VESSEL_GetTransponder(VESSEL* hVessel) { return hVessel->GetTransponder(); }
+    bool oapic_VESSEL_SetTransponderChannel(VESSEL* hVessel, DWORD ch) { return hVessel->SetTransponderChannel(ch); }
+    UINT oapic_VESSEL_DockCount(VESSEL* hVessel) { return hVessel->DockCount(); }
+    DOCKHANDLE oapic_VESSEL_GetDockHandle(VESSEL* hVessel, UINT n) { return hVessel->GetDockHandle(n); }
+    bool oapic_VESSEL_EnableIDS(VESSEL* hVessel, DOCKHANDLE hDock, bool enable) { return hVessel->EnableIDS(hDock, enable); }
+    NAVHANDLE oapic_VESSEL_GetIDS(VESSEL* hVessel, DOCKHANDLE hDock) { return hVessel->GetIDS(hDock); }
+    void oapic_VESSEL_SetIDSChannel(VESSEL* hVessel, DOCKHANDLE hDock, DWORD ch) { hVessel->SetIDSChannel(hDock, ch); }
+    double oapic_VESSEL_GetThrusterGroupLevel(VESSEL* hVessel, int thgt) { return hVessel->GetThrusterGroupLevel((THGROUP_TYPE)thgt); }
+    void oapic_VESSEL_SetThrusterGroupLevel(VESSEL* hVessel, int thgt, double level) { hVessel->SetThrusterGroupLevel((THGROUP_TYPE)thgt, level); }
+    DWORD oapic_VESSEL_GetGroupThrusterCount(VESSEL* hVessel, int thgt) { return hVessel->GetGroupThrusterCount((THGROUP_TYPE)thgt); }
+    THRUSTER_HANDLE oapic_VESSEL_GetGroupThruster(VESSEL* hVessel, int thgt, DWORD n) { return hVessel->GetGroupThruster((THGROUP_TYPE)thgt, n); }
+    double oapic_VESSEL_GetThrusterMax0(VESSEL* hVessel, THRUSTER_HANDLE th) { return hVessel->GetThrusterMax0(th); }
+    double oapic_VESSEL_GetThrusterIsp0(VESSEL* hVessel, THRUSTER_HANDLE th) { return hVessel->GetThrusterIsp0(th); }
+    void oapic_VESSEL_GetThrusterDir(VESSEL* hVessel, THRUSTER_HANDLE th, oapic_VECTOR3* dir) {
+        VECTOR3 result;
+        hVessel->GetThrusterDir(th, result);
+        convert(result, dir);
+    }
+    int oapic_VESSEL_GetAttitudeMode(VESSEL* hVessel) { return hVessel->GetAttitudeMode(); }
+    bool oapic_VESSEL_SetAttitudeMode(VESSEL* hVessel, int mode) { return hVessel->SetAttitudeMode(mode); }
+    void oapic_VESSEL_SetAttitudeRotLevel(VESSEL* hVessel, const oapic_VECTOR3* th) { hVessel->SetAttitudeRotLevel(convert(th)); }
 }
@@ -0,0 +1,398 @@
+use crate::{Key, KeyStates, Module, ModuleCallbacks, MouseEvent, RenderMode, Vessel};
+use std::collections::VecDeque;
+use std::sync::mpsc::{SyncSender, TrySendError};
+
+/// Event generated by Orbiter for a module.
+///
+/// Each variant corresponds to one of the methods of [`ModuleCallbacks`].
+///
+/// [`ModuleCallbacks`]: ./trait.ModuleCallbacks.html
+pub enum ModuleEvent<'a> {
+    /// A simulation session started.
+    SimulationStart { render_mode: RenderMode },
+    /// The simulation session ended.
+    SimulationEnd,
+    /// A time step is about to be computed.
+    PreStep { simt: f64, simdt: f64, mjd: f64 },
+    /// A time step was computed.
+    PostStep { simt: f64, simdt: f64, mjd: f64 },
+    /// The simulation time jumped discontinuously.
+    TimeJump { simt: f64, simdt: f64, mjd: f64 },
+    /// The input focus moved to another vessel.
+    FocusChanged {
+        new_focus: Vessel,
+        old_focus: Option<Vessel>,
+    },
+    /// The time acceleration factor changed.
+    TimeAccChanged { new_warp: f64, old_warp: f64 },
+    /// A vessel was created.
+    NewVessel(Vessel),
+    /// A vessel is about to be deleted.
+    DeleteVessel(Vessel),
+    /// A vessel was moved discontinuously.
+    VesselJump(Vessel),
+    /// The simulation was paused (`true`) or resumed (`false`).
+    Pause(bool),
+    /// A mouse event occured.
+    Mouse(MouseEvent),
+    /// Immediate keyboard state, sent once per frame.
+    KeyboardImmediate {
+        key_states: KeyStates<'a>,
+        sim_running: bool,
+    },
+    /// A key was pressed.
+    KeyboardBuffered {
+        key: Key,
+        key_states: KeyStates<'a>,
+        sim_running: bool,
+    },
+}
+
+impl<'a> ModuleEvent<'a> {
+    /// Converts the event into one that can be stored past the callback.
+    ///
+    /// Keyboard states are copied with [`KeyStates::snapshot`].
+    ///
+    /// [`KeyStates::snapshot`]: ./struct.KeyStates.html#method.snapshot
+    pub fn into_owned(self) -> ModuleEvent<'static> {
+        match self {
+            Self::SimulationStart { render_mode } => ModuleEvent::SimulationStart { render_mode },
+            Self::SimulationEnd => ModuleEvent::SimulationEnd,
+            Self::PreStep { simt, simdt, mjd } => ModuleEvent::PreStep { simt, simdt, mjd },
+            Self::PostStep { simt, simdt, mjd } => ModuleEvent::PostStep { simt, simdt, mjd },
+            Self::TimeJump { simt, simdt, mjd } => ModuleEvent::TimeJump { simt, simdt, mjd },
+            Self::FocusChanged {
+                new_focus,
+                old_focus,
+            } => ModuleEvent::FocusChanged {
+                new_focus,
+                old_focus,
+            },
+            Self::TimeAccChanged { new_warp, old_warp } => {
+                ModuleEvent::TimeAccChanged { new_warp, old_warp }
+            }
+            Self::NewVessel(vessel) => ModuleEvent::NewVessel(vessel),
+            Self::DeleteVessel(vessel) => ModuleEvent::DeleteVessel(vessel),
+            Self::VesselJump(vessel) => ModuleEvent::VesselJump(vessel),
+            Self::Pause(pause) => ModuleEvent::Pause(pause),
+            Self::Mouse(event) => ModuleEvent::Mouse(event),
+            Self::KeyboardImmediate {
+                key_states,
+                sim_running,
+            } => ModuleEvent::KeyboardImmediate {
+                key_states: key_states.snapshot(),
+                sim_running,
+            },
+            Self::KeyboardBuffered {
+                key,
+                key_states,
+                sim_running,
+            } => ModuleEvent::KeyboardBuffered {
+                key,
+                key_states: key_states.snapshot(),
+                sim_running,
+            },
+        }
+    }
+}
+
+/// Response of an event handler.
+///
+/// Only meaningful for mouse and keyboard events, where it tells Orbiter
+/// whether the input was consumed by the module. It is ignored for all other
+/// events.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Response {
+    /// Orbiter should continue processing the input.
+    #[default]
+    Ignored,
+    /// Orbiter should skip default processing of the input.
+    Consumed,
+}
+
+impl From<bool> for Response {
+    fn from(consumed: bool) -> Self {
+        if consumed {
+            Self::Consumed
+        } else {
+            Self::Ignored
+        }
+    }
+}
+
+impl From<Response> for bool {
+    fn from(response: Response) -> Self {
+        response == Response::Consumed
+    }
+}
+
+/// Module that forwards every callback to a single event handler.
+///
+/// Created by [`InstanceHandle::register_event_handler`].
+///
+/// [`InstanceHandle::register_event_handler`]: ./struct.InstanceHandle.html#method.register_event_handler
+pub struct EventHandler<F>(F);
+
+impl<F: FnMut(&mut Module, ModuleEvent) -> Response> EventHandler<F> {
+    pub(crate) fn new(handler: F) -> Self {
+        Self(handler)
+    }
+
+    fn handle(&mut self, module: &mut Module, event: ModuleEvent) -> bool {
+        (self.0)(module, event).into()
+    }
+}
+
+impl<F: FnMut(&mut Module, ModuleEvent) -> Response> ModuleCallbacks for EventHandler<F> {
+    fn on_simulation_start(&mut self, module: &mut Module, render_mode: RenderMode) {
+        self.handle(module, ModuleEvent::SimulationStart { render_mode });
+    }
+
+    fn on_simulation_end(&mut self, module: &mut Module) {
+        self.handle(module, ModuleEvent::SimulationEnd);
+    }
+
+    fn on_pre_step(&mut self, module: &mut Module, simt: f64, simdt: f64, mjd: f64) {
+        self.handle(module, ModuleEvent::PreStep { simt, simdt, mjd });
+    }
+
+    fn on_post_step(&mut self, module: &mut Module, simt: f64, simdt: f64, mjd: f64) {
+        self.handle(module, ModuleEvent::PostStep { simt, simdt, mjd });
+    }
+
+    fn on_time_jump(&mut self, module: &mut Module, simt: f64, simdt: f64, mjd: f64) {
+        self.handle(module, ModuleEvent::TimeJump { simt, simdt, mjd });
+    }
+
+    fn on_focus_changed(
+        &mut self,
+        module: &mut Module,
+        new_focus: Vessel,
+        old_focus: Option<Vessel>,
+    ) {
+        self.handle(
+            module,
+            ModuleEvent::FocusChanged {
+                new_focus,
+                old_focus,
+            },
+        );
+    }
+
+    fn on_time_acc_changed(&mut self, module: &mut Module, new_warp: f64, old_warp: f64) {
+        self.handle(module, ModuleEvent::TimeAccChanged { new_warp, old_warp });
+    }
+
+    fn on_new_vessel(&mut self, module: &mut Module, vessel: Vessel) {
+        self.handle(module, ModuleEvent::NewVessel(vessel));
+    }
+
+    fn on_delete_vessel(&mut self, module: &mut Module, vessel: Vessel) {
+        self.handle(module, ModuleEvent::DeleteVessel(vessel));
+    }
+
+    fn on_vessel_jump(&mut self, module: &mut Module, vessel: Vessel) {
+        self.handle(module, ModuleEvent::VesselJump(vessel));
+    }
+
+    fn on_pause(&mut self, module: &mut Module, pause: bool) {
+        self.handle(module, ModuleEvent::Pause(pause));
+    }
+
+    fn on_process_mouse(&mut self, module: &mut Module, event: MouseEvent) -> bool {
+        self.handle(module, ModuleEvent::Mouse(event))
+    }
+
+    fn on_process_keyboard_immediate(
+        &mut self,
+        module: &mut Module,
+        key_states: &mut KeyStates,
+        sim_running: bool,
+    ) -> bool {
+        self.handle(
+            module,
+            ModuleEvent::KeyboardImmediate {
+                key_states: key_states.reborrow(),
+                sim_running,
+            },
+        )
+    }
+
+    fn on_process_keyboard_buffered(
+        &mut self,
+        module: &mut Module,
+        key: Key,
+        key_states: &mut KeyStates,
+        sim_running: bool,
+    ) -> bool {
+        self.handle(
+            module,
+            ModuleEvent::KeyboardBuffered {
+                key,
+                key_states: key_states.reborrow(),
+                sim_running,
+            },
+        )
+    }
+}
+
+impl ModuleEvent<'_> {
+    /// Checks if the event is sent every frame, so that missing one is
+    /// harmless.
+    fn is_periodic(&self) -> bool {
+        matches!(
+            self,
+            Self::PreStep { .. } | Self::PostStep { .. } | Self::KeyboardImmediate { .. }
+        )
+    }
+}
+
+pub(crate) fn channel_handler(
+    sender: SyncSender<ModuleEvent<'static>>,
+    capacity: usize,
+) -> impl FnMut(&mut Module, ModuleEvent) -> Response {
+    let mut queue = ChannelQueue::new(sender, capacity);
+    move |_module, event| {
+        if let Some(dropped) = queue.send(event.into_owned()) {
+            crate::write_log(&format!("Event channel full: dropped {} events", dropped));
+        }
+        Response::Ignored
+    }
+}
+
+/// Sends the events of a channel module, keeping the events which are not
+/// periodic aside while the channel is full.
+struct ChannelQueue {
+    sender: SyncSender<ModuleEvent<'static>>,
+    /// Events waiting for room in the channel, in order
+    pending: VecDeque<ModuleEvent<'static>>,
+    capacity: usize,
+    /// Number of pending events dropped since the channel was last full
+    dropped: usize,
+}
+
+impl ChannelQueue {
+    fn new(sender: SyncSender<ModuleEvent<'static>>, capacity: usize) -> Self {
+        Self {
+            sender,
+            pending: VecDeque::with_capacity(capacity),
+            capacity,
+            dropped: 0,
+        }
+    }
+
+    /// Sends an event, or keeps it aside if the channel is full.
+    ///
+    /// Returns the number of events dropped because too many were kept aside,
+    /// once all the remaining ones were sent.
+    fn send(&mut self, event: ModuleEvent<'static>) -> Option<usize> {
+        if !event.is_periodic() {
+            if self.pending.len() == self.capacity {
+                self.pending.pop_front();
+                self.dropped += 1;
+            }
+            self.pending.push_back(event);
+        } else if self.pending.is_empty() {
+            // Dropped if the channel is full
+            let _ = self.sender.try_send(event);
+        }
+        while let Some(event) = self.pending.pop_front() {
+            match self.sender.try_send(event) {
+                Ok(()) => {}
+                Err(TrySendError::Full(event)) => {
+                    self.pending.push_front(event);
+                    return None;
+                }
+                // The receiver was dropped, events are discarded
+                Err(TrySendError::Disconnected(_)) => {
+                    self.pending.clear();
+                    self.dropped = 0;
+                    return None;
+                }
+            }
+        }
+        match std::mem::take(&mut self.dropped) {
+            0 => None,
+            dropped => Some(dropped),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::{sync_channel, Receiver};
+
+    fn pauses(receiver: &Receiver<ModuleEvent<'static>>) -> Vec<bool> {
+        receiver
+            .try_iter()
+            .filter_map(|event| match event {
+                ModuleEvent::Pause(pause) => Some(pause),
+                ModuleEvent::PreStep { .. } => None,
+                _ => panic!("unexpected event"),
+            })
+            .collect()
+    }
+
+    fn pre_step(simt: f64) -> ModuleEvent<'static> {
+        ModuleEvent::PreStep {
+            simt,
+            simdt: 0.1,
+            mjd: 51544.5,
+        }
+    }
+
+    #[test]
+    fn bounds_events_of_unpolled_receiver() {
+        let (sender, receiver) = sync_channel(2);
+        let mut queue = ChannelQueue::new(sender, 2);
+        for i in 0..1000 {
+            assert_eq!(queue.send(ModuleEvent::Pause(i % 2 == 0)), None);
+            assert_eq!(queue.send(pre_step(i as f64)), None);
+        }
+        assert_eq!(queue.pending.len(), 2);
+        assert_eq!(queue.dropped, 997);
+
+        // The channel keeps the first events and the oldest pending ones are
+        // dropped
+        assert_eq!(pauses(&receiver), vec![true]);
+        assert_eq!(queue.send(pre_step(1000.0)), Some(997));
+        assert_eq!(pauses(&receiver), vec![true, false]);
+        assert!(queue.pending.is_empty());
+        assert_eq!(queue.send(ModuleEvent::Pause(true)), None);
+    }
+
+    #[test]
+    fn keeps_order_while_full() {
+        let (sender, receiver) = sync_channel(2);
+        let mut queue = ChannelQueue::new(sender, 2);
+        queue.send(ModuleEvent::Pause(true));
+        queue.send(ModuleEvent::Pause(false));
+        queue.send(ModuleEvent::Pause(true));
+        queue.send(pre_step(0.0));
+        assert_eq!(queue.pending.len(), 1);
+
+        assert_eq!(pauses(&receiver), vec![true, false]);
+        // The periodic event waits behind the pending one, so it is dropped
+        assert_eq!(queue.send(pre_step(1.0)), None);
+        assert_eq!(pauses(&receiver), vec![true]);
+        assert_eq!(queue.send(pre_step(2.0)), None);
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(ModuleEvent::PreStep { simt, .. }) if simt == 2.0
+        ));
+    }
+
+    #[test]
+    fn discards_events_of_dropped_receiver() {
+        let (sender, receiver) = sync_channel(2);
+        let mut queue = ChannelQueue::new(sender, 2);
+        drop(receiver);
+        for i in 0..1000 {
+            assert_eq!(queue.send(ModuleEvent::Pause(i % 2 == 0)), None);
+            assert_eq!(queue.send(pre_step(i as f64)), None);
+        }
+        assert!(queue.pending.is_empty());
+        assert_eq!(queue.dropped, 0);
+    }
+}
@@ -0,0 +1,605 @@
+use crate::kepler::KeplerOrbit;
+use crate::maneuver::Burn;
+use crate::orbit::StateVector;
+use crate::time::SECONDS_PER_DAY;
+use crate::{
+    pseudovector_to_right_handed, set_time_acceleration, time_acceleration, to_left_handed,
+    to_right_handed, AttitudeMode, Object, ObjectTrait, ThrusterGroup, Vector3, Vessel,
+    VesselTrait, WeakVessel,
+};
+use nalgebra::Rotation3;
+
+/// Real time (in s) kept between dropping the time acceleration and the
+/// start of the alignment
+const WARP_MARGIN: f64 = 10.0;
+
+/// Time (in s) over which the engines throttle down at the end of the burn
+const THROTTLE_TIME: f64 = 2.0;
+
+/// Minimum thrust level while throttling down
+const MIN_LEVEL: f64 = 0.05;
+
+/// Maximum angle (in rad) between the thrust and the burn direction for the
+/// engines to fire
+const MAX_POINTING_ERROR: f64 = 5.0 * std::f64::consts::PI / 180.0;
+
+/// Returns the time (in s) to change the velocity of a vessel by `delta_v`
+/// (in m/s) with a thruster group at full level, from the rocket equation.
+///
+/// Returns `None` if the group has no thrust. A velocity change that is not
+/// positive takes no time.
+pub fn burn_duration(vessel: &dyn VesselTrait, group: ThrusterGroup, delta_v: f64) -> Option<f64> {
+    let isp = vessel.thruster_group_isp(group)?;
+    let thrust = vessel.thruster_group_max_thrust(group).norm();
+    rocket_burn_time(vessel.mass(), thrust, isp, delta_v)
+}
+
+/// Returns the time (in s) for a mass (in kg) to change its velocity by
+/// `delta_v` (in m/s) with a thrust (in N) and a specific impulse (in m/s).
+fn rocket_burn_time(mass: f64, thrust: f64, isp: f64, delta_v: f64) -> Option<f64> {
+    if thrust <= 0.0 || isp <= 0.0 {
+        return None;
+    }
+    let burned = mass * (1.0 - (-delta_v.max(0.0) / isp).exp());
+    Some(burned * isp / thrust)
+}
+
+/// Returns `true` if the time acceleration must drop to 1 for the alignment
+/// to start on time, `time_to_ignition` seconds before the ignition.
+fn drops_warp(warp: f64, time_to_ignition: f64, alignment_time: f64) -> bool {
+    warp > 1.0 && time_to_ignition < alignment_time + WARP_MARGIN * warp
+}
+
+/// Returns the phase before the ignition, `time_to_ignition` seconds before
+/// it, or [`BurnError::Missed`] if the ignition is late by more than
+/// `max_ignition_delay` seconds.
+///
+/// [`BurnError::Missed`]: ./enum.BurnError.html#variant.Missed
+fn countdown(
+    time_to_ignition: f64,
+    alignment_time: f64,
+    max_ignition_delay: f64,
+) -> Result<BurnStatus, BurnError> {
+    if time_to_ignition < -max_ignition_delay {
+        Err(BurnError::Missed)
+    } else if time_to_ignition > alignment_time {
+        Ok(BurnStatus::Coasting)
+    } else {
+        Ok(BurnStatus::Aligning)
+    }
+}
+
+/// Returns the angle (in rad) from the direction of the thrust to the target
+/// direction, and the unit axis of the rotation between them.
+///
+/// The axis is zero when both directions are the same.
+fn pointing_error(thrust: &Vector3, target: &Vector3) -> (f64, Vector3) {
+    let engine = thrust.normalize();
+    let target = target.normalize();
+    let angle = engine.angle(&target);
+    let axis = engine.cross(&target);
+    let axis = if axis.norm() > 1e-9 {
+        axis.normalize()
+    } else if angle > 0.0 {
+        // Opposite directions: any perpendicular axis works
+        engine
+            .cross(&Vector3::x())
+            .try_normalize(1e-9)
+            .unwrap_or_else(Vector3::y)
+    } else {
+        Vector3::zeros()
+    };
+    (angle, axis)
+}
+
+/// Returns the attitude thruster levels turning the vessel by `angle` around
+/// `axis`, with the (proportional, derivative) `gains` of the controller.
+fn attitude_levels(
+    gains: (f64, f64),
+    angle: f64,
+    axis: &Vector3,
+    angular_velocity: &Vector3,
+) -> Vector3 {
+    let (proportional, derivative) = gains;
+    (proportional * angle * axis - derivative * angular_velocity)
+        .map(|level| level.clamp(-1.0, 1.0))
+}
+
+/// Returns the thrust level for a velocity change `along` (in m/s) left in
+/// the direction of the thrust, with the acceleration at full thrust (in
+/// m/s²), the length of the time step (in s) and the pointing error (in rad).
+///
+/// The level decreases over the last seconds of the burn, and never exceeds
+/// the velocity change left within the time step. The engines stay off while
+/// the vessel points too far from the burn direction.
+fn throttle_level(along: f64, acceleration: f64, simdt: f64, pointing_error: f64) -> f64 {
+    if pointing_error > MAX_POINTING_ERROR {
+        0.0
+    } else {
+        (along / (acceleration * THROTTLE_TIME))
+            .clamp(MIN_LEVEL, 1.0)
+            .min(along / (acceleration * simdt))
+    }
+}
+
+/// Error stopping a [`BurnExecutor`]
+///
+/// [`BurnExecutor`]: ./struct.BurnExecutor.html
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BurnError {
+    /// The vessel was deleted
+    VesselDeleted,
+    /// The thruster group has no thrust, e.g. because it has no thrusters
+    /// or no fuel left
+    NoThrust,
+    /// The vessel has no gravity reference to measure the burn against
+    NoReference,
+    /// The ignition is too late, e.g. after a time jump past the burn
+    Missed,
+    /// The burn was cancelled with [`BurnExecutor::cancel`]
+    ///
+    /// [`BurnExecutor::cancel`]: ./struct.BurnExecutor.html#method.cancel
+    Cancelled,
+}
+
+impl std::fmt::Display for BurnError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::VesselDeleted => f.write_str("the vessel was deleted"),
+            Self::NoThrust => f.write_str("the thruster group has no thrust"),
+            Self::NoReference => f.write_str("the vessel has no gravity reference"),
+            Self::Missed => f.write_str("the ignition was missed"),
+            Self::Cancelled => f.write_str("the burn was cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for BurnError {}
+
+/// Outcome of a completed burn
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BurnReport {
+    /// Date of the ignition (MJD)
+    pub ignition_mjd: f64,
+    /// Date of the cutoff (MJD)
+    pub cutoff_mjd: f64,
+    /// Velocity change left at cutoff as (prograde, normal, radial)
+    /// components, in m/s
+    ///
+    /// Uses the orbital frame of the planned burn, like [`Burn::delta_v`].
+    ///
+    /// [`Burn::delta_v`]: ./maneuver/struct.Burn.html#structfield.delta_v
+    pub residual: Vector3,
+}
+
+/// Phase of a [`BurnExecutor`]
+///
+/// [`BurnExecutor`]: ./struct.BurnExecutor.html
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BurnStatus {
+    /// Waiting for the alignment to start
+    Coasting,
+    /// Turning the vessel to the burn direction
+    Aligning,
+    /// Firing the thruster group
+    Burning,
+    /// The burn is complete
+    Complete(BurnReport),
+    /// The burn was stopped before completion
+    Aborted(BurnError),
+}
+
+impl BurnStatus {
+    /// Returns `true` if the executor will not act anymore.
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Complete(_) | Self::Aborted(_))
+    }
+}
+
+/// Flies a planned [`Burn`] with the thrusters of a vessel.
+///
+/// The executor turns the vessel with its attitude thrusters so that the
+/// thrust of the main engines points along the velocity change, ignites half
+/// the burn duration before the date of the burn and throttles down near the
+/// end. The duration comes from the rocket equation with the current mass,
+/// thrust and specific impulse of the vessel. The time acceleration drops
+/// to 1 before the alignment and stays there until cutoff, when the attitude
+/// mode of the vessel is also restored.
+///
+/// The velocity change left is measured from the state of the vessel, as the
+/// difference with the orbit planned after the burn. The report reflects the
+/// actual thrust, e.g. when the fuel runs out or the engines lose thrust in
+/// an atmosphere. If the ignition comes too late, e.g. after a time jump
+/// past the burn, the burn aborts with [`BurnError::Missed`] instead of
+/// firing at the wrong point of the orbit.
+///
+/// Call [`update`] at every time step, from [`ModuleCallbacks::on_pre_step`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use orbiter::kepler::KeplerOrbit;
+/// use orbiter::orbit::StateVector;
+/// use orbiter::*;
+///
+/// struct Autopilot {
+///     executor: Option<BurnExecutor>,
+/// }
+///
+/// impl Autopilot {
+///     // Plans the circularization of the orbit of the focus vessel
+///     fn circularize(&mut self, module: &Module) {
+///         let vessel = Vessel::focus_vessel().unwrap();
+///         if let Some(Object::Planet(body)) = vessel.gravity_ref() {
+///             let state = StateVector::relative(&vessel, &body);
+///             let orbit = KeplerOrbit::new(state, body.gravitational_parameter(), module.sim_mjd());
+///             let maneuver = maneuver::circularize_at_apoapsis(&orbit).unwrap();
+///             self.executor = Some(BurnExecutor::new(&vessel, &maneuver.burns[0]));
+///         }
+///     }
+/// }
+///
+/// impl ModuleCallbacks for Autopilot {
+///     fn on_pre_step(&mut self, _module: &mut Module, _simt: f64, simdt: f64, mjd: f64) {
+///         if let Some(executor) = &mut self.executor {
+///             if let BurnStatus::Complete(report) = executor.update(mjd, simdt) {
+///                 debug_string!("Residual: {:.2} m/s", report.residual.norm());
+///                 self.executor = None;
+///             }
+///         }
+///     }
+/// }
+/// ```
+///
+/// [`Burn`]: ./maneuver/struct.Burn.html
+/// [`BurnError::Missed`]: ./enum.BurnError.html#variant.Missed
+/// [`update`]: #method.update
+/// [`ModuleCallbacks::on_pre_step`]: ./trait.ModuleCallbacks.html#method.on_pre_step
+pub struct BurnExecutor {
+    vessel: WeakVessel,
+    reference: Option<Object>,
+    group: ThrusterGroup,
+    mjd: f64,
+    /// Orbit planned after the burn
+    target: KeplerOrbit,
+    /// Velocity change left in the right-handed frame of the reference
+    remaining: Vector3,
+    frame: Rotation3<f64>,
+    ignition_mjd: f64,
+    status: BurnStatus,
+    attitude_mode: Option<AttitudeMode>,
+    alignment_time: f64,
+    max_ignition_delay: f64,
+    tolerance: f64,
+    gains: (f64, f64),
+}
+
+impl BurnExecutor {
+    /// Creates an executor flying a burn with the main engines of a vessel.
+    ///
+    /// The burn must be planned from the orbit of the vessel around its
+    /// current gravity reference.
+    pub fn new(vessel: &Vessel, burn: &Burn) -> Self {
+        Self {
+            vessel: vessel.downgrade(),
+            reference: vessel.gravity_ref(),
+            group: ThrusterGroup::Main,
+            mjd: burn.mjd,
+            target: KeplerOrbit::new(burn.elements.to_state_vector(), burn.elements.mu, burn.mjd),
+            remaining: burn.inertial_delta_v(),
+            frame: burn.state.orbital_frame(),
+            ignition_mjd: burn.mjd,
+            status: BurnStatus::Coasting,
+            attitude_mode: None,
+            alignment_time: 120.0,
+            max_ignition_delay: 10.0,
+            tolerance: 0.05,
+            gains: (2.0, 4.0),
+        }
+    }
+
+    /// Uses another thruster group than the main engines.
+    pub fn thruster_group(mut self, group: ThrusterGroup) -> Self {
+        self.group = group;
+        self
+    }
+
+    /// Sets the time (in s) reserved before ignition to turn the vessel.
+    ///
+    /// Defaults to 120 s.
+    pub fn alignment_time(mut self, time: f64) -> Self {
+        self.alignment_time = time;
+        self
+    }
+
+    /// Sets the time (in s) after the planned ignition past which the burn
+    /// aborts with [`BurnError::Missed`] if the engines are not firing yet.
+    ///
+    /// Defaults to 10 s.
+    ///
+    /// [`BurnError::Missed`]: ./enum.BurnError.html#variant.Missed
+    pub fn max_ignition_delay(mut self, delay: f64) -> Self {
+        self.max_ignition_delay = delay;
+        self
+    }
+
+    /// Sets the velocity change (in m/s) left at which the burn is complete.
+    ///
+    /// Defaults to 0.05 m/s.
+    pub fn tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Sets the proportional gain (per rad) and the derivative gain (per
+    /// rad/s) of the attitude controller.
+    ///
+    /// The attitude thruster levels are proportional to the pointing error
+    /// and opposed to the angular velocity. Defaults to 2 and 4.
+    pub fn attitude_gains(mut self, proportional: f64, derivative: f64) -> Self {
+        self.gains = (proportional, derivative);
+        self
+    }
+
+    /// Returns the current phase of the burn.
+    pub fn status(&self) -> BurnStatus {
+        self.status
+    }
+
+    /// Returns the estimated date of the ignition (MJD).
+    ///
+    /// The estimate is updated until the ignition.
+    pub fn ignition_mjd(&self) -> f64 {
+        self.ignition_mjd
+    }
+
+    /// Returns the velocity change left (in m/s), as of the last update.
+    pub fn remaining_delta_v(&self) -> f64 {
+        self.remaining.norm()
+    }
+
+    /// Stops the burn, shutting down the thrusters.
+    pub fn cancel(&mut self) {
+        if self.status.is_finished() {
+            return;
+        }
+        if let Ok(mut vessel) = self.vessel.upgrade() {
+            self.shut_down(&mut vessel);
+        }
+        self.status = BurnStatus::Aborted(BurnError::Cancelled);
+    }
+
+    /// Controls the vessel for the next time step and returns the phase of
+    /// the burn.
+    ///
+    /// `mjd` is the current date and `simdt` the length of the time step (in
+    /// s), as given to [`ModuleCallbacks::on_pre_step`].
+    ///
+    /// [`ModuleCallbacks::on_pre_step`]: ./trait.ModuleCallbacks.html#method.on_pre_step
+    pub fn update(&mut self, mjd: f64, simdt: f64) -> BurnStatus {
+        if self.status.is_finished() {
+            return self.status;
+        }
+        let mut vessel = match self.vessel.upgrade() {
+            Ok(vessel) => vessel,
+            Err(_) => {
+                self.status = BurnStatus::Aborted(BurnError::VesselDeleted);
+                return self.status;
+            }
+        };
+
+        let state = match &self.reference {
+            Some(reference) => StateVector::relative(&vessel, reference),
+            None => return self.abort(&mut vessel, BurnError::NoReference),
+        };
+        // Until ignition, the planned impulse is the best estimate: the
+        // planned orbit only meets the current one at the burn
+        if self.status == BurnStatus::Burning {
+            if let Ok(target) = self.target.state_at(mjd) {
+                self.remaining = target.velocity - state.velocity;
+            }
+        }
+        let remaining = to_left_handed(self.remaining);
+        let thrust = vessel.thruster_group_max_thrust(self.group);
+        let duration = match burn_duration(&vessel, self.group, remaining.norm()) {
+            Some(duration) => duration,
+            None => return self.abort(&mut vessel, BurnError::NoThrust),
+        };
+
+        if self.status != BurnStatus::Burning {
+            self.ignition_mjd = self.mjd - duration / 2.0 / SECONDS_PER_DAY;
+            let time_to_ignition = (self.ignition_mjd - mjd) * SECONDS_PER_DAY;
+            if drops_warp(time_acceleration(), time_to_ignition, self.alignment_time) {
+                set_time_acceleration(1.0);
+            }
+            match countdown(
+                time_to_ignition,
+                self.alignment_time,
+                self.max_ignition_delay,
+            ) {
+                Ok(BurnStatus::Coasting) => {
+                    self.status = BurnStatus::Coasting;
+                    return self.status;
+                }
+                Ok(_) => (),
+                Err(error) => return self.abort(&mut vessel, error),
+            }
+            if self.status == BurnStatus::Coasting {
+                self.attitude_mode = vessel.attitude_mode();
+                vessel.set_attitude_mode(AttitudeMode::Rotational);
+                self.status = BurnStatus::Aligning;
+            }
+
+            let error = self.steer(&mut vessel, remaining, thrust);
+            if time_to_ignition > 0.0 || error > MAX_POINTING_ERROR {
+                return self.status;
+            }
+            self.ignition_mjd = mjd;
+            self.status = BurnStatus::Burning;
+        }
+
+        if time_acceleration() > 1.0 {
+            set_time_acceleration(1.0);
+        }
+        let error = self.steer(&mut vessel, remaining, thrust);
+        let direction = vessel.local_to_global_direction(thrust).normalize();
+        let along = remaining.dot(&direction);
+        if remaining.norm() <= self.tolerance || along <= 0.0 {
+            self.shut_down(&mut vessel);
+            self.status = BurnStatus::Complete(BurnReport {
+                ignition_mjd: self.ignition_mjd,
+                cutoff_mjd: mjd,
+                residual: self.frame.inverse() * self.remaining,
+            });
+            return self.status;
+        }
+
+        let acceleration = thrust.norm() / vessel.mass();
+        let level = throttle_level(along, acceleration, simdt, error);
+        vessel.set_thruster_group_level(self.group, level);
+        self.status
+    }
+
+    fn abort(&mut self, vessel: &mut Vessel, error: BurnError) -> BurnStatus {
+        self.shut_down(vessel);
+        self.status = BurnStatus::Aborted(error);
+        self.status
+    }
+
+    /// Turns the thrust of the vessel toward a global direction and returns
+    /// the angle between them.
+    fn steer(&self, vessel: &mut Vessel, direction: Vector3, thrust: Vector3) -> f64 {
+        let target = to_right_handed(vessel.global_to_local_direction(direction));
+        let (angle, axis) = pointing_error(&to_right_handed(thrust), &target);
+        let angular_velocity = pseudovector_to_right_handed(vessel.angular_velocity());
+        let level = attitude_levels(self.gains, angle, &axis, &angular_velocity);
+        vessel.set_attitude_rot_level(pseudovector_to_right_handed(level));
+        angle
+    }
+
+    fn shut_down(&mut self, vessel: &mut Vessel) {
+        vessel.set_thruster_group_level(self.group, 0.0);
+        vessel.set_attitude_rot_level(Vector3::zeros());
+        if let Some(mode) = self.attitude_mode.take() {
+            vessel.set_attitude_mode(mode);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::{FRAC_PI_2, LN_2, PI};
+
+    #[test]
+    fn rocket_equation() {
+        // Burning half the mass
+        let duration = rocket_burn_time(1000.0, 3000.0, 3000.0, 3000.0 * LN_2).unwrap();
+        assert!((duration - 500.0).abs() < 1e-9);
+        // Short burns barely change the mass
+        let duration = rocket_burn_time(1000.0, 3000.0, 3000.0, 0.3).unwrap();
+        assert!((duration - 0.1).abs() < 1e-5);
+
+        assert_eq!(rocket_burn_time(1000.0, 3000.0, 3000.0, 0.0), Some(0.0));
+        assert_eq!(rocket_burn_time(1000.0, 3000.0, 3000.0, -10.0), Some(0.0));
+    }
+
+    #[test]
+    fn no_burn_without_thrust() {
+        assert_eq!(rocket_burn_time(1000.0, 0.0, 3000.0, 10.0), None);
+        assert_eq!(rocket_burn_time(1000.0, 3000.0, 0.0, 10.0), None);
+    }
+
+    #[test]
+    fn warp_drops_before_the_alignment() {
+        assert!(!drops_warp(1.0, 0.0, 120.0));
+        assert!(!drops_warp(10.0, 300.0, 120.0));
+        // 10 s of margin at each step of the time acceleration
+        assert!(drops_warp(10.0, 200.0, 120.0));
+        assert!(!drops_warp(100.0, 1200.0, 120.0));
+        assert!(drops_warp(100.0, 1000.0, 120.0));
+    }
+
+    #[test]
+    fn countdown_phases() {
+        assert_eq!(countdown(121.0, 120.0, 10.0), Ok(BurnStatus::Coasting));
+        assert_eq!(countdown(120.0, 120.0, 10.0), Ok(BurnStatus::Aligning));
+        assert_eq!(countdown(0.0, 120.0, 10.0), Ok(BurnStatus::Aligning));
+        // Late, e.g. while still turning, but within the allowed delay
+        assert_eq!(countdown(-10.0, 120.0, 10.0), Ok(BurnStatus::Aligning));
+    }
+
+    #[test]
+    fn missed_ignition_aborts() {
+        let status = match countdown(-10.5, 120.0, 10.0) {
+            Ok(status) => status,
+            Err(error) => BurnStatus::Aborted(error),
+        };
+        assert_eq!(status, BurnStatus::Aborted(BurnError::Missed));
+        assert!(status.is_finished());
+        assert!(!BurnStatus::Coasting.is_finished());
+        assert!(!BurnStatus::Burning.is_finished());
+
+        // A time jump far past the burn
+        assert_eq!(countdown(-1e6, 120.0, 10.0), Err(BurnError::Missed));
+    }
+
+    #[test]
+    fn pointing_error_axis() {
+        let (angle, axis) = pointing_error(&Vector3::new(0.0, 0.0, 2.0), &Vector3::z());
+        assert_eq!(angle, 0.0);
+        assert_eq!(axis, Vector3::zeros());
+
+        let (angle, axis) = pointing_error(&Vector3::x(), &Vector3::new(0.0, 3.0, 0.0));
+        assert!((angle - FRAC_PI_2).abs() < 1e-12);
+        assert!((axis - Vector3::z()).norm() < 1e-12);
+
+        // Turning the other way
+        let (_, axis) = pointing_error(&Vector3::y(), &Vector3::x());
+        assert!((axis + Vector3::z()).norm() < 1e-12);
+
+        for thrust in [Vector3::x(), Vector3::z()] {
+            let (angle, axis) = pointing_error(&thrust, &-thrust);
+            assert!((angle - PI).abs() < 1e-12);
+            assert!((axis.norm() - 1.0).abs() < 1e-12);
+            assert!(axis.dot(&thrust).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn attitude_levels_turn_and_damp() {
+        let levels = attitude_levels((2.0, 4.0), 0.1, &Vector3::z(), &Vector3::zeros());
+        assert!((levels - Vector3::new(0.0, 0.0, 0.2)).norm() < 1e-12);
+
+        // Saturated far from the target
+        let levels = attitude_levels((2.0, 4.0), PI, &Vector3::z(), &Vector3::zeros());
+        assert_eq!(levels, Vector3::z());
+
+        // Braking when turning too fast toward the target
+        let levels = attitude_levels((2.0, 4.0), 0.1, &Vector3::z(), &Vector3::new(0.0, 0.0, 0.1));
+        assert!((levels - Vector3::new(0.0, 0.0, -0.2)).norm() < 1e-12);
+
+        // Stopping a rotation on the target
+        let levels = attitude_levels((2.0, 4.0), 0.0, &Vector3::zeros(), &Vector3::x());
+        assert_eq!(levels, -Vector3::x());
+    }
+
+    #[test]
+    fn throttles_down_near_cutoff() {
+        // 10 m/s² at full thrust
+        assert_eq!(throttle_level(100.0, 10.0, 0.1, 0.0), 1.0);
+        assert!((throttle_level(10.0, 10.0, 0.1, 0.0) - 0.5).abs() < 1e-12);
+        assert_eq!(throttle_level(0.5, 10.0, 0.01, 0.0), MIN_LEVEL);
+        // Never more than the velocity change left in the time step
+        assert!((throttle_level(0.02, 10.0, 0.1, 0.0) - 0.02).abs() < 1e-12);
+    }
+
+    #[test]
+    fn no_thrust_while_misaligned() {
+        assert_eq!(throttle_level(100.0, 10.0, 0.1, MAX_POINTING_ERROR), 1.0);
+        assert_eq!(
+            throttle_level(100.0, 10.0, 0.1, 2.0 * MAX_POINTING_ERROR),
+            0.0
+        );
+    }
+}
@@ -0,0 +1,17 @@
+//! Values and states shared by the unit tests of the orbit mechanics modules.
+
+use crate::orbit::StateVector;
+use crate::Vector3;
+
+/// Gravitational parameter of the Earth, in m³/s²
+pub(crate) const MU_EARTH: f64 = 3.986004418e14;
+
+/// Returns the state at the periapsis of an orbit around the Earth, with the
+/// periapsis on the x axis and the orbit inclined around it.
+pub(crate) fn periapsis_state(periapsis: f64, eccentricity: f64, inclination: f64) -> StateVector {
+    let speed = (MU_EARTH * (1.0 + eccentricity) / periapsis).sqrt();
+    StateVector::new(
+        Vector3::new(periapsis, 0.0, 0.0),
+        speed * Vector3::new(0.0, inclination.cos(), inclination.sin()),
+    )
+}
@@ -0,0 +1,543 @@
+//! Vectors tagged with their reference frame.
+//!
+//! The methods of [`ObjectTrait`] return bare [`Vector3`] values, and it is
+//! easy to mix up vectors expressed in different frames. The types of this
+//! module carry their frame as a type parameter, so that such mistakes are
+//! caught by the compiler. They have the same memory layout as `Vector3`.
+//!
+//! A [`Vector`] is a free vector (a direction, a velocity, a displacement),
+//! while a [`Position`] is a point relative to an origin. The origin is the
+//! one of the frame unless given as a second type parameter: a
+//! `Position<Global, Equatorial>` has global axes but is centered on a
+//! planet. Changing frame is done with a [`Transform`], which only rotates
+//! vectors but also translates positions.
+//!
+//! Vessels and celestial bodies have distinct local frames, [`VesselLocal`]
+//! and [`Equatorial`], so that a transform only accepts values of its own
+//! frame.
+//!
+//! All frames use Orbiter's left-handed convention.
+//!
+//! # Examples
+//!
+//! ```
+//! use orbiter::frame::{Equatorial, Global, Position, Transform, Vector, VesselLocal};
+//! use orbiter::{Matrix3, Vector3};
+//!
+//! let local_to_global: Transform<VesselLocal, Global> =
+//!     Transform::new(Matrix3::identity(), Vector3::new(100.0, 0.0, 0.0));
+//!
+//! let point = Position::<VesselLocal>::new(1.0, 2.0, 3.0);
+//! let direction = Vector::<VesselLocal>::new(0.0, 0.0, 1.0);
+//!
+//! assert_eq!(local_to_global.position(point), Position::new(101.0, 2.0, 3.0));
+//! assert_eq!(local_to_global.vector(direction), Vector::new(0.0, 0.0, 1.0));
+//!
+//! let global_to_local = local_to_global.inverse();
+//! assert_eq!(global_to_local.position(local_to_global.position(point)), point);
+//!
+//! // Positions keep their origin when only the axes change
+//! let equatorial_to_global: Transform<Equatorial, Global> =
+//!     Transform::new(Matrix3::identity(), Vector3::new(1e11, 0.0, 0.0));
+//! let around_planet = Position::<Global, Equatorial>::new(6.4e6, 0.0, 0.0);
+//! let equatorial = equatorial_to_global.inverse().rotate_position(around_planet);
+//! assert_eq!(equatorial, Position::<Equatorial>::new(6.4e6, 0.0, 0.0));
+//! ```
+//!
+//! [`ObjectTrait`]: ../trait.ObjectTrait.html
+//! [`Vector3`]: ../type.Vector3.html
+//! [`Vector`]: ./struct.Vector.html
+//! [`Position`]: ./struct.Position.html
+//! [`Transform`]: ./struct.Transform.html
+//! [`VesselLocal`]: ./enum.VesselLocal.html
+//! [`Equatorial`]: ./enum.Equatorial.html
+
+use crate::{GeoPosition, Matrix3, ObjectTrait, Planet, Star, Vector3, Vessel};
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+/// Marker trait for reference frames
+pub trait Frame: 'static {}
+
+/// Global frame: heliocentric ecliptic frame at ecliptic and equinox of J2000
+pub enum Global {}
+
+/// Rotating equatorial frame of a celestial body, with the y axis along the
+/// rotation axis and the x axis towards 0° longitude
+///
+/// Its origin is the center of the body.
+pub enum Equatorial {}
+
+/// Local frame of a vessel, attached to the vessel and rotating with it
+///
+/// Its origin is the center of gravity of the vessel.
+pub enum VesselLocal {}
+
+/// Local horizon frame, with x pointing east, y up and z north
+pub enum Horizon {}
+
+impl Frame for Global {}
+impl Frame for Equatorial {}
+impl Frame for VesselLocal {}
+impl Frame for Horizon {}
+
+/// Free vector expressed in the frame `F`
+#[repr(transparent)]
+pub struct Vector<F: Frame> {
+    raw: Vector3,
+    frame: PhantomData<fn() -> F>,
+}
+
+/// Position expressed in the axes of the frame `F`, relative to the origin of
+/// the frame `O`
+///
+/// By default, the position is relative to the origin of its own frame.
+#[repr(transparent)]
+pub struct Position<F: Frame, O: Frame = F> {
+    raw: Vector3,
+    frame: PhantomData<fn() -> (F, O)>,
+}
+
+/// Objects with a local frame
+///
+/// Used by [`Framed::local_to_global`] to tag the transform with the frame of
+/// the object.
+///
+/// [`Framed::local_to_global`]: ./struct.Framed.html#method.local_to_global
+pub trait LocalFrame: ObjectTrait {
+    type Frame: Frame;
+}
+
+impl LocalFrame for Vessel {
+    type Frame = VesselLocal;
+}
+
+impl LocalFrame for Planet {
+    type Frame = Equatorial;
+}
+
+impl LocalFrame for Star {
+    type Frame = Equatorial;
+}
+
+macro_rules! impl_common {
+    ($type:ident $(, $origin:ident)?) => {
+        impl<F: Frame $(, $origin: Frame)?> $type<F $(, $origin)?> {
+            pub fn new(x: f64, y: f64, z: f64) -> Self {
+                Self::from_raw(Vector3::new(x, y, z))
+            }
+
+            /// Tags a bare vector with the frame `F`.
+            pub fn from_raw(raw: Vector3) -> Self {
+                Self {
+                    raw,
+                    frame: PhantomData,
+                }
+            }
+
+            /// Returns the bare vector.
+            pub fn raw(&self) -> Vector3 {
+                self.raw
+            }
+        }
+
+        impl<F: Frame $(, $origin: Frame)?> Clone for $type<F $(, $origin)?> {
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+
+        impl<F: Frame $(, $origin: Frame)?> Copy for $type<F $(, $origin)?> {}
+
+        impl<F: Frame $(, $origin: Frame)?> PartialEq for $type<F $(, $origin)?> {
+            fn eq(&self, other: &Self) -> bool {
+                self.raw == other.raw
+            }
+        }
+
+        impl<F: Frame $(, $origin: Frame)?> fmt::Debug for $type<F $(, $origin)?> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_tuple(stringify!($type))
+                    .field(&self.raw.x)
+                    .field(&self.raw.y)
+                    .field(&self.raw.z)
+                    .finish()
+            }
+        }
+    };
+}
+
+impl_common!(Vector);
+impl_common!(Position, O);
+
+impl<F: Frame> Vector<F> {
+    pub fn zeros() -> Self {
+        Self::from_raw(Vector3::zeros())
+    }
+
+    pub fn norm(&self) -> f64 {
+        self.raw.norm()
+    }
+
+    pub fn normalize(&self) -> Self {
+        Self::from_raw(self.raw.normalize())
+    }
+
+    pub fn dot(&self, other: &Self) -> f64 {
+        self.raw.dot(&other.raw)
+    }
+
+    pub fn cross(&self, other: &Self) -> Self {
+        Self::from_raw(self.raw.cross(&other.raw))
+    }
+}
+
+impl<F: Frame, O: Frame> Position<F, O> {
+    /// Returns the origin of the frame `O`.
+    pub fn origin() -> Self {
+        Self::from_raw(Vector3::zeros())
+    }
+
+    /// Returns the vector from the origin of the frame `O` to the position.
+    pub fn coords(&self) -> Vector<F> {
+        Vector::from_raw(self.raw)
+    }
+}
+
+impl Position<Equatorial> {
+    /// Converts equatorial coordinates to a position in the equatorial frame
+    /// of a planet.
+    pub fn from_geo(position: &GeoPosition) -> Self {
+        Self::from_raw(position.to_local())
+    }
+
+    /// Converts a position in the equatorial frame of a planet to equatorial
+    /// coordinates.
+    pub fn to_geo(&self) -> GeoPosition {
+        GeoPosition::from_local(self.raw)
+    }
+}
+
+impl<F: Frame> Add for Vector<F> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::from_raw(self.raw + rhs.raw)
+    }
+}
+
+impl<F: Frame> AddAssign for Vector<F> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.raw += rhs.raw;
+    }
+}
+
+impl<F: Frame> Sub for Vector<F> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::from_raw(self.raw - rhs.raw)
+    }
+}
+
+impl<F: Frame> SubAssign for Vector<F> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.raw -= rhs.raw;
+    }
+}
+
+impl<F: Frame> Neg for Vector<F> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::from_raw(-self.raw)
+    }
+}
+
+impl<F: Frame> Mul<f64> for Vector<F> {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Self {
+        Self::from_raw(self.raw * rhs)
+    }
+}
+
+impl<F: Frame> Mul<Vector<F>> for f64 {
+    type Output = Vector<F>;
+
+    fn mul(self, rhs: Vector<F>) -> Vector<F> {
+        Vector::from_raw(rhs.raw * self)
+    }
+}
+
+impl<F: Frame> Div<f64> for Vector<F> {
+    type Output = Self;
+
+    fn div(self, rhs: f64) -> Self {
+        Self::from_raw(self.raw / rhs)
+    }
+}
+
+impl<F: Frame, O: Frame> Add<Vector<F>> for Position<F, O> {
+    type Output = Self;
+
+    fn add(self, rhs: Vector<F>) -> Self {
+        Self::from_raw(self.raw + rhs.raw)
+    }
+}
+
+impl<F: Frame, O: Frame> AddAssign<Vector<F>> for Position<F, O> {
+    fn add_assign(&mut self, rhs: Vector<F>) {
+        self.raw += rhs.raw;
+    }
+}
+
+impl<F: Frame, O: Frame> Sub<Vector<F>> for Position<F, O> {
+    type Output = Self;
+
+    fn sub(self, rhs: Vector<F>) -> Self {
+        Self::from_raw(self.raw - rhs.raw)
+    }
+}
+
+impl<F: Frame, O: Frame> SubAssign<Vector<F>> for Position<F, O> {
+    fn sub_assign(&mut self, rhs: Vector<F>) {
+        self.raw -= rhs.raw;
+    }
+}
+
+impl<F: Frame, O: Frame> Sub for Position<F, O> {
+    type Output = Vector<F>;
+
+    fn sub(self, rhs: Self) -> Vector<F> {
+        Vector::from_raw(self.raw - rhs.raw)
+    }
+}
+
+/// Rigid transform from the frame `From` to the frame `To`
+///
+/// The transform is `rotation * v + translation`, where `translation` is the
+/// origin of `From` expressed in `To`. Vectors are only rotated.
+pub struct Transform<From: Frame, To: Frame> {
+    rotation: Matrix3,
+    translation: Vector3,
+    frames: PhantomData<fn(From) -> To>,
+}
+
+impl<From: Frame, To: Frame> Transform<From, To> {
+    /// Creates a transform from a rotation matrix and the origin of `From`
+    /// expressed in `To`.
+    pub fn new(rotation: Matrix3, translation: Vector3) -> Self {
+        Self {
+            rotation,
+            translation,
+            frames: PhantomData,
+        }
+    }
+
+    /// Returns the rotation matrix of the transform.
+    pub fn rotation(&self) -> Matrix3 {
+        self.rotation
+    }
+
+    /// Returns the origin of `From` expressed in `To`.
+    pub fn translation(&self) -> Position<To> {
+        Position::from_raw(self.translation)
+    }
+
+    /// Rotates a vector from `From` to `To`.
+    pub fn vector(&self, vector: Vector<From>) -> Vector<To> {
+        Vector::from_raw(self.rotation * vector.raw)
+    }
+
+    /// Transforms a position from `From` to `To`.
+    pub fn position(&self, position: Position<From>) -> Position<To> {
+        Position::from_raw(self.rotation * position.raw + self.translation)
+    }
+
+    /// Rotates a position from the axes of `From` to the axes of `To`,
+    /// keeping its origin.
+    pub fn rotate_position<O: Frame>(&self, position: Position<From, O>) -> Position<To, O> {
+        Position::from_raw(self.rotation * position.raw)
+    }
+
+    /// Returns the transform from `To` to `From`.
+    pub fn inverse(&self) -> Transform<To, From> {
+        let rotation = self.rotation.transpose();
+        Transform::new(rotation, -(rotation * self.translation))
+    }
+
+    /// Chains this transform with a transform from `To` to `Next`.
+    pub fn then<Next: Frame>(&self, next: &Transform<To, Next>) -> Transform<From, Next> {
+        Transform::new(
+            next.rotation * self.rotation,
+            next.rotation * self.translation + next.translation,
+        )
+    }
+}
+
+impl<From: Frame, To: Frame> Clone for Transform<From, To> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<From: Frame, To: Frame> Copy for Transform<From, To> {}
+
+impl<From: Frame, To: Frame> fmt::Debug for Transform<From, To> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Transform")
+            .field("rotation", &self.rotation)
+            .field("translation", &self.translation)
+            .finish()
+    }
+}
+
+/// Frame-tagged accessors of an object
+///
+/// Returned by [`ObjectTrait::framed`].
+///
+/// [`ObjectTrait::framed`]: ../trait.ObjectTrait.html#method.framed
+pub struct Framed<'a, T: ObjectTrait> {
+    object: &'a T,
+}
+
+impl<'a, T: ObjectTrait> Framed<'a, T> {
+    pub(crate) fn new(object: &'a T) -> Self {
+        Self { object }
+    }
+
+    /// Returns the position of the object in the global frame.
+    pub fn global_pos(&self) -> Position<Global> {
+        Position::from_raw(self.object.global_pos())
+    }
+
+    /// Returns the velocity of the object in the global frame.
+    pub fn global_velocity(&self) -> Vector<Global> {
+        Vector::from_raw(self.object.global_velocity())
+    }
+
+    /// Returns the distance vector from the reference object to the object,
+    /// in the global frame.
+    pub fn relative_position(&self, reference: &dyn ObjectTrait) -> Vector<Global> {
+        Vector::from_raw(self.object.relative_position(reference))
+    }
+
+    /// Returns the velocity of the object relative to the reference object,
+    /// in the global frame.
+    pub fn relative_velocity(&self, reference: &dyn ObjectTrait) -> Vector<Global> {
+        Vector::from_raw(self.object.relative_velocity(reference))
+    }
+
+    /// Returns the position of the object relative to the center of a
+    /// planet, in the global frame.
+    pub fn position_around(&self, planet: &Planet) -> Position<Global, Equatorial> {
+        Position::from_raw(self.object.global_pos() - planet.global_pos())
+    }
+
+    /// Returns the transform from the local horizon frame of the object above
+    /// a planet to the global frame.
+    ///
+    /// The origin of the horizon frame is the center of the object.
+    pub fn horizon_to_global(&self, planet: &Planet) -> Transform<Horizon, Global> {
+        let equatorial = planet.framed().global_to_local();
+        let geo = equatorial
+            .rotate_position(self.position_around(planet))
+            .to_geo();
+
+        let (sin_lat, cos_lat) = geo.latitude.sin_cos();
+        let (sin_lng, cos_lng) = geo.longitude.sin_cos();
+        let east = Vector3::new(-sin_lng, 0.0, cos_lng);
+        let up = Vector3::new(cos_lat * cos_lng, sin_lat, cos_lat * sin_lng);
+        let north = Vector3::new(-sin_lat * cos_lng, cos_lat, -sin_lat * sin_lng);
+        let horizon = Matrix3::from_columns(&[east, up, north]);
+
+        Transform::new(planet.rotation_matrix() * horizon, self.object.global_pos())
+    }
+}
+
+impl<'a, T: LocalFrame> Framed<'a, T> {
+    /// Returns the transform from the local frame of the object to the
+    /// global frame.
+    pub fn local_to_global(&self) -> Transform<T::Frame, Global> {
+        Transform::new(self.object.rotation_matrix(), self.object.global_pos())
+    }
+
+    /// Returns the transform from the global frame to the local frame of the
+    /// object.
+    pub fn global_to_local(&self) -> Transform<Global, T::Frame> {
+        self.local_to_global().inverse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rotation_y(angle: f64) -> Matrix3 {
+        let (sin, cos) = angle.sin_cos();
+        Matrix3::new(cos, 0.0, sin, 0.0, 1.0, 0.0, -sin, 0.0, cos)
+    }
+
+    fn assert_close<F: Frame, O: Frame>(actual: Position<F, O>, expected: Position<F, O>) {
+        assert!(
+            (actual - expected).norm() < 1e-6,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let to_global: Transform<VesselLocal, Global> =
+            Transform::new(rotation_y(0.3), Vector3::new(1.0, -2.0, 3.0));
+        let point = Position::new(4.0, 5.0, -6.0);
+
+        assert_close(
+            to_global.inverse().position(to_global.position(point)),
+            point,
+        );
+        assert_close(to_global.translation(), Position::new(1.0, -2.0, 3.0));
+    }
+
+    #[test]
+    fn then() {
+        let vessel: Transform<VesselLocal, Global> =
+            Transform::new(rotation_y(0.3), Vector3::new(1.0, -2.0, 3.0));
+        let planet: Transform<Equatorial, Global> =
+            Transform::new(rotation_y(-1.1), Vector3::new(1e3, 0.0, 0.0));
+        let vessel_to_planet = vessel.then(&planet.inverse());
+        let point = Position::new(4.0, 5.0, -6.0);
+
+        assert_close(
+            vessel_to_planet.position(point),
+            planet.inverse().position(vessel.position(point)),
+        );
+    }
+
+    #[test]
+    fn rotate_position_keeps_origin() {
+        let to_global: Transform<Equatorial, Global> =
+            Transform::new(rotation_y(0.7), Vector3::new(1e3, 0.0, 0.0));
+        let around_planet = Position::<Global, Equatorial>::new(10.0, 0.0, 0.0);
+        let global = Position::<Global>::new(1e3, 0.0, 0.0) + around_planet.coords();
+
+        assert_close(
+            to_global.inverse().rotate_position(around_planet),
+            to_global.inverse().position(global),
+        );
+    }
+
+    #[test]
+    fn geo() {
+        let position = GeoPosition::new(0.5, -1.2, 6.4e6);
+        let equatorial = Position::<Equatorial>::from_geo(&position);
+        let back = equatorial.to_geo();
+
+        assert!((back.longitude - position.longitude).abs() < 1e-12);
+        assert!((back.latitude - position.latitude).abs() < 1e-12);
+        assert!((back.radius - position.radius).abs() < 1e-6);
+    }
+}
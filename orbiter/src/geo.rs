@@ -0,0 +1,373 @@
+use crate::time::SECONDS_PER_DAY;
+use crate::{oapic_VECTOR3, CelestialBodyTrait, Matrix3, ObjectTrait, Planet, Vector3, OBJHANDLE};
+use std::f64::consts::PI;
+
+/// Position in the rotating equatorial frame of a celestial body.
+///
+/// Angles are in radians and the radius is the distance to the center of the
+/// body in meters. Longitudes are positive towards the east.
+///
+/// # Examples
+///
+/// ```
+/// use orbiter::GeoPosition;
+///
+/// let position = GeoPosition::new(0.5, -1.2, 6.4e6);
+/// let back = GeoPosition::from_local(position.to_local());
+///
+/// assert!((back.latitude - position.latitude).abs() < 1e-12);
+/// assert!((back.longitude - position.longitude).abs() < 1e-12);
+/// assert!((back.radius - position.radius).abs() < 1e-6);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GeoPosition {
+    /// Latitude, from -π/2 (south pole) to π/2 (north pole)
+    pub latitude: f64,
+    /// Longitude, from -π to π
+    pub longitude: f64,
+    /// Distance from the center of the body
+    pub radius: f64,
+}
+
+impl GeoPosition {
+    pub fn new(latitude: f64, longitude: f64, radius: f64) -> Self {
+        Self {
+            latitude,
+            longitude,
+            radius,
+        }
+    }
+
+    /// Converts a position in the local frame of the body.
+    ///
+    /// The local frame has its origin at the center of the body, the y axis
+    /// along the rotation axis and the x axis pointing towards 0° longitude.
+    pub fn from_local(local: Vector3) -> Self {
+        let radius = local.norm();
+        if radius == 0.0 {
+            return Self::new(0.0, 0.0, 0.0);
+        }
+        Self {
+            latitude: (local.y / radius).asin(),
+            longitude: local.z.atan2(local.x),
+            radius,
+        }
+    }
+
+    /// Converts the position to the local frame of the body.
+    pub fn to_local(&self) -> Vector3 {
+        let (sin_lat, cos_lat) = self.latitude.sin_cos();
+        let (sin_lng, cos_lng) = self.longitude.sin_cos();
+        self.radius * Vector3::new(cos_lat * cos_lng, sin_lat, cos_lat * sin_lng)
+    }
+
+    /// Returns the altitude above a body of the given radius.
+    pub fn altitude(&self, body_radius: f64) -> f64 {
+        self.radius - body_radius
+    }
+}
+
+/// Rotation parameters of a celestial body.
+///
+/// This allows conversions between global and equatorial coordinates without
+/// a running simulation, for example in planning tools or tests. The rotation
+/// matrix of the body is `equator * Ry(φ)`, where `φ` is the rotation angle at
+/// the requested time and `Ry` is a rotation around the y axis, following
+/// Orbiter's conventions.
+///
+/// # Examples
+///
+/// ```
+/// use orbiter::{BodyRotation, GeoPosition, Vector3};
+///
+/// let earth = BodyRotation::new(0.4091, 0.0, 86164.1, 4.88, 51544.5);
+/// let earth_pos = Vector3::new(1.0e11, 0.0, -1.1e11);
+///
+/// let ksc = GeoPosition::new(28.5f64.to_radians(), -80.6f64.to_radians(), 6.371e6);
+/// let global = earth.equatorial_to_global(&ksc, earth_pos, 51545.25);
+/// let back = earth.global_to_equatorial(global, earth_pos, 51545.25);
+///
+/// assert!((back.latitude - ksc.latitude).abs() < 1e-9);
+/// assert!((back.longitude - ksc.longitude).abs() < 1e-9);
+/// assert!((back.radius - ksc.radius).abs() < 1e-3);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BodyRotation {
+    /// Orientation of the non-rotating equatorial frame in the global frame
+    pub equator: Matrix3,
+    /// Sidereal rotation period in seconds
+    pub period: f64,
+    /// Rotation angle at the epoch in radians
+    pub rotation_at_epoch: f64,
+    /// Epoch of the rotation angle, as a Modified Julian Date
+    pub epoch_mjd: f64,
+}
+
+impl BodyRotation {
+    /// Creates rotation parameters from the orientation of the rotation axis.
+    ///
+    /// `obliquity` is the angle between the rotation axis and the ecliptic
+    /// normal, and `theta` is the longitude of the ascending node of the
+    /// equator on the ecliptic, both in radians.
+    pub fn new(
+        obliquity: f64,
+        theta: f64,
+        period: f64,
+        rotation_at_epoch: f64,
+        epoch_mjd: f64,
+    ) -> Self {
+        let (sin_eps, cos_eps) = obliquity.sin_cos();
+        let obliquity_matrix =
+            Matrix3::new(1.0, 0.0, 0.0, 0.0, cos_eps, -sin_eps, 0.0, sin_eps, cos_eps);
+        Self {
+            equator: rotation_y(theta) * obliquity_matrix,
+            period,
+            rotation_at_epoch,
+            epoch_mjd,
+        }
+    }
+
+    /// Returns the rotation angle of the body at a given time, in radians.
+    pub fn rotation_angle(&self, mjd: f64) -> f64 {
+        let elapsed = (mjd - self.epoch_mjd) * SECONDS_PER_DAY;
+        (self.rotation_at_epoch + 2.0 * PI * elapsed / self.period).rem_euclid(2.0 * PI)
+    }
+
+    /// Returns the rotation matrix from the local frame of the body to the
+    /// global frame at a given time.
+    pub fn rotation_matrix(&self, mjd: f64) -> Matrix3 {
+        self.equator * rotation_y(self.rotation_angle(mjd))
+    }
+
+    /// Converts a global position to the local frame of the body.
+    pub fn global_to_local(&self, global: Vector3, body_position: Vector3, mjd: f64) -> Vector3 {
+        self.rotation_matrix(mjd).transpose() * (global - body_position)
+    }
+
+    /// Converts a position in the local frame of the body to a global position.
+    pub fn local_to_global(&self, local: Vector3, body_position: Vector3, mjd: f64) -> Vector3 {
+        self.rotation_matrix(mjd) * local + body_position
+    }
+
+    /// Converts a global position to equatorial coordinates.
+    pub fn global_to_equatorial(
+        &self,
+        global: Vector3,
+        body_position: Vector3,
+        mjd: f64,
+    ) -> GeoPosition {
+        GeoPosition::from_local(self.global_to_local(global, body_position, mjd))
+    }
+
+    /// Converts equatorial coordinates to a global position.
+    pub fn equatorial_to_global(
+        &self,
+        position: &GeoPosition,
+        body_position: Vector3,
+        mjd: f64,
+    ) -> Vector3 {
+        self.local_to_global(position.to_local(), body_position, mjd)
+    }
+}
+
+fn rotation_y(angle: f64) -> Matrix3 {
+    let (sin, cos) = angle.sin_cos();
+    Matrix3::new(cos, 0.0, -sin, 0.0, 1.0, 0.0, sin, 0.0, cos)
+}
+
+impl Planet {
+    /// Converts a global position to equatorial coordinates of the planet.
+    pub fn global_to_equatorial(&self, global: Vector3) -> GeoPosition {
+        let global = oapic_VECTOR3::from(global);
+        let mut position = GeoPosition::new(0.0, 0.0, 0.0);
+        unsafe {
+            oapic_oapiGlobalToEqu(
+                self.handle,
+                &global,
+                &mut position.longitude,
+                &mut position.latitude,
+                &mut position.radius,
+            )
+        };
+        position
+    }
+
+    /// Converts equatorial coordinates of the planet to a global position.
+    pub fn equatorial_to_global(&self, position: &GeoPosition) -> Vector3 {
+        let mut global = oapic_VECTOR3::new();
+        unsafe {
+            oapic_oapiEquToGlobal(
+                self.handle,
+                position.longitude,
+                position.latitude,
+                position.radius,
+                &mut global,
+            )
+        };
+        global.into()
+    }
+
+    /// Converts a global position to the local frame of the planet.
+    ///
+    /// The local frame has its origin at the center of the planet, the y axis
+    /// along the rotation axis and the x axis pointing towards 0° longitude.
+    pub fn global_to_local(&self, global: Vector3) -> Vector3 {
+        let global = oapic_VECTOR3::from(global);
+        let mut local = oapic_VECTOR3::new();
+        unsafe { oapic_oapiGlobalToLocal(self.handle, &global, &mut local) };
+        local.into()
+    }
+
+    /// Converts a position in the local frame of the planet to a global position.
+    pub fn local_to_global(&self, local: Vector3) -> Vector3 {
+        let local = oapic_VECTOR3::from(local);
+        let mut global = oapic_VECTOR3::new();
+        unsafe { oapic_oapiLocalToGlobal(self.handle, &local, &mut global) };
+        global.into()
+    }
+
+    /// Returns the current rotation angle of the planet around its axis, in radians.
+    pub fn current_rotation(&self) -> f64 {
+        unsafe { oapic_oapiGetPlanetCurrentRotation(self.handle) }
+    }
+
+    /// Captures the current rotation parameters of the planet.
+    ///
+    /// The result can be used to convert coordinates at other times, without
+    /// calling into Orbiter.
+    pub fn body_rotation(&self) -> BodyRotation {
+        let rotation = self.current_rotation();
+        BodyRotation {
+            equator: self.rotation_matrix() * rotation_y(rotation).transpose(),
+            period: self.rotation_period(),
+            rotation_at_epoch: rotation,
+            epoch_mjd: unsafe { oapic_oapiGetSimMJD() },
+        }
+    }
+}
+
+#[cfg_attr(windows, link(name = "orbiter_c"))]
+extern "C" {
+    fn oapic_oapiGetSimMJD() -> f64;
+    fn oapic_oapiGlobalToEqu(
+        handle: OBJHANDLE,
+        global: *const oapic_VECTOR3,
+        lng: *mut f64,
+        lat: *mut f64,
+        rad: *mut f64,
+    );
+    fn oapic_oapiEquToGlobal(
+        handle: OBJHANDLE,
+        lng: f64,
+        lat: f64,
+        rad: f64,
+        global: *mut oapic_VECTOR3,
+    );
+    fn oapic_oapiGlobalToLocal(
+        handle: OBJHANDLE,
+        global: *const oapic_VECTOR3,
+        local: *mut oapic_VECTOR3,
+    );
+    fn oapic_oapiLocalToGlobal(
+        handle: OBJHANDLE,
+        local: *const oapic_VECTOR3,
+        global: *mut oapic_VECTOR3,
+    );
+    fn oapic_oapiGetPlanetCurrentRotation(handle: OBJHANDLE) -> f64;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RADIUS: f64 = 6.371e6;
+
+    fn assert_close(a: Vector3, b: Vector3) {
+        assert!((a - b).norm() < 1e-3, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn poles() {
+        let north = GeoPosition::from_local(Vector3::new(0.0, RADIUS, 0.0));
+        assert_eq!(north.latitude, PI / 2.0);
+        assert_eq!(north.radius, RADIUS);
+        let south = GeoPosition::from_local(Vector3::new(0.0, -RADIUS, 0.0));
+        assert_eq!(south.latitude, -PI / 2.0);
+
+        // The longitude does not matter at the poles
+        for longitude in [0.0, 1.0, -2.5] {
+            let pole = GeoPosition::new(PI / 2.0, longitude, RADIUS);
+            assert_close(pole.to_local(), Vector3::new(0.0, RADIUS, 0.0));
+        }
+    }
+
+    #[test]
+    fn longitudes() {
+        let at = |x: f64, z: f64| GeoPosition::from_local(Vector3::new(x, 0.0, z)).longitude;
+        assert_eq!(at(RADIUS, 0.0), 0.0);
+        assert_eq!(at(0.0, RADIUS), PI / 2.0);
+        assert_eq!(at(0.0, -RADIUS), -PI / 2.0);
+        assert_eq!(at(-RADIUS, 0.0), PI);
+
+        // Western longitudes are negative
+        let west = GeoPosition::new(0.3, -80.6f64.to_radians(), RADIUS);
+        let back = GeoPosition::from_local(west.to_local());
+        assert!((back.longitude - west.longitude).abs() < 1e-12);
+        assert!((back.latitude - west.latitude).abs() < 1e-12);
+        assert!(west.to_local().z < 0.0);
+    }
+
+    #[test]
+    fn zero_radius() {
+        let center = GeoPosition::from_local(Vector3::zeros());
+        assert_eq!(center, GeoPosition::new(0.0, 0.0, 0.0));
+        assert_eq!(center.to_local(), Vector3::zeros());
+        assert_eq!(center.altitude(RADIUS), -RADIUS);
+
+        // The center of the body converts without NaN
+        let rotation = BodyRotation::new(0.4, 0.2, 86164.1, 1.0, 51544.5);
+        let body = Vector3::new(1.0e11, 2.0e9, -1.1e11);
+        let geo = rotation.global_to_equatorial(body, body, 51545.0);
+        assert_eq!(geo, GeoPosition::new(0.0, 0.0, 0.0));
+        assert_eq!(rotation.equatorial_to_global(&geo, body, 51545.0), body);
+    }
+
+    #[test]
+    fn rotation_angle() {
+        let period = 86164.1;
+        let rotation = BodyRotation::new(0.0, 0.0, period, 1.0, 51544.5);
+        let quarter = period / 4.0 / SECONDS_PER_DAY;
+        assert!((rotation.rotation_angle(51544.5) - 1.0).abs() < 1e-12);
+        assert!((rotation.rotation_angle(51544.5 + quarter) - (1.0 + PI / 2.0)).abs() < 1e-9);
+
+        // The angle stays between 0 and 2π, also before the epoch
+        let before = rotation.rotation_angle(51544.5 - quarter);
+        assert!((before - (1.0 - PI / 2.0 + 2.0 * PI)).abs() < 1e-9);
+        let later = rotation.rotation_angle(51544.5 + 1000.0 * period / SECONDS_PER_DAY);
+        assert!((later - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn rotation_matrix() {
+        // Without obliquity, the 0° meridian turns from x towards z
+        let period = 86164.1;
+        let rotation = BodyRotation::new(0.0, 0.0, period, 0.0, 51544.5);
+        let mjd = 51544.5 + period / 4.0 / SECONDS_PER_DAY;
+        let prime_meridian = GeoPosition::new(0.0, 0.0, RADIUS);
+        let body = Vector3::new(1.0e11, 0.0, -1.1e11);
+        assert_close(
+            rotation.equatorial_to_global(&prime_meridian, body, mjd),
+            body + Vector3::new(0.0, 0.0, RADIUS),
+        );
+
+        // The rotation axis is tilted by the obliquity from the ecliptic
+        // normal, towards z when the equator node is at 0
+        let obliquity = 23.44f64.to_radians();
+        let tilted = BodyRotation::new(obliquity, 0.0, period, 0.0, 51544.5);
+        let north_pole = GeoPosition::new(PI / 2.0, 0.0, RADIUS);
+        assert_close(
+            tilted.equatorial_to_global(&north_pole, Vector3::zeros(), mjd),
+            RADIUS * Vector3::new(0.0, obliquity.cos(), obliquity.sin()),
+        );
+    }
+}
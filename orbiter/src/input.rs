@@ -1,7 +1,7 @@
+use crate::win::minwindef::{DWORD, UINT, WPARAM};
+use crate::win::winuser;
 use bitflags::bitflags;
 use std::os::raw::c_char;
-use winapi::shared::minwindef::{DWORD, UINT, WPARAM};
-use winapi::um::winuser;
 
 /// Keyboard key.
 #[derive(Debug, Copy, Clone, PartialOrd, PartialEq)]
@@ -437,23 +437,59 @@ impl Key {
 /// Helper methods are provided to check the state of key modifiers
 /// (shift, control and alt).
 pub struct KeyStates<'a> {
-    kstate: &'a mut [c_char],
+    kstate: KeyStatesBuffer<'a>,
+}
+
+enum KeyStatesBuffer<'a> {
+    Borrowed(&'a mut [c_char]),
+    Owned(Box<[c_char]>),
 }
 
 impl<'a> KeyStates<'a> {
     pub(crate) fn from(kstate: *mut c_char) -> Self {
         let slice = unsafe { std::slice::from_raw_parts_mut(kstate, 256) };
-        Self { kstate: slice }
+        Self {
+            kstate: KeyStatesBuffer::Borrowed(slice),
+        }
+    }
+
+    pub(crate) fn reborrow(&mut self) -> KeyStates<'_> {
+        KeyStates {
+            kstate: KeyStatesBuffer::Borrowed(self.buffer_mut()),
+        }
+    }
+
+    /// Returns a copy of the key states that outlives the callback.
+    ///
+    /// Resetting a key on the copy does not prevent Orbiter from processing it.
+    pub fn snapshot(&self) -> KeyStates<'static> {
+        KeyStates {
+            kstate: KeyStatesBuffer::Owned(self.buffer().into()),
+        }
+    }
+
+    fn buffer(&self) -> &[c_char] {
+        match &self.kstate {
+            KeyStatesBuffer::Borrowed(slice) => slice,
+            KeyStatesBuffer::Owned(slice) => slice,
+        }
+    }
+
+    fn buffer_mut(&mut self) -> &mut [c_char] {
+        match &mut self.kstate {
+            KeyStatesBuffer::Borrowed(slice) => slice,
+            KeyStatesBuffer::Owned(slice) => slice,
+        }
     }
 
     /// Check if a key is pressed down
     pub fn is_down(&self, key: Key) -> bool {
-        (self.kstate[key.as_u8() as usize] as u8) & 0x80 != 0
+        (self.buffer()[key.as_u8() as usize] as u8) & 0x80 != 0
     }
 
     /// Prevents default processing of the key
     pub fn reset_key(&mut self, key: Key) {
-        self.kstate[key.as_u8() as usize] = 0;
+        self.buffer_mut()[key.as_u8() as usize] = 0;
     }
 
     /// Check if left shift key is pressed
@@ -0,0 +1,414 @@
+//! Two-body propagation.
+//!
+//! Propagates state vectors along Keplerian orbits by solving Kepler's
+//! equation in universal variables, which handles elliptic, parabolic and
+//! hyperbolic orbits alike, forward or backward in time. Also solves the
+//! inverse problems, i.e. the time to reach a point of an orbit.
+//!
+//! State vectors are those of the [`orbit`] module, in right-handed frames
+//! centered on the central body.
+//!
+//! # Examples
+//!
+//! ```
+//! use orbiter::kepler::KeplerOrbit;
+//! use orbiter::orbit::StateVector;
+//! use orbiter::Vector3;
+//!
+//! const MU_EARTH: f64 = 3.986004418e14;
+//!
+//! // Elliptic orbit with a 7000 km periapsis, at the periapsis at MJD 51544
+//! let state = StateVector::new(Vector3::new(7000e3, 0.0, 0.0), Vector3::new(0.0, 8500.0, 0.0));
+//! let orbit = KeplerOrbit::new(state, MU_EARTH, 51544.0);
+//!
+//! // After one period, the vessel is back at the periapsis
+//! let period = orbit.elements().period().unwrap();
+//! let later = orbit.state_at(51544.0 + period / 86400.0).unwrap();
+//! assert!((later.position - state.position).norm() < 0.1);
+//!
+//! // Half a period later, it is at the apoapsis
+//! let apoapsis = orbit.state_at(51544.0 + period / 2.0 / 86400.0).unwrap();
+//! let time_to_apoapsis = orbit.elements().time_to_apoapsis().unwrap();
+//! assert!((time_to_apoapsis - period / 2.0).abs() < 1e-6);
+//! assert!((apoapsis.position.norm() - orbit.elements().apoapsis().unwrap()).abs() < 0.1);
+//! ```
+//!
+//! [`orbit`]: ../orbit/index.html
+
+use crate::orbit::{normalize_angle, OrbitalElements, StateVector};
+use crate::time::SECONDS_PER_DAY;
+use std::f64::consts::PI;
+
+/// Maximum number of iterations of the Kepler solver
+const MAX_ITERATIONS: usize = 50;
+
+/// Tolerance of the Kepler solver, relative to the time span
+const TOLERANCE: f64 = 1e-12;
+
+/// Error returned when Kepler's equation could not be solved.
+///
+/// This only happens with degenerate inputs, e.g. a null position or a
+/// non-finite time span.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ConvergenceError;
+
+impl std::fmt::Display for ConvergenceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Kepler's equation did not converge")
+    }
+}
+
+impl std::error::Error for ConvergenceError {}
+
+/// A state vector at an epoch, propagated along its Keplerian orbit
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct KeplerOrbit {
+    /// State at the epoch
+    pub state: StateVector,
+    /// Gravitational parameter of the central body, in m³/s²
+    pub mu: f64,
+    /// Epoch of the state, as a Modified Julian Date
+    ///
+    /// Inside Orbiter, use [`Module::sim_mjd`] for the current epoch.
+    ///
+    /// [`Module::sim_mjd`]: ../struct.Module.html#method.sim_mjd
+    pub epoch: f64,
+}
+
+impl KeplerOrbit {
+    /// Creates an orbit from a state vector at an epoch (MJD).
+    pub fn new(state: StateVector, mu: f64, epoch: f64) -> Self {
+        Self { state, mu, epoch }
+    }
+
+    /// Returns the orbital elements at the epoch.
+    pub fn elements(&self) -> OrbitalElements {
+        self.state.to_elements(self.mu)
+    }
+
+    /// Returns the state vector at a date (MJD), before or after the epoch.
+    pub fn state_at(&self, mjd: f64) -> Result<StateVector, ConvergenceError> {
+        self.state
+            .propagate(self.mu, (mjd - self.epoch) * SECONDS_PER_DAY)
+    }
+
+    /// Returns the orbit with its epoch moved to a date (MJD).
+    pub fn at(&self, mjd: f64) -> Result<KeplerOrbit, ConvergenceError> {
+        Ok(Self::new(self.state_at(mjd)?, self.mu, mjd))
+    }
+}
+
+impl StateVector {
+    /// Propagates the state vector by a time span (in s) around a central body
+    /// with the gravitational parameter `mu` (in m³/s²).
+    ///
+    /// The time span is negative to propagate backward.
+    pub fn propagate(&self, mu: f64, dt: f64) -> Result<StateVector, ConvergenceError> {
+        let r0 = self.position.norm();
+        let v0 = self.velocity.norm();
+        let sqrt_mu = mu.sqrt();
+        let vr0 = self.position.dot(&self.velocity) / r0;
+        // Reciprocal of the semi-major axis, negative for hyperbolic orbits
+        let alpha = 2.0 / r0 - v0 * v0 / mu;
+
+        let chi = solve_universal_kepler(r0, vr0, alpha, sqrt_mu, dt)?;
+        let z = alpha * chi * chi;
+        let (c, s) = stumpff(z);
+
+        let f = 1.0 - chi * chi / r0 * c;
+        let g = dt - chi.powi(3) / sqrt_mu * s;
+        let position = f * self.position + g * self.velocity;
+        let r = position.norm();
+
+        let f_dot = sqrt_mu / (r * r0) * (alpha * chi.powi(3) * s - chi);
+        let g_dot = 1.0 - chi * chi / r * c;
+        let velocity = f_dot * self.position + g_dot * self.velocity;
+
+        Ok(StateVector::new(position, velocity))
+    }
+}
+
+impl OrbitalElements {
+    /// Propagates the orbital elements by a time span, in s.
+    pub fn propagate(&self, dt: f64) -> Result<OrbitalElements, ConvergenceError> {
+        Ok(self
+            .to_state_vector()
+            .propagate(self.mu, dt)?
+            .to_elements(self.mu))
+    }
+
+    /// Returns the time (in s) until the orbiting object reaches a true
+    /// anomaly.
+    ///
+    /// Returns `None` if the true anomaly is never reached: on open orbits, if
+    /// it is beyond the asymptotes or was already passed.
+    pub fn time_to_true_anomaly(&self, true_anomaly: f64) -> Option<f64> {
+        let target = Self {
+            true_anomaly,
+            ..*self
+        };
+        let dm = target.mean_anomaly() - self.mean_anomaly();
+        if !dm.is_finite() {
+            return None;
+        }
+
+        if self.is_closed() {
+            Some(normalize_angle(dm) / self.mean_motion())
+        } else if dm >= 0.0 {
+            Some(dm / self.mean_motion())
+        } else {
+            None
+        }
+    }
+
+    /// Returns the time (in s) until the next periapsis passage.
+    ///
+    /// Returns `None` on open orbits once the periapsis was passed.
+    pub fn time_to_periapsis(&self) -> Option<f64> {
+        self.time_to_true_anomaly(0.0)
+    }
+
+    /// Returns the time (in s) until the next apoapsis passage, or `None` if
+    /// the orbit is open.
+    pub fn time_to_apoapsis(&self) -> Option<f64> {
+        if self.is_closed() {
+            self.time_to_true_anomaly(PI)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the time (in s) until the orbiting object is next at a distance
+    /// (in m) from the center of the central body.
+    ///
+    /// Returns `None` if the distance is never reached, e.g. if it is below
+    /// the periapsis. Circular orbits never reach any distance.
+    pub fn time_to_radius(&self, radius: f64) -> Option<f64> {
+        let cos_nu = (self.semi_latus_rectum / radius - 1.0) / self.eccentricity;
+        if !(-1.0..=1.0).contains(&cos_nu) {
+            return None;
+        }
+
+        let nu = cos_nu.acos();
+        let outbound = self.time_to_true_anomaly(nu);
+        let inbound = self.time_to_true_anomaly(2.0 * PI - nu);
+        match (outbound, inbound) {
+            (Some(outbound), Some(inbound)) => Some(outbound.min(inbound)),
+            (outbound, inbound) => outbound.or(inbound),
+        }
+    }
+}
+
+/// Solves Kepler's equation in universal variables for the universal anomaly.
+///
+/// Uses the Laguerre-Conway iteration, which converges from the initial
+/// guesses for all types of orbits.
+fn solve_universal_kepler(
+    r0: f64,
+    vr0: f64,
+    alpha: f64,
+    sqrt_mu: f64,
+    dt: f64,
+) -> Result<f64, ConvergenceError> {
+    if dt == 0.0 {
+        return Ok(0.0);
+    }
+
+    let sigma0 = r0 * vr0 / sqrt_mu;
+    let mut chi = initial_guess(r0, sigma0, alpha, sqrt_mu, dt);
+    for _ in 0..MAX_ITERATIONS {
+        let z = alpha * chi * chi;
+        let (c, s) = stumpff(z);
+
+        let f =
+            sigma0 * chi * chi * c + (1.0 - alpha * r0) * chi.powi(3) * s + r0 * chi - sqrt_mu * dt;
+        let df = sigma0 * chi * (1.0 - z * s) + (1.0 - alpha * r0) * chi * chi * c + r0;
+        // Residual in time, scaled by sqrt(mu)
+        if f.abs() <= TOLERANCE * sqrt_mu * dt.abs() {
+            return Ok(chi);
+        }
+
+        let ddf = sigma0 * (1.0 - z * c) + (1.0 - alpha * r0) * chi * (1.0 - z * s);
+        const N: f64 = 5.0;
+        let root = ((N - 1.0).powi(2) * df * df - N * (N - 1.0) * f * ddf)
+            .abs()
+            .sqrt();
+        let delta = N * f / (df + root.copysign(df));
+        chi -= delta;
+        if !chi.is_finite() {
+            break;
+        }
+    }
+    Err(ConvergenceError)
+}
+
+fn initial_guess(r0: f64, sigma0: f64, alpha: f64, sqrt_mu: f64, dt: f64) -> f64 {
+    if alpha > 0.0 {
+        // Elliptic orbits: the universal anomaly increases by 2π/sqrt(α) every period
+        return sqrt_mu * dt * alpha;
+    }
+
+    // Hyperbolic orbits, from Vallado's Fundamentals of Astrodynamics. The
+    // guess diverges for nearly parabolic orbits.
+    if alpha * r0 < -1e-6 {
+        let a = 1.0 / alpha;
+        let sign = dt.signum();
+        let guess = sign
+            * (-a).sqrt()
+            * ((-2.0 * sqrt_mu * sqrt_mu * alpha * dt)
+                / (sigma0 * sqrt_mu + sign * (-sqrt_mu * sqrt_mu * a).sqrt() * (1.0 - r0 * alpha)))
+                .ln();
+        if guess.is_finite() {
+            return guess;
+        }
+    }
+
+    // Parabolic orbits, and fallback: rectilinear orbit starting at rest
+    (6.0 * sqrt_mu * dt.abs())
+        .cbrt()
+        .min(sqrt_mu * dt.abs() / r0)
+        * dt.signum()
+}
+
+/// Returns the Stumpff functions C(z) and S(z).
+fn stumpff(z: f64) -> (f64, f64) {
+    if z > 1e-3 {
+        let sqrt_z = z.sqrt();
+        (
+            (1.0 - sqrt_z.cos()) / z,
+            (sqrt_z - sqrt_z.sin()) / (sqrt_z * z),
+        )
+    } else if z < -1e-3 {
+        let sqrt_z = (-z).sqrt();
+        (
+            (sqrt_z.cosh() - 1.0) / -z,
+            (sqrt_z.sinh() - sqrt_z) / (sqrt_z * -z),
+        )
+    } else {
+        (
+            1.0 / 2.0 - z / 24.0 + z * z / 720.0 - z.powi(3) / 40320.0,
+            1.0 / 6.0 - z / 120.0 + z * z / 5040.0 - z.powi(3) / 362880.0,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{periapsis_state, MU_EARTH};
+
+    const PERIAPSIS: f64 = 7000e3;
+    const INCLINATION: f64 = 0.6;
+
+    /// Returns the state at the periapsis of an orbit
+    fn state(eccentricity: f64) -> StateVector {
+        periapsis_state(PERIAPSIS, eccentricity, INCLINATION)
+    }
+
+    fn assert_round_trip(state: StateVector, dt: f64) {
+        let there = state.propagate(MU_EARTH, dt).unwrap();
+        let back = there.propagate(MU_EARTH, -dt).unwrap();
+        // Relative to the distance travelled, for long hyperbolic legs
+        let scale = there.position.norm() / state.position.norm();
+        assert!((back.position - state.position).norm() < 1e-9 * scale * PERIAPSIS);
+        assert!((back.velocity - state.velocity).norm() < 1e-9 * scale * state.velocity.norm());
+
+        // The energy and the angular momentum are conserved
+        let energy =
+            |s: &StateVector| s.velocity.norm_squared() / 2.0 - MU_EARTH / s.position.norm();
+        let momentum = |s: &StateVector| s.position.cross(&s.velocity);
+        assert!((energy(&there) - energy(&state)).abs() < 1e-6 * MU_EARTH / PERIAPSIS);
+        assert!((momentum(&there) - momentum(&state)).norm() < 1e-9 * momentum(&state).norm());
+    }
+
+    #[test]
+    fn elliptic_round_trips() {
+        let state = state(0.5);
+        let period = state.to_elements(MU_EARTH).period().unwrap();
+        for dt in [600.0, -600.0, 2.5 * period, -2.5 * period] {
+            assert_round_trip(state, dt);
+        }
+
+        let later = state.propagate(MU_EARTH, -period).unwrap();
+        assert!((later.position - state.position).norm() < 1e-3);
+    }
+
+    #[test]
+    fn parabolic_round_trips() {
+        let state = state(1.0);
+        for dt in [600.0, -600.0, 86400.0, -86400.0] {
+            assert_round_trip(state, dt);
+        }
+
+        // Barker's equation: the true anomaly is 90° when the time since the
+        // periapsis is 2/3 √(p³/μ), with p twice the periapsis radius
+        let p = 2.0 * PERIAPSIS;
+        let dt = 2.0 / 3.0 * (p.powi(3) / MU_EARTH).sqrt();
+        let after = state.propagate(MU_EARTH, dt).unwrap();
+        let before = state.propagate(MU_EARTH, -dt).unwrap();
+        assert!((after.position.norm() - p).abs() < 1e-3);
+        assert!(after.position.dot(&state.position).abs() < 1e-3 * p * p);
+        assert!((before.position.norm() - p).abs() < 1e-3);
+        assert!(before.position.dot(&after.position) < 0.0);
+    }
+
+    #[test]
+    fn hyperbolic_round_trips() {
+        let state = state(3.0);
+        for dt in [600.0, -600.0, 10.0 * 86400.0, -10.0 * 86400.0] {
+            assert_round_trip(state, dt);
+        }
+
+        // Both legs of the hyperbola are symmetric
+        let after = state.propagate(MU_EARTH, 3600.0).unwrap();
+        let before = state.propagate(MU_EARTH, -3600.0).unwrap();
+        assert!((after.position.norm() - before.position.norm()).abs() < 1e-3);
+    }
+
+    #[test]
+    fn time_to_true_anomaly_matches_propagation() {
+        for eccentricity in [0.5, 1.0, 3.0] {
+            let elements = state(eccentricity).to_elements(MU_EARTH);
+            let dt = elements.time_to_true_anomaly(1.0).unwrap();
+            let reached = elements.propagate(dt).unwrap();
+            assert!((reached.true_anomaly - 1.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn time_to_true_anomaly_beyond_the_asymptote() {
+        // The asymptotes of a hyperbola with e = 2 are at ±120°
+        let elements = state(2.0).to_elements(MU_EARTH);
+        assert!((elements.eccentricity - 2.0).abs() < 1e-9);
+
+        assert!(elements.time_to_true_anomaly(110f64.to_radians()).is_some());
+        assert_eq!(elements.time_to_true_anomaly(150f64.to_radians()), None);
+        assert_eq!(elements.time_to_true_anomaly(210f64.to_radians()), None);
+        // Already passed on the inbound leg
+        assert_eq!(elements.time_to_true_anomaly(-30f64.to_radians()), None);
+
+        // The apoapsis is never reached on an open orbit
+        assert_eq!(elements.time_to_apoapsis(), None);
+        assert_eq!(elements.time_to_periapsis(), Some(0.0));
+    }
+
+    #[test]
+    fn time_to_radius() {
+        let elements = state(0.5).to_elements(MU_EARTH);
+        let apoapsis = elements.apoapsis().unwrap();
+
+        assert_eq!(elements.time_to_radius(PERIAPSIS - 1e3), None);
+        assert_eq!(elements.time_to_radius(apoapsis + 1e3), None);
+
+        let radius = (PERIAPSIS + apoapsis) / 2.0;
+        let dt = elements.time_to_radius(radius).unwrap();
+        assert!(dt > 0.0 && dt < elements.time_to_apoapsis().unwrap());
+        let reached = elements.to_state_vector().propagate(MU_EARTH, dt).unwrap();
+        assert!((reached.position.norm() - radius).abs() < 1e-3);
+
+        // Hyperbolic orbits only reach distances above the periapsis
+        let hyperbola = state(3.0).to_elements(MU_EARTH);
+        assert_eq!(hyperbola.time_to_radius(PERIAPSIS / 2.0), None);
+        assert!(hyperbola.time_to_radius(10.0 * PERIAPSIS).is_some());
+    }
+}
@@ -0,0 +1,823 @@
+//! Lambert's problem and transfer planning.
+//!
+//! [`solve`] finds the orbits connecting two positions in a given time,
+//! including multi-revolution transfers, with Izzo's algorithm. A
+//! [`TransferPlanner`] uses it to compute transfers between two objects
+//! orbiting the same central body, and to generate porkchop plots over a
+//! [`TransferWindow`].
+//!
+//! Dates are Modified Julian Dates. Positions and velocities are relative to
+//! the central body, in right-handed frames (see the [`orbit`] module).
+//!
+//! # Examples
+//!
+//! ```
+//! use orbiter::kepler::KeplerOrbit;
+//! use orbiter::lambert::{TransferPlanner, TransferWindow};
+//! use orbiter::orbit::StateVector;
+//! use orbiter::Vector3;
+//!
+//! const MU_SUN: f64 = 1.32712440018e20;
+//! const AU: f64 = 1.495978707e11;
+//!
+//! // Circular orbits of the Earth and Mars
+//! let circular = |radius: f64, angle: f64| {
+//!     let speed = (MU_SUN / radius).sqrt();
+//!     StateVector::new(
+//!         radius * Vector3::new(angle.cos(), angle.sin(), 0.0),
+//!         speed * Vector3::new(-angle.sin(), angle.cos(), 0.0),
+//!     )
+//! };
+//! let earth = KeplerOrbit::new(circular(AU, 0.0), MU_SUN, 51544.0);
+//! let mars = KeplerOrbit::new(circular(1.524 * AU, 0.8), MU_SUN, 51544.0);
+//!
+//! let planner = TransferPlanner::new(earth, mars);
+//! let window = TransferWindow::new(51544.0, 51544.0 + 100.0, 150.0, 350.0);
+//! let transfer = planner.best_transfer(&window).unwrap();
+//! assert!(transfer.total_delta_v() < 12e3);
+//!
+//! let mut csv = Vec::new();
+//! planner.porkchop(&window).write_csv(&mut csv).unwrap();
+//! ```
+//!
+//! [`solve`]: ./fn.solve.html
+//! [`TransferPlanner`]: ./struct.TransferPlanner.html
+//! [`TransferWindow`]: ./struct.TransferWindow.html
+//! [`orbit`]: ../orbit/index.html
+
+use crate::kepler::KeplerOrbit;
+use crate::orbit::StateVector;
+use crate::time::SECONDS_PER_DAY;
+use crate::{to_left_handed, CelestialBodyTrait, ObjectTrait, Vector3, VesselTrait};
+use std::f64::consts::PI;
+use std::io::{self, Write};
+
+/// Maximum number of iterations of the root finders
+const MAX_ITERATIONS: usize = 35;
+
+/// Tolerance of the root finders on the Lagrange parameter x
+const TOLERANCE: f64 = 1e-11;
+
+/// Direction of motion of a transfer around the z axis
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Direction {
+    /// Counter-clockwise when seen from +z, like the planets of the solar
+    /// system
+    Prograde,
+    /// Clockwise when seen from +z
+    Retrograde,
+}
+
+/// Error returned when Lambert's problem is ill-posed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LambertError {
+    /// The time of flight is not positive
+    InvalidTimeOfFlight,
+    /// A position is at the center of the central body, or the positions are
+    /// collinear, which leaves the plane of the transfer undefined
+    InvalidGeometry,
+}
+
+impl std::fmt::Display for LambertError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidTimeOfFlight => f.write_str("the time of flight must be positive"),
+            Self::InvalidGeometry => f.write_str("the transfer plane is undefined"),
+        }
+    }
+}
+
+impl std::error::Error for LambertError {}
+
+/// A solution of Lambert's problem
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LambertSolution {
+    /// Number of complete revolutions of the transfer
+    pub revolutions: u32,
+    /// Velocity at the departure position, in m/s
+    pub departure_velocity: Vector3,
+    /// Velocity at the arrival position, in m/s
+    pub arrival_velocity: Vector3,
+}
+
+/// Solves Lambert's problem: finds the orbits going from `departure` to
+/// `arrival` (positions in m) in `time_of_flight` seconds, around a central
+/// body with the gravitational parameter `mu` (in m³/s²).
+///
+/// Returns the direct solution (less than one revolution), then the two
+/// solutions (long and short period) for each number of complete revolutions
+/// up to `max_revolutions`, when they exist.
+///
+/// # Examples
+///
+/// ```
+/// use orbiter::lambert::{solve, Direction};
+/// use orbiter::orbit::StateVector;
+/// use orbiter::Vector3;
+///
+/// const MU_EARTH: f64 = 3.986004418e14;
+///
+/// let departure = StateVector::new(Vector3::new(7000e3, 0.0, 0.0), Vector3::new(0.0, 8000.0, 500.0));
+/// let arrival = departure.propagate(MU_EARTH, 2000.0).unwrap();
+///
+/// let solutions = solve(MU_EARTH, departure.position, arrival.position, 2000.0, Direction::Prograde, 0).unwrap();
+/// assert_eq!(solutions.len(), 1);
+/// assert!((solutions[0].departure_velocity - departure.velocity).norm() < 1e-3);
+/// assert!((solutions[0].arrival_velocity - arrival.velocity).norm() < 1e-3);
+/// ```
+pub fn solve(
+    mu: f64,
+    departure: Vector3,
+    arrival: Vector3,
+    time_of_flight: f64,
+    direction: Direction,
+    max_revolutions: u32,
+) -> Result<Vec<LambertSolution>, LambertError> {
+    if time_of_flight <= 0.0 {
+        return Err(LambertError::InvalidTimeOfFlight);
+    }
+
+    let chord = (arrival - departure).norm();
+    let r1 = departure.norm();
+    let r2 = arrival.norm();
+    let semiperimeter = (r1 + r2 + chord) / 2.0;
+    let i_r1 = departure / r1;
+    let i_r2 = arrival / r2;
+    let i_h = i_r1.cross(&i_r2);
+    let i_h_norm = i_h.norm();
+    if i_h_norm.is_nan() || i_h_norm <= 1e-12 {
+        return Err(LambertError::InvalidGeometry);
+    }
+    let i_h = i_h / i_h_norm;
+
+    let mut lambda = (1.0 - (chord / semiperimeter).min(1.0)).sqrt();
+    let (mut i_t1, mut i_t2) = if i_h.z < 0.0 {
+        lambda = -lambda;
+        (i_r1.cross(&i_h), i_r2.cross(&i_h))
+    } else {
+        (i_h.cross(&i_r1), i_h.cross(&i_r2))
+    };
+    if direction == Direction::Retrograde {
+        lambda = -lambda;
+        i_t1 = -i_t1;
+        i_t2 = -i_t2;
+    }
+
+    let t = (2.0 * mu / semiperimeter.powi(3)).sqrt() * time_of_flight;
+    let gamma = (mu * semiperimeter / 2.0).sqrt();
+    let rho = (r1 - r2) / chord;
+    let sigma = (1.0 - rho * rho).sqrt();
+
+    let solution = |revolutions, x: f64| {
+        let y = compute_y(x, lambda);
+        let vr1 = gamma * ((lambda * y - x) - rho * (lambda * y + x)) / r1;
+        let vr2 = -gamma * ((lambda * y - x) + rho * (lambda * y + x)) / r2;
+        let vt1 = gamma * sigma * (y + lambda * x) / r1;
+        let vt2 = gamma * sigma * (y + lambda * x) / r2;
+        LambertSolution {
+            revolutions,
+            departure_velocity: vr1 * i_r1 + vt1 * i_t1,
+            arrival_velocity: vr2 * i_r2 + vt2 * i_t2,
+        }
+    };
+
+    let mut solutions = Vec::new();
+    if let Some(x) = householder(initial_guess(t, lambda, 0, true), t, lambda, 0) {
+        solutions.push(solution(0, x));
+    }
+    for revolutions in 1..=max_revolutions.min(max_feasible_revolutions(t, lambda)) {
+        for &low_path in &[true, false] {
+            let x0 = initial_guess(t, lambda, revolutions, low_path);
+            if let Some(x) = householder(x0, t, lambda, revolutions) {
+                solutions.push(solution(revolutions, x));
+            }
+        }
+    }
+    Ok(solutions)
+}
+
+fn compute_y(x: f64, lambda: f64) -> f64 {
+    (1.0 - lambda * lambda * (1.0 - x * x)).sqrt()
+}
+
+fn compute_psi(x: f64, y: f64, lambda: f64) -> f64 {
+    if (-1.0..1.0).contains(&x) {
+        (x * y + lambda * (1.0 - x * x)).clamp(-1.0, 1.0).acos()
+    } else if x > 1.0 {
+        ((y - x * lambda) * (x * x - 1.0).sqrt()).asinh()
+    } else {
+        0.0
+    }
+}
+
+/// Non-dimensional time of flight as a function of the Lagrange parameter x.
+fn time_of_flight(x: f64, lambda: f64, revolutions: u32) -> f64 {
+    let y = compute_y(x, lambda);
+    if revolutions == 0 && 0.6f64.sqrt() < x && x < 1.4f64.sqrt() {
+        // Battin's series near the parabola, where the general expression
+        // loses precision
+        let eta = y - lambda * x;
+        let s1 = (1.0 - lambda - x * eta) / 2.0;
+        let q = 4.0 / 3.0 * hypergeometric(s1);
+        (eta.powi(3) * q + 4.0 * lambda * eta) / 2.0
+    } else {
+        let psi = compute_psi(x, y, lambda);
+        ((psi + revolutions as f64 * PI) / (1.0 - x * x).abs().sqrt() - x + lambda * y)
+            / (1.0 - x * x)
+    }
+}
+
+/// Derivatives of the time of flight with respect to x.
+fn time_of_flight_derivatives(x: f64, t: f64, lambda: f64) -> (f64, f64, f64) {
+    let y = compute_y(x, lambda);
+    let l2 = lambda * lambda;
+    let dt = (3.0 * t * x - 2.0 + 2.0 * lambda.powi(3) * x / y) / (1.0 - x * x);
+    let ddt =
+        (3.0 * t + 5.0 * x * dt + 2.0 * (1.0 - l2) * lambda.powi(3) / y.powi(3)) / (1.0 - x * x);
+    let dddt = (7.0 * x * ddt + 8.0 * dt - 6.0 * (1.0 - l2) * lambda.powi(5) * x / y.powi(5))
+        / (1.0 - x * x);
+    (dt, ddt, dddt)
+}
+
+/// Gauss hypergeometric function 2F1(3, 1, 5/2, z).
+fn hypergeometric(z: f64) -> f64 {
+    if z >= 1.0 {
+        return f64::INFINITY;
+    }
+    let mut result = 1.0;
+    let mut term = 1.0;
+    for i in 0.. {
+        let i = i as f64;
+        term *= (3.0 + i) * (1.0 + i) / (2.5 + i) * z / (i + 1.0);
+        let previous = result;
+        result += term;
+        if result == previous {
+            break;
+        }
+    }
+    result
+}
+
+/// Returns the maximum number of revolutions possible in the time of flight.
+fn max_feasible_revolutions(t: f64, lambda: f64) -> u32 {
+    let mut max = (t / PI).floor() as u32;
+    let t00 = lambda.acos() + lambda * (1.0 - lambda * lambda).sqrt();
+    if max > 0 && t < t00 + max as f64 * PI {
+        // Find the minimum time of flight with `max` revolutions with Halley's method
+        let mut x = 0.1;
+        for _ in 0..MAX_ITERATIONS {
+            let (dt, ddt, dddt) =
+                time_of_flight_derivatives(x, time_of_flight(x, lambda, max), lambda);
+            let step = 2.0 * dt * ddt / (2.0 * ddt * ddt - dt * dddt);
+            x -= step;
+            if step.is_nan() || step.abs() <= TOLERANCE {
+                break;
+            }
+        }
+        if t < time_of_flight(x, lambda, max) {
+            max -= 1;
+        }
+    }
+    max
+}
+
+fn initial_guess(t: f64, lambda: f64, revolutions: u32, low_path: bool) -> f64 {
+    if revolutions == 0 {
+        let t0 = lambda.acos() + lambda * (1.0 - lambda * lambda).sqrt();
+        let t1 = 2.0 * (1.0 - lambda.powi(3)) / 3.0;
+        if t >= t0 {
+            (t0 / t).powf(2.0 / 3.0) - 1.0
+        } else if t < t1 {
+            5.0 / 2.0 * t1 / t * (t1 - t) / (1.0 - lambda.powi(5)) + 1.0
+        } else {
+            (2f64.ln() * (t / t0).ln() / (t1 / t0).ln()).exp() - 1.0
+        }
+    } else {
+        let m = revolutions as f64 * PI;
+        let left = ((m + PI) / (8.0 * t)).powf(2.0 / 3.0);
+        let right = ((8.0 * t) / m).powf(2.0 / 3.0);
+        let x_left = (left - 1.0) / (left + 1.0);
+        let x_right = (right - 1.0) / (right + 1.0);
+        if low_path {
+            x_left.max(x_right)
+        } else {
+            x_left.min(x_right)
+        }
+    }
+}
+
+/// Finds the x giving the time of flight with Householder's method.
+fn householder(mut x: f64, t: f64, lambda: f64, revolutions: u32) -> Option<f64> {
+    for _ in 0..MAX_ITERATIONS {
+        let tof = time_of_flight(x, lambda, revolutions);
+        let f = tof - t;
+        let (dt, ddt, dddt) = time_of_flight_derivatives(x, tof, lambda);
+        let step = f * (dt * dt - f * ddt / 2.0) / (dt * (dt * dt - f * ddt) + dddt * f * f / 6.0);
+        x -= step;
+        if !x.is_finite() {
+            return None;
+        }
+        if step.abs() < TOLERANCE {
+            return Some(x);
+        }
+    }
+    None
+}
+
+/// A transfer between two objects
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Transfer {
+    /// Date of the departure (MJD)
+    pub departure_mjd: f64,
+    /// Date of the arrival (MJD)
+    pub arrival_mjd: f64,
+    /// Solution of Lambert's problem of the transfer
+    pub solution: LambertSolution,
+    /// State of the departure object at the departure
+    pub departure_state: StateVector,
+    /// State of the arrival object at the arrival
+    pub arrival_state: StateVector,
+    /// Velocity change to enter the transfer orbit, in m/s
+    pub departure_delta_v: Vector3,
+    /// Velocity change to match the velocity of the arrival object, in m/s
+    pub arrival_delta_v: Vector3,
+}
+
+impl Transfer {
+    fn new(
+        departure_mjd: f64,
+        arrival_mjd: f64,
+        solution: LambertSolution,
+        departure_state: StateVector,
+        arrival_state: StateVector,
+    ) -> Self {
+        Self {
+            departure_mjd,
+            arrival_mjd,
+            solution,
+            departure_state,
+            arrival_state,
+            departure_delta_v: solution.departure_velocity - departure_state.velocity,
+            arrival_delta_v: arrival_state.velocity - solution.arrival_velocity,
+        }
+    }
+
+    /// Returns the time of flight, in days.
+    pub fn flight_time(&self) -> f64 {
+        self.arrival_mjd - self.departure_mjd
+    }
+
+    /// Returns the total velocity change of the transfer, in m/s.
+    pub fn total_delta_v(&self) -> f64 {
+        self.departure_delta_v.norm() + self.arrival_delta_v.norm()
+    }
+
+    /// Returns the departure velocity change in the orbital frame of the
+    /// departure object, as (prograde, normal, radial) components.
+    ///
+    /// See [`StateVector::orbital_frame`].
+    ///
+    /// [`StateVector::orbital_frame`]: ../orbit/struct.StateVector.html#method.orbital_frame
+    pub fn departure_delta_v_orbital(&self) -> Vector3 {
+        self.departure_state.orbital_frame().inverse() * self.departure_delta_v
+    }
+
+    /// Returns the arrival velocity change in the orbital frame of the
+    /// arrival object, as (prograde, normal, radial) components.
+    pub fn arrival_delta_v_orbital(&self) -> Vector3 {
+        self.arrival_state.orbital_frame().inverse() * self.arrival_delta_v
+    }
+
+    /// Returns the departure velocity change in the local frame of a vessel,
+    /// with its current attitude.
+    ///
+    /// The velocity change must be expressed in the ecliptic frame, as when
+    /// the planner was created with [`TransferPlanner::from_objects`]. The
+    /// result uses Orbiter's left-handed convention, like the other vessel
+    /// frames.
+    ///
+    /// [`TransferPlanner::from_objects`]: ./struct.TransferPlanner.html#method.from_objects
+    pub fn departure_delta_v_local(&self, vessel: &dyn VesselTrait) -> Vector3 {
+        vessel.global_to_local_direction(to_left_handed(self.departure_delta_v))
+    }
+
+    /// Returns the arrival velocity change in the local frame of a vessel,
+    /// with its current attitude.
+    ///
+    /// See [`departure_delta_v_local`].
+    ///
+    /// [`departure_delta_v_local`]: #method.departure_delta_v_local
+    pub fn arrival_delta_v_local(&self, vessel: &dyn VesselTrait) -> Vector3 {
+        vessel.global_to_local_direction(to_left_handed(self.arrival_delta_v))
+    }
+}
+
+/// Range of departure dates and flight times explored by a
+/// [`TransferPlanner`]
+///
+/// [`TransferPlanner`]: ./struct.TransferPlanner.html
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TransferWindow {
+    /// Earliest departure date (MJD)
+    pub earliest_departure: f64,
+    /// Latest departure date (MJD)
+    pub latest_departure: f64,
+    /// Shortest flight time, in days
+    pub min_flight_time: f64,
+    /// Longest flight time, in days
+    pub max_flight_time: f64,
+    /// Number of departure dates of the grid
+    pub departure_steps: usize,
+    /// Number of flight times of the grid
+    pub flight_time_steps: usize,
+}
+
+impl TransferWindow {
+    /// Creates a window with a 50 × 50 grid.
+    pub fn new(
+        earliest_departure: f64,
+        latest_departure: f64,
+        min_flight_time: f64,
+        max_flight_time: f64,
+    ) -> Self {
+        Self {
+            earliest_departure,
+            latest_departure,
+            min_flight_time,
+            max_flight_time,
+            departure_steps: 50,
+            flight_time_steps: 50,
+        }
+    }
+
+    /// Returns the departure dates of the grid.
+    pub fn departures(&self) -> Vec<f64> {
+        grid(
+            self.earliest_departure,
+            self.latest_departure,
+            self.departure_steps,
+        )
+    }
+
+    /// Returns the flight times of the grid.
+    pub fn flight_times(&self) -> Vec<f64> {
+        grid(
+            self.min_flight_time,
+            self.max_flight_time,
+            self.flight_time_steps,
+        )
+    }
+}
+
+fn grid(start: f64, end: f64, steps: usize) -> Vec<f64> {
+    match steps {
+        0 => Vec::new(),
+        1 => vec![start],
+        _ => (0..steps)
+            .map(|i| start + (end - start) * i as f64 / (steps - 1) as f64)
+            .collect(),
+    }
+}
+
+/// Total velocity change of the transfers over a grid of departure dates and
+/// flight times
+pub struct Porkchop {
+    /// Departure dates (MJD)
+    pub departures: Vec<f64>,
+    /// Flight times, in days
+    pub flight_times: Vec<f64>,
+    /// Total velocity change in m/s, indexed by departure then flight time
+    ///
+    /// `None` where no transfer was found.
+    pub delta_v: Vec<Vec<Option<f64>>>,
+}
+
+impl Porkchop {
+    /// Returns the departure date, flight time and total velocity change of
+    /// the cheapest transfer of the grid.
+    pub fn best(&self) -> Option<(f64, f64, f64)> {
+        let mut best: Option<(f64, f64, f64)> = None;
+        for (departure, row) in self.departures.iter().zip(&self.delta_v) {
+            for (flight_time, delta_v) in self.flight_times.iter().zip(row) {
+                if let Some(delta_v) = *delta_v {
+                    if best.map_or(true, |(_, _, best)| delta_v < best) {
+                        best = Some((*departure, *flight_time, delta_v));
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    /// Writes the grid as CSV, with one line per departure date and flight
+    /// time.
+    ///
+    /// The columns are `departure_mjd`, `flight_time_days` and `delta_v`. The
+    /// velocity change is empty where no transfer was found.
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "departure_mjd,flight_time_days,delta_v")?;
+        for (departure, row) in self.departures.iter().zip(&self.delta_v) {
+            for (flight_time, delta_v) in self.flight_times.iter().zip(row) {
+                match delta_v {
+                    Some(delta_v) => writeln!(writer, "{},{},{}", departure, flight_time, delta_v)?,
+                    None => writeln!(writer, "{},{},", departure, flight_time)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Planner of transfers between two objects orbiting the same central body
+///
+/// The objects are assumed to follow Keplerian orbits from their state at the
+/// epoch, which is accurate enough for planning but ignores perturbations.
+pub struct TransferPlanner {
+    departure: KeplerOrbit,
+    arrival: KeplerOrbit,
+    direction: Direction,
+    max_revolutions: u32,
+}
+
+impl TransferPlanner {
+    /// Creates a planner for prograde single-revolution transfers between two
+    /// orbits around the same central body.
+    pub fn new(departure: KeplerOrbit, arrival: KeplerOrbit) -> Self {
+        Self {
+            departure,
+            arrival,
+            direction: Direction::Prograde,
+            max_revolutions: 0,
+        }
+    }
+
+    /// Creates a planner from the current states of objects of the
+    /// simulation around a central body, e.g. two planets around the sun.
+    ///
+    /// The epoch `mjd` is the current simulation date, see
+    /// [`Module::sim_mjd`]. The vectors are in the right-handed ecliptic frame
+    /// of [`StateVector::relative`].
+    ///
+    /// [`Module::sim_mjd`]: ../struct.Module.html#method.sim_mjd
+    /// [`StateVector::relative`]: ../orbit/struct.StateVector.html#method.relative
+    pub fn from_objects<B: CelestialBodyTrait>(
+        departure: &dyn ObjectTrait,
+        arrival: &dyn ObjectTrait,
+        central_body: &B,
+        mjd: f64,
+    ) -> Self {
+        let mu = central_body.gravitational_parameter();
+        Self::new(
+            KeplerOrbit::new(StateVector::relative(departure, central_body), mu, mjd),
+            KeplerOrbit::new(StateVector::relative(arrival, central_body), mu, mjd),
+        )
+    }
+
+    /// Sets the direction of the transfers.
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Sets the maximum number of complete revolutions of the transfers.
+    pub fn max_revolutions(mut self, max_revolutions: u32) -> Self {
+        self.max_revolutions = max_revolutions;
+        self
+    }
+
+    /// Returns the cheapest transfer departing and arriving at the given
+    /// dates (MJD), or `None` if there is none.
+    pub fn transfer(&self, departure_mjd: f64, arrival_mjd: f64) -> Option<Transfer> {
+        let departure_state = self.departure.state_at(departure_mjd).ok()?;
+        let arrival_state = self.arrival.state_at(arrival_mjd).ok()?;
+        solve(
+            self.departure.mu,
+            departure_state.position,
+            arrival_state.position,
+            (arrival_mjd - departure_mjd) * SECONDS_PER_DAY,
+            self.direction,
+            self.max_revolutions,
+        )
+        .ok()?
+        .into_iter()
+        .map(|solution| {
+            Transfer::new(
+                departure_mjd,
+                arrival_mjd,
+                solution,
+                departure_state,
+                arrival_state,
+            )
+        })
+        .min_by(|a, b| a.total_delta_v().total_cmp(&b.total_delta_v()))
+    }
+
+    /// Computes the total velocity change of the transfers over the grid of a
+    /// window.
+    pub fn porkchop(&self, window: &TransferWindow) -> Porkchop {
+        let departures = window.departures();
+        let flight_times = window.flight_times();
+        let delta_v = departures
+            .iter()
+            .map(|&departure| {
+                flight_times
+                    .iter()
+                    .map(|&flight_time| {
+                        self.transfer(departure, departure + flight_time)
+                            .map(|transfer| transfer.total_delta_v())
+                    })
+                    .collect()
+            })
+            .collect();
+        Porkchop {
+            departures,
+            flight_times,
+            delta_v,
+        }
+    }
+
+    /// Returns the cheapest transfer over the grid of a window.
+    pub fn best_transfer(&self, window: &TransferWindow) -> Option<Transfer> {
+        let (departure, flight_time, _) = self.porkchop(window).best()?;
+        self.transfer(departure, departure + flight_time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{periapsis_state, MU_EARTH};
+
+    /// Elliptic orbit between 7000 and 10500 km, in the xy plane
+    fn departure() -> StateVector {
+        periapsis_state(7000e3, 0.2, 0.0)
+    }
+
+    /// Checks that a solution goes from the departure to the arrival.
+    fn assert_reaches(
+        solution: &LambertSolution,
+        departure: Vector3,
+        arrival: &StateVector,
+        time_of_flight: f64,
+    ) {
+        let state = StateVector::new(departure, solution.departure_velocity);
+        let end = state.propagate(MU_EARTH, time_of_flight).unwrap();
+        assert!(
+            (end.position - arrival.position).norm() < 1.0,
+            "{:?}",
+            solution
+        );
+        assert!((end.velocity - solution.arrival_velocity).norm() < 1e-3);
+    }
+
+    #[test]
+    fn multi_revolution() {
+        let departure = departure();
+        let period = departure.to_elements(MU_EARTH).period().unwrap();
+        for (periods, expected) in [(1.3, vec![0, 1, 1]), (2.3, vec![0, 1, 1, 2, 2])] {
+            let time_of_flight = periods * period;
+            let arrival = departure.propagate(MU_EARTH, time_of_flight).unwrap();
+            let solutions = solve(
+                MU_EARTH,
+                departure.position,
+                arrival.position,
+                time_of_flight,
+                Direction::Prograde,
+                3,
+            )
+            .unwrap();
+
+            let revolutions: Vec<u32> = solutions
+                .iter()
+                .map(|solution| solution.revolutions)
+                .collect();
+            assert_eq!(revolutions, expected);
+            for solution in &solutions {
+                assert_reaches(solution, departure.position, &arrival, time_of_flight);
+            }
+
+            // The actual orbit is one of the solutions
+            let revolutions = periods.floor() as u32;
+            assert!(solutions
+                .iter()
+                .any(|solution| solution.revolutions == revolutions
+                    && (solution.departure_velocity - departure.velocity).norm() < 1e-3));
+        }
+    }
+
+    #[test]
+    fn revolutions_are_limited() {
+        let departure = departure();
+        let time_of_flight = 2.3 * departure.to_elements(MU_EARTH).period().unwrap();
+        let arrival = departure.propagate(MU_EARTH, time_of_flight).unwrap();
+        let solutions = solve(
+            MU_EARTH,
+            departure.position,
+            arrival.position,
+            time_of_flight,
+            Direction::Prograde,
+            1,
+        )
+        .unwrap();
+        let revolutions: Vec<u32> = solutions
+            .iter()
+            .map(|solution| solution.revolutions)
+            .collect();
+        assert_eq!(revolutions, vec![0, 1, 1]);
+    }
+
+    #[test]
+    fn retrograde() {
+        let prograde = departure();
+        let departure = StateVector::new(prograde.position, -prograde.velocity);
+        let time_of_flight = 2000.0;
+        let arrival = departure.propagate(MU_EARTH, time_of_flight).unwrap();
+
+        let solutions = solve(
+            MU_EARTH,
+            departure.position,
+            arrival.position,
+            time_of_flight,
+            Direction::Retrograde,
+            0,
+        )
+        .unwrap();
+        assert_eq!(solutions.len(), 1);
+        assert!((solutions[0].departure_velocity - departure.velocity).norm() < 1e-3);
+        assert_reaches(&solutions[0], departure.position, &arrival, time_of_flight);
+
+        // The prograde transfer goes the other way around
+        let solutions = solve(
+            MU_EARTH,
+            departure.position,
+            arrival.position,
+            time_of_flight,
+            Direction::Prograde,
+            0,
+        )
+        .unwrap();
+        assert_eq!(solutions.len(), 1);
+        assert!(departure.position.cross(&solutions[0].departure_velocity).z > 0.0);
+        assert_reaches(&solutions[0], departure.position, &arrival, time_of_flight);
+    }
+
+    #[test]
+    fn invalid_problems() {
+        let r1 = Vector3::new(7000e3, 0.0, 0.0);
+        let r2 = Vector3::new(0.0, 8000e3, 0.0);
+        for time_of_flight in [0.0, -100.0] {
+            assert_eq!(
+                solve(MU_EARTH, r1, r2, time_of_flight, Direction::Prograde, 0),
+                Err(LambertError::InvalidTimeOfFlight)
+            );
+        }
+        // Collinear positions and a position at the center
+        for r2 in [2.0 * r1, -r1, Vector3::zeros()] {
+            assert_eq!(
+                solve(MU_EARTH, r1, r2, 1000.0, Direction::Prograde, 0),
+                Err(LambertError::InvalidGeometry)
+            );
+        }
+    }
+
+    #[test]
+    fn grids() {
+        assert_eq!(grid(1.0, 2.0, 0), Vec::<f64>::new());
+        assert_eq!(grid(1.0, 2.0, 1), vec![1.0]);
+        assert_eq!(grid(1.0, 2.0, 5), vec![1.0, 1.25, 1.5, 1.75, 2.0]);
+
+        let window = TransferWindow {
+            departure_steps: 3,
+            flight_time_steps: 2,
+            ..TransferWindow::new(100.0, 110.0, 5.0, 6.0)
+        };
+        assert_eq!(window.departures(), vec![100.0, 105.0, 110.0]);
+        assert_eq!(window.flight_times(), vec![5.0, 6.0]);
+    }
+
+    #[test]
+    fn porkchop_best() {
+        let porkchop = Porkchop {
+            departures: vec![100.0, 105.0],
+            flight_times: vec![5.0, 6.0, 7.0],
+            delta_v: vec![
+                vec![Some(3000.0), None, Some(2500.0)],
+                vec![None, Some(2000.0), Some(f64::INFINITY)],
+            ],
+        };
+        assert_eq!(porkchop.best(), Some((105.0, 6.0, 2000.0)));
+
+        let mut csv = Vec::new();
+        porkchop.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv.lines().count(), 7);
+        assert!(csv.contains("\n100,6,\n"));
+
+        let empty = Porkchop {
+            departures: vec![100.0],
+            flight_times: vec![5.0],
+            delta_v: vec![vec![None]],
+        };
+        assert_eq!(empty.best(), None);
+    }
+}
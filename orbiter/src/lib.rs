@@ -1,10 +1,36 @@
 use std::ffi::CString;
 use std::os::raw::{c_char, c_int};
 
+mod atmosphere;
+mod base;
+mod celestial;
+pub mod config;
+mod event;
+mod executor;
+#[cfg(test)]
+mod fixtures;
+pub mod frame;
+mod geo;
 mod input;
+pub mod kepler;
+pub mod lambert;
+pub mod maneuver;
 mod module;
+mod nav;
+pub mod nbody;
 mod object;
+pub mod orbit;
+mod query;
+pub mod rendezvous;
+mod snapshot;
+mod terrain;
+mod thruster;
+pub mod time;
+pub mod trajectory;
+#[cfg(feature = "units")]
+pub mod units;
 mod vessel;
+mod win;
 
 /// Defines the required functions to make your DLL available to Orbiter.
 ///
@@ -12,13 +38,19 @@ mod vessel;
 /// when Orbiter loads your DLL. It provides a [`InstanceHandle`] that you can
 /// use to register a module, a MFD, etc. The `exit` function get called when
 /// Orbiter unloads your DLL. It should contain cleanup code, such as releasing
-/// resources and unregistering MFDs, etc.
+/// resources and unregistering MFDs, etc. The loaded [`Config`] files are
+/// saved after it.
 ///
 /// **You must call this macro once at the root of your crate.**
 ///
+/// The [`plugin`] attribute is an alternative to this macro which supports
+/// error handling and automatic module registration.
+///
 /// # Examples
 ///
 /// ```
+/// use orbiter::init;
+///
 /// init!(
 ///     fn init(instance) {
 ///         println!("Hello from Rust!");
@@ -33,6 +65,8 @@ mod vessel;
 /// ```
 ///
 /// [`InstanceHandle`]: ./struct.InstanceHandle.html
+/// [`Config`]: ./config/struct.Config.html
+/// [`plugin`]: ./attr.plugin.html
 #[macro_export]
 macro_rules! init {
     ( fn init($module_init_ident:ident) $body_init:block fn exit($module_exit_ident:ident) $body_exit:block) => {
@@ -46,18 +80,22 @@ macro_rules! init {
         #[no_mangle]
         pub unsafe extern "C" fn InitModule(module: orbiter::HINSTANCE) {
             let mut $module_init_ident = orbiter::InstanceHandle::from(module);
-            $body_init
+            $module_init_ident.track_vessel_deletions();
+            orbiter::catch_panic("Plugin initialization", || $body_init);
         }
 
         // This is called before the module is unloaded by Orbiter
         #[no_mangle]
         pub extern "C" fn ExitModule(module: orbiter::HINSTANCE) {
             let mut $module_exit_ident = orbiter::InstanceHandle::from(module);
-            $body_exit
+            orbiter::catch_panic("Plugin cleanup", || $body_exit);
+            orbiter::catch_panic("Configuration saving", orbiter::config::save_loaded);
         }
     };
 }
 
+pub use orbiter_macros::plugin;
+
 lazy_static::lazy_static! {
     static ref BUILD_DATE: CString = CString::new(env!("ORBITER_DATE")).unwrap();
 }
@@ -72,8 +110,48 @@ pub fn get_module_date() -> *const std::os::raw::c_char {
     BUILD_DATE.as_ptr()
 }
 
+/// Result of the functions marked with the [`plugin`] attribute.
+///
+/// [`plugin`]: ./attr.plugin.html
+#[doc(hidden)]
+pub trait PluginResult {
+    fn into_plugin_result(self) -> Result<(), String>;
+}
+
+impl PluginResult for () {
+    fn into_plugin_result(self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+impl<E: std::fmt::Display> PluginResult for Result<(), E> {
+    fn into_plugin_result(self) -> Result<(), String> {
+        self.map_err(|err| err.to_string())
+    }
+}
+
+/// Calls a function from an entry point called by Orbiter, logging its panic
+/// instead of unwinding into Orbiter, which would abort the simulator.
+///
+/// Returns `None` if the function panicked.
+#[doc(hidden)]
+pub fn catch_panic<R>(context: &str, f: impl FnOnce() -> R) -> Option<R> {
+    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)) {
+        Ok(result) => Some(result),
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("unknown error");
+            write_log(&format!("{} panicked: {}", context, message));
+            None
+        }
+    }
+}
+
 /// Instance handle from the Windows API
-pub use winapi::shared::minwindef::HINSTANCE;
+pub use crate::win::minwindef::HINSTANCE;
 
 #[derive(Copy, Clone, PartialEq)]
 pub struct InstanceHandle(HINSTANCE);
@@ -88,9 +166,86 @@ impl InstanceHandle {
         self.0
     }
 
-    pub fn register_module<M: module::ModuleCallbacks + 'static>(&mut self, module: M) {
+    /// Registers the module tracking vessel deletions for [`Vessel::is_valid`],
+    /// once per DLL. Called by `InitModule`.
+    ///
+    /// [`Vessel::is_valid`]: ./struct.Vessel.html#method.is_valid
+    #[doc(hidden)]
+    pub fn track_vessel_deletions(&mut self) {
+        vessel::track_deletions(self);
+    }
+
+    /// Registers a module that receives the callbacks of the simulation.
+    ///
+    /// Multiple modules can be registered by the same DLL. The returned handle
+    /// can be used to unregister or disable the module later, and to access
+    /// the callbacks value.
+    pub fn register_module<M: module::ModuleCallbacks + 'static>(
+        &mut self,
+        module: M,
+    ) -> ModuleRegistration<M> {
         module::ModuleAdapter::new(self, module)
     }
+
+    /// Registers a module that delivers all its callbacks to a single function.
+    ///
+    /// The returned [`Response`] tells Orbiter whether mouse and keyboard events
+    /// were consumed. It is ignored for the other events.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn example(instance: &mut orbiter::InstanceHandle) {
+    /// use orbiter::{Key, ModuleEvent, Response};
+    ///
+    /// instance.register_event_handler(|_module, event| match event {
+    ///     ModuleEvent::KeyboardBuffered { key: Key::F9, .. } => Response::Consumed,
+    ///     _ => Response::Ignored,
+    /// });
+    /// # }
+    /// ```
+    ///
+    /// [`Response`]: ./enum.Response.html
+    pub fn register_event_handler<F>(&mut self, handler: F) -> ModuleRegistration<EventHandler<F>>
+    where
+        F: FnMut(&mut Module, ModuleEvent) -> Response + 'static,
+    {
+        self.register_module(event::EventHandler::new(handler))
+    }
+
+    /// Registers a module that queues its events in a channel holding up to
+    /// `capacity` events.
+    ///
+    /// The events can then be consumed in order by iterating over the receiver,
+    /// for example with [`Receiver::try_iter`]. Keyboard states are copied, so
+    /// mouse and keyboard events are never reported as consumed.
+    ///
+    /// While the channel is full, e.g. when the receiver is not polled, the
+    /// events sent every frame ([`PreStep`], [`PostStep`] and
+    /// [`KeyboardImmediate`]) are dropped. Up to `capacity` other events are
+    /// kept aside and sent in order once there is room again. Beyond that, the
+    /// oldest ones are dropped and their number is written to the Orbiter log.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is 0.
+    ///
+    /// [`Receiver::try_iter`]: https://doc.rust-lang.org/std/sync/mpsc/struct.Receiver.html#method.try_iter
+    /// [`PreStep`]: ./enum.ModuleEvent.html#variant.PreStep
+    /// [`PostStep`]: ./enum.ModuleEvent.html#variant.PostStep
+    /// [`KeyboardImmediate`]: ./enum.ModuleEvent.html#variant.KeyboardImmediate
+    pub fn register_event_channel(
+        &mut self,
+        capacity: usize,
+    ) -> std::sync::mpsc::Receiver<ModuleEvent<'static>> {
+        assert!(
+            capacity > 0,
+            "the capacity of the event channel must not be 0"
+        );
+        let (sender, receiver) = std::sync::mpsc::sync_channel(capacity);
+        self.register_event_handler(event::channel_handler(sender, capacity));
+        receiver
+    }
 }
 
 /// Displays a string in the lower left corner of the viewport.
@@ -106,11 +261,11 @@ impl InstanceHandle {
 ///
 /// # Examples
 ///
-/// ```
+/// ```no_run
 /// use orbiter::debug_string;
 ///
 /// let some_value = 42;
-/// debug_string!("This is my value: {}", 42);
+/// debug_string!("This is my value: {}", some_value);
 /// ```
 ///
 /// [`format!`]: https://doc.rust-lang.org/std/fmt/index.html
@@ -135,6 +290,15 @@ pub fn _debug_string(mut text: String) {
     }
 }
 
+/// Writes a line to the Orbiter log file (`Orbiter.log`).
+///
+/// This should be used to report errors and other useful information
+/// to the end-user.
+pub fn write_log(message: &str) {
+    let encoded = CString::new(message.replace('\0', "")).unwrap();
+    unsafe { oapic_oapiWriteLog(encoded.as_ptr()) };
+}
+
 /// Returns the version number of the Orbiter core system.
 ///
 /// Orbiter version numbers are derived from the build date.
@@ -161,18 +325,34 @@ pub fn orbiter_instance() -> HINSTANCE {
     unsafe { oapic_oapiGetOrbiterInstance() }
 }
 
-#[link(name = "orbiter_c")]
+/// Returns the time acceleration factor of the simulation.
+pub fn time_acceleration() -> f64 {
+    unsafe { oapic_oapiGetTimeAcceleration() }
+}
+
+/// Sets the time acceleration factor of the simulation.
+///
+/// Orbiter clamps the factor between 0.1 and 100000.
+pub fn set_time_acceleration(warp: f64) {
+    unsafe { oapic_oapiSetTimeAcceleration(warp) };
+}
+
+#[cfg_attr(windows, link(name = "orbiter_c"))]
 extern "C" {
     fn oapic_dummy();
     fn oapic_oapiGetOrbiterVersion() -> c_int;
     fn oapic_oapiGetModuleVersion() -> c_int;
     fn oapic_oapiGetOrbiterInstance() -> HINSTANCE;
     fn oapic_oapiDebugString() -> *mut c_char;
+    fn oapic_oapiWriteLog(line: *const c_char);
+    fn oapic_oapiGetTimeAcceleration() -> f64;
+    fn oapic_oapiSetTimeAcceleration(warp: f64);
 }
 
 pub type Vector3 = nalgebra::Vector3<f64>;
 
 #[repr(C)]
+#[derive(Copy, Clone)]
 struct oapic_VECTOR3 {
     x: f64,
     y: f64,
@@ -195,19 +375,69 @@ impl From<oapic_VECTOR3> for Vector3 {
     }
 }
 
-pub use crate::input::*;
-pub use crate::module::*;
-pub use crate::object::*;
-pub use crate::vessel::*;
+impl From<Vector3> for oapic_VECTOR3 {
+    fn from(from: Vector3) -> Self {
+        Self {
+            x: from.x,
+            y: from.y,
+            z: from.z,
+        }
+    }
+}
 
-pub struct Star {
-    handle: OBJHANDLE,
+pub type Matrix3 = nalgebra::Matrix3<f64>;
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct oapic_MATRIX3 {
+    m11: f64,
+    m12: f64,
+    m13: f64,
+    m21: f64,
+    m22: f64,
+    m23: f64,
+    m31: f64,
+    m32: f64,
+    m33: f64,
 }
 
-pub struct Planet {
-    handle: OBJHANDLE,
+impl oapic_MATRIX3 {
+    fn new() -> Self {
+        Self {
+            m11: 0.0,
+            m12: 0.0,
+            m13: 0.0,
+            m21: 0.0,
+            m22: 0.0,
+            m23: 0.0,
+            m31: 0.0,
+            m32: 0.0,
+            m33: 0.0,
+        }
+    }
 }
 
-pub struct SurfaceBase {
-    handle: OBJHANDLE,
+impl From<oapic_MATRIX3> for Matrix3 {
+    fn from(from: oapic_MATRIX3) -> Self {
+        Matrix3::new(
+            from.m11, from.m12, from.m13, from.m21, from.m22, from.m23, from.m31, from.m32,
+            from.m33,
+        )
+    }
 }
+
+pub use crate::atmosphere::*;
+pub use crate::base::*;
+pub use crate::celestial::*;
+pub use crate::event::*;
+pub use crate::executor::*;
+pub use crate::geo::*;
+pub use crate::input::*;
+pub use crate::module::*;
+pub use crate::nav::*;
+pub use crate::object::*;
+pub use crate::query::*;
+pub use crate::snapshot::*;
+pub use crate::terrain::*;
+pub use crate::thruster::*;
+pub use crate::vessel::*;
@@ -0,0 +1,592 @@
+//! Maneuvers for common orbit changes.
+//!
+//! The functions of this module plan sequences of impulsive burns from a
+//! [`KeplerOrbit`], whose epoch is usually the current simulation date (see
+//! [`Module::sim_mjd`]). Each [`Burn`] gives its time, the velocity change in
+//! the orbital frame of the vessel and the orbital elements after the burn.
+//!
+//! The orbital frame follows the conventions of the [`orbit`] module, so
+//! velocity changes must be converted with [`to_left_handed`] before they
+//! are compared with vessel data.
+//!
+//! # Examples
+//!
+//! ```
+//! use orbiter::kepler::KeplerOrbit;
+//! use orbiter::maneuver;
+//! use orbiter::orbit::StateVector;
+//! use orbiter::Vector3;
+//!
+//! const MU_EARTH: f64 = 3.986004418e14;
+//!
+//! // From a circular low orbit to the geostationary radius
+//! let radius = 6_678e3;
+//! let state = StateVector::new(
+//!     Vector3::new(radius, 0.0, 0.0),
+//!     Vector3::new(0.0, (MU_EARTH / radius).sqrt(), 0.0),
+//! );
+//! let orbit = KeplerOrbit::new(state, MU_EARTH, 51544.0);
+//!
+//! let transfer = maneuver::hohmann(&orbit, 42_164e3).unwrap();
+//! assert_eq!(transfer.burns.len(), 2);
+//! assert!((transfer.total_delta_v() - 3_893.0).abs() < 1.0);
+//!
+//! let last = transfer.final_elements().unwrap();
+//! assert!((last.semi_major_axis() - 42_164e3).abs() < 1.0);
+//! assert!(last.eccentricity < 1e-9);
+//! ```
+//!
+//! [`KeplerOrbit`]: ../kepler/struct.KeplerOrbit.html
+//! [`Module::sim_mjd`]: ../struct.Module.html#method.sim_mjd
+//! [`Burn`]: ./struct.Burn.html
+//! [`orbit`]: ../orbit/index.html
+//! [`to_left_handed`]: ../fn.to_left_handed.html
+
+use crate::kepler::{ConvergenceError, KeplerOrbit};
+use crate::orbit::{angle_between, normalize_angle, OrbitalElements, StateVector};
+use crate::time::SECONDS_PER_DAY;
+use crate::Vector3;
+use nalgebra::{Rotation3, Unit};
+use std::f64::consts::PI;
+
+/// Eccentricity under which an orbit is considered circular, so that a burn
+/// at an apsis can happen anywhere
+const CIRCULAR_ECCENTRICITY: f64 = 1e-6;
+
+/// Error returned when a maneuver cannot be planned.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ManeuverError {
+    /// The maneuver requires a closed orbit, e.g. to burn at the apoapsis
+    OpenOrbit,
+    /// The target of the maneuver is invalid, e.g. a negative radius
+    InvalidTarget,
+    /// The orbit could not be propagated to a burn
+    Propagation(ConvergenceError),
+}
+
+impl std::fmt::Display for ManeuverError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OpenOrbit => f.write_str("the maneuver requires a closed orbit"),
+            Self::InvalidTarget => f.write_str("the target of the maneuver is invalid"),
+            Self::Propagation(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ManeuverError {}
+
+impl From<ConvergenceError> for ManeuverError {
+    fn from(error: ConvergenceError) -> Self {
+        Self::Propagation(error)
+    }
+}
+
+/// An impulsive burn
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Burn {
+    /// Time of the burn, in seconds after the epoch of the initial orbit
+    pub time: f64,
+    /// Date of the burn (MJD)
+    pub mjd: f64,
+    /// Velocity change as (prograde, normal, radial) components, in m/s
+    ///
+    /// See [`StateVector::orbital_frame`].
+    ///
+    /// [`StateVector::orbital_frame`]: ../orbit/struct.StateVector.html#method.orbital_frame
+    pub delta_v: Vector3,
+    /// State vector right before the burn
+    pub state: StateVector,
+    /// Orbital elements right after the burn
+    pub elements: OrbitalElements,
+}
+
+impl Burn {
+    /// Returns the velocity change in the right-handed frame of the central
+    /// body, i.e. along fixed axes instead of the orbital frame.
+    pub fn inertial_delta_v(&self) -> Vector3 {
+        self.state.orbital_frame() * self.delta_v
+    }
+}
+
+/// A sequence of burns
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Maneuver {
+    /// Burns of the maneuver, in chronological order
+    pub burns: Vec<Burn>,
+}
+
+impl Maneuver {
+    /// Returns the total velocity change of the burns, in m/s.
+    pub fn total_delta_v(&self) -> f64 {
+        self.burns.iter().map(|burn| burn.delta_v.norm()).sum()
+    }
+
+    /// Returns the orbital elements after the last burn, or `None` if there
+    /// is no burn.
+    pub fn final_elements(&self) -> Option<&OrbitalElements> {
+        self.burns.last().map(|burn| &burn.elements)
+    }
+}
+
+/// Burns planned along an orbit
+struct Planner {
+    start: f64,
+    orbit: KeplerOrbit,
+    burns: Vec<Burn>,
+}
+
+impl Planner {
+    fn new(orbit: &KeplerOrbit) -> Self {
+        Self {
+            start: orbit.epoch,
+            orbit: *orbit,
+            burns: Vec::new(),
+        }
+    }
+
+    fn elements(&self) -> OrbitalElements {
+        self.orbit.elements()
+    }
+
+    /// Coasts for `dt` seconds, then changes the velocity.
+    fn burn<F>(&mut self, dt: f64, velocity: F) -> Result<&mut Self, ManeuverError>
+    where
+        F: FnOnce(&StateVector) -> Vector3,
+    {
+        let mjd = self.orbit.epoch + dt / SECONDS_PER_DAY;
+        let before = self.orbit.state_at(mjd)?;
+        let after = StateVector::new(before.position, velocity(&before));
+        self.orbit = KeplerOrbit::new(after, self.orbit.mu, mjd);
+        self.burns.push(Burn {
+            time: (mjd - self.start) * SECONDS_PER_DAY,
+            mjd,
+            delta_v: before.orbital_frame().inverse() * (after.velocity - before.velocity),
+            state: before,
+            elements: after.to_elements(self.orbit.mu),
+        });
+        Ok(self)
+    }
+
+    /// Coasts for `dt` seconds, then burns horizontally to reach `radius` on
+    /// the opposite side of the orbit.
+    fn transfer(&mut self, dt: f64, radius: f64) -> Result<&mut Self, ManeuverError> {
+        let mu = self.orbit.mu;
+        self.burn(dt, |state| {
+            let r = state.position.norm();
+            let speed = (2.0 * mu * radius / (r * (r + radius))).sqrt();
+            speed * horizontal(state)
+        })
+    }
+
+    /// Coasts to the opposite side of a transfer orbit, then circularizes in
+    /// the plane with the normal `normal`.
+    fn arrive(&mut self, normal: Vector3) -> Result<&mut Self, ManeuverError> {
+        let mu = self.orbit.mu;
+        let dt = self.elements().period().ok_or(ManeuverError::OpenOrbit)? / 2.0;
+        self.burn(dt, |state| {
+            let r = state.position.norm();
+            (mu / r).sqrt() * normal.cross(&state.position).normalize()
+        })
+    }
+
+    fn finish(&mut self) -> Maneuver {
+        Maneuver {
+            burns: std::mem::take(&mut self.burns),
+        }
+    }
+}
+
+/// Returns the horizontal prograde direction of a state.
+fn horizontal(state: &StateVector) -> Vector3 {
+    state
+        .position
+        .cross(&state.velocity)
+        .cross(&state.position)
+        .normalize()
+}
+
+/// Returns the normal of the plane with an inclination and a longitude of
+/// the ascending node.
+fn plane_normal(inclination: f64, longitude_of_ascending_node: f64) -> Vector3 {
+    let (sin_i, cos_i) = inclination.sin_cos();
+    let (sin_node, cos_node) = longitude_of_ascending_node.sin_cos();
+    Vector3::new(sin_node * sin_i, -cos_node * sin_i, cos_i)
+}
+
+/// Returns the time until the next burn at an apsis, to change the radius of
+/// the opposite apsis to `radius`: at the periapsis to raise the orbit, and
+/// at the apoapsis to lower it. On a circular orbit, the burn is immediate.
+fn time_to_apsis(elements: &OrbitalElements, radius: f64) -> Result<f64, ManeuverError> {
+    if !elements.is_closed() {
+        return Err(ManeuverError::OpenOrbit);
+    }
+    if radius <= 0.0 {
+        return Err(ManeuverError::InvalidTarget);
+    }
+    if elements.eccentricity < CIRCULAR_ECCENTRICITY {
+        return Ok(0.0);
+    }
+    let time = if radius >= elements.periapsis() {
+        elements.time_to_periapsis()
+    } else {
+        elements.time_to_apoapsis()
+    };
+    time.ok_or(ManeuverError::OpenOrbit)
+}
+
+/// Returns the time until the next crossing of the line of nodes between the
+/// orbit and the plane with the normal `normal`, or `None` if the planes are
+/// the same.
+///
+/// Fails if the orbit is open and does not reach any of the nodes.
+fn time_to_node(
+    elements: &OrbitalElements,
+    normal: &Vector3,
+) -> Result<Option<f64>, ManeuverError> {
+    let rotation = elements.perifocal_rotation();
+    let periapsis = rotation * Vector3::x();
+    let current_normal = rotation * Vector3::z();
+    let node = current_normal.cross(normal);
+    if node.norm() < 1e-12 {
+        return Ok(None);
+    }
+
+    let true_anomaly = angle_between(&periapsis, &node, &current_normal);
+    let ascending = elements.time_to_true_anomaly(true_anomaly);
+    let descending = elements.time_to_true_anomaly(normalize_angle(true_anomaly + PI));
+    match (ascending, descending) {
+        (Some(ascending), Some(descending)) => Ok(Some(ascending.min(descending))),
+        (None, None) => Err(ManeuverError::OpenOrbit),
+        (ascending, descending) => Ok(ascending.or(descending)),
+    }
+}
+
+/// Plans a Hohmann transfer to a circular orbit of the given radius (in m).
+///
+/// The first burn is at the next periapsis when raising the orbit, or at the
+/// next apoapsis when lowering it, or right away from a circular orbit. The
+/// second burn circularizes the orbit on the opposite side.
+pub fn hohmann(orbit: &KeplerOrbit, radius: f64) -> Result<Maneuver, ManeuverError> {
+    let elements = orbit.elements();
+    let dt = time_to_apsis(&elements, radius)?;
+    let normal = elements.perifocal_rotation() * Vector3::z();
+
+    let mut planner = Planner::new(orbit);
+    planner.transfer(dt, radius)?.arrive(normal)?;
+    Ok(planner.finish())
+}
+
+/// Plans a bi-elliptic transfer to a circular orbit of the given radius (in
+/// m), through an intermediate apoapsis.
+///
+/// The transfer is cheaper than a Hohmann transfer when the ratio of the
+/// radii is above about 11.94, at the cost of a much longer flight.
+pub fn bi_elliptic(
+    orbit: &KeplerOrbit,
+    intermediate_radius: f64,
+    radius: f64,
+) -> Result<Maneuver, ManeuverError> {
+    let elements = orbit.elements();
+    let dt = time_to_apsis(&elements, intermediate_radius)?;
+    if radius <= 0.0 {
+        return Err(ManeuverError::InvalidTarget);
+    }
+    let normal = elements.perifocal_rotation() * Vector3::z();
+
+    let mut planner = Planner::new(orbit);
+    planner.transfer(dt, intermediate_radius)?;
+    let dt = planner
+        .elements()
+        .period()
+        .ok_or(ManeuverError::OpenOrbit)?
+        / 2.0;
+    planner.transfer(dt, radius)?.arrive(normal)?;
+    Ok(planner.finish())
+}
+
+/// Plans a burn circularizing the orbit at the next apoapsis.
+pub fn circularize_at_apoapsis(orbit: &KeplerOrbit) -> Result<Maneuver, ManeuverError> {
+    let elements = orbit.elements();
+    let dt = elements
+        .time_to_apoapsis()
+        .ok_or(ManeuverError::OpenOrbit)?;
+    circularize(orbit, dt)
+}
+
+/// Plans a burn circularizing the orbit at the next periapsis.
+///
+/// On open orbits, the periapsis must not have been passed yet.
+pub fn circularize_at_periapsis(orbit: &KeplerOrbit) -> Result<Maneuver, ManeuverError> {
+    let elements = orbit.elements();
+    let dt = elements
+        .time_to_periapsis()
+        .ok_or(ManeuverError::OpenOrbit)?;
+    circularize(orbit, dt)
+}
+
+fn circularize(orbit: &KeplerOrbit, dt: f64) -> Result<Maneuver, ManeuverError> {
+    let mu = orbit.mu;
+    let mut planner = Planner::new(orbit);
+    planner.burn(dt, |state| {
+        (mu / state.position.norm()).sqrt() * horizontal(state)
+    })?;
+    Ok(planner.finish())
+}
+
+/// Plans a burn changing the plane of the orbit to the given inclination and
+/// longitude of the ascending node (in radians).
+///
+/// The burn is at the next crossing of the line of nodes between the current
+/// and the target planes, and keeps the shape of the orbit. Returns an empty
+/// maneuver if the orbit is already in the target plane, and fails with
+/// [`ManeuverError::OpenOrbit`] if an open orbit does not reach the nodes.
+///
+/// [`ManeuverError::OpenOrbit`]: ./enum.ManeuverError.html#variant.OpenOrbit
+pub fn change_plane(
+    orbit: &KeplerOrbit,
+    inclination: f64,
+    longitude_of_ascending_node: f64,
+) -> Result<Maneuver, ManeuverError> {
+    let elements = orbit.elements();
+    let normal = plane_normal(inclination, longitude_of_ascending_node);
+    let dt = match time_to_node(&elements, &normal)? {
+        Some(dt) => dt,
+        None => return Ok(Maneuver::default()),
+    };
+
+    let mut planner = Planner::new(orbit);
+    planner.burn(dt, |state| {
+        let current = state.position.cross(&state.velocity);
+        let rotation = Rotation3::rotation_between(&current, &normal).unwrap_or_else(|| {
+            Rotation3::from_axis_angle(&Unit::new_normalize(state.position), PI)
+        });
+        rotation * state.velocity
+    })?;
+    Ok(planner.finish())
+}
+
+/// Plans a burn changing the inclination of the orbit (in radians), at the
+/// next ascending or descending node.
+pub fn change_inclination(
+    orbit: &KeplerOrbit,
+    inclination: f64,
+) -> Result<Maneuver, ManeuverError> {
+    let elements = orbit.elements();
+    change_plane(orbit, inclination, elements.longitude_of_ascending_node)
+}
+
+/// Plans a burn changing the longitude of the ascending node of the orbit
+/// (in radians), keeping its inclination.
+pub fn change_node(
+    orbit: &KeplerOrbit,
+    longitude_of_ascending_node: f64,
+) -> Result<Maneuver, ManeuverError> {
+    let elements = orbit.elements();
+    change_plane(orbit, elements.inclination, longitude_of_ascending_node)
+}
+
+/// Plans a transfer to a circular orbit of the given radius (in m) in another
+/// plane.
+///
+/// The first burn raises or lowers the orbit at the next crossing of the line
+/// of nodes. The second burn, on the opposite node, circularizes the orbit and
+/// changes its plane at once, which is cheaper than separate burns when the
+/// orbit is raised.
+pub fn transfer_with_plane_change(
+    orbit: &KeplerOrbit,
+    radius: f64,
+    inclination: f64,
+    longitude_of_ascending_node: f64,
+) -> Result<Maneuver, ManeuverError> {
+    if radius <= 0.0 {
+        return Err(ManeuverError::InvalidTarget);
+    }
+    let elements = orbit.elements();
+    let normal = plane_normal(inclination, longitude_of_ascending_node);
+    let dt = match time_to_node(&elements, &normal)? {
+        Some(dt) => dt,
+        None => return hohmann(orbit, radius),
+    };
+
+    let mut planner = Planner::new(orbit);
+    planner.transfer(dt, radius)?.arrive(normal)?;
+    Ok(planner.finish())
+}
+
+/// Plans a phasing orbit to rendezvous with a target on the same circular
+/// orbit.
+///
+/// The first burn, right away, enters an orbit whose period brings the
+/// vessel back to its current position after `revolutions` revolutions, when
+/// the target arrives there. The second burn restores the initial orbit.
+/// More revolutions need less velocity change but take longer.
+///
+/// Both orbits must be near-circular and in the same plane: the phase angle
+/// is measured in the plane of the vessel, and the phasing orbit keeps the
+/// current radius as an apsis. Returns [`ManeuverError::InvalidTarget`] if
+/// the phasing orbit would fall below `body_radius` (in m), e.g. for a target
+/// far ahead with few revolutions; more revolutions then help.
+///
+/// [`ManeuverError::InvalidTarget`]: ./enum.ManeuverError.html#variant.InvalidTarget
+pub fn phasing(
+    orbit: &KeplerOrbit,
+    target: &KeplerOrbit,
+    revolutions: u32,
+    body_radius: f64,
+) -> Result<Maneuver, ManeuverError> {
+    if revolutions == 0 {
+        return Err(ManeuverError::InvalidTarget);
+    }
+    let target_elements = target.elements();
+    let target_period = target_elements.period().ok_or(ManeuverError::OpenOrbit)?;
+
+    // Angle from the vessel to the target, in the direction of motion
+    let state = orbit.state;
+    let target_state = target.state_at(orbit.epoch)?;
+    let normal = state.position.cross(&state.velocity);
+    let phase = angle_between(&state.position, &target_state.position, &normal.normalize());
+
+    let revolutions = revolutions as f64;
+    let period = (2.0 * PI * revolutions - phase) / (2.0 * PI * revolutions) * target_period;
+    let semi_major_axis = (orbit.mu * (period / (2.0 * PI)).powi(2)).cbrt();
+    // The current radius is the apoapsis of a faster phasing orbit
+    let radius = state.position.norm();
+    if 2.0 * semi_major_axis - radius <= body_radius.max(0.0) {
+        return Err(ManeuverError::InvalidTarget);
+    }
+
+    let mu = orbit.mu;
+    let mut planner = Planner::new(orbit);
+    planner
+        .burn(0.0, |state| {
+            let speed = (mu * (2.0 / state.position.norm() - 1.0 / semi_major_axis)).sqrt();
+            speed * state.velocity.normalize()
+        })?
+        .burn(revolutions * period, |_| state.velocity)?;
+    Ok(planner.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{periapsis_state, MU_EARTH};
+
+    /// Mean radius of the Earth, in m
+    const EARTH_RADIUS: f64 = 6_371e3;
+
+    /// Returns an orbit with its periapsis on the x axis, inclined around it.
+    fn orbit(periapsis: f64, eccentricity: f64, inclination: f64) -> KeplerOrbit {
+        let state = periapsis_state(periapsis, eccentricity, inclination);
+        KeplerOrbit::new(state, MU_EARTH, 51544.0)
+    }
+
+    #[test]
+    fn hohmann_from_a_circular_orbit_burns_right_away() {
+        let start = orbit(7000e3, 0.0, 0.5);
+        for &dt in &[0.0, 1000.0, 3000.0] {
+            let orbit = start.at(51544.0 + dt / SECONDS_PER_DAY).unwrap();
+            let transfer = hohmann(&orbit, 42_164e3).unwrap();
+            assert_eq!(transfer.burns[0].time, 0.0);
+            let last = transfer.final_elements().unwrap();
+            assert!((last.semi_major_axis() - 42_164e3).abs() < 1e-3);
+            assert!(last.eccentricity < 1e-9);
+        }
+    }
+
+    #[test]
+    fn hohmann_from_an_elliptic_orbit_burns_at_an_apsis() {
+        let orbit = orbit(7000e3, 0.1, 0.0).at(51544.01).unwrap();
+        let elements = orbit.elements();
+
+        let raise = hohmann(&orbit, 42_164e3).unwrap();
+        let lower = hohmann(&orbit, 6_800e3).unwrap();
+        assert!((raise.burns[0].time - elements.time_to_periapsis().unwrap()).abs() < 1e-6);
+        assert!((lower.burns[0].time - elements.time_to_apoapsis().unwrap()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn plane_changes_reach_the_target_plane() {
+        let orbit = orbit(7000e3, 0.2, 0.5).at(51544.01).unwrap();
+        let eccentricity = orbit.elements().eccentricity;
+
+        let elements = *change_plane(&orbit, 1.0, 2.0)
+            .unwrap()
+            .final_elements()
+            .unwrap();
+        assert!((elements.inclination - 1.0).abs() < 1e-9);
+        assert!((elements.longitude_of_ascending_node - 2.0).abs() < 1e-9);
+        assert!((elements.eccentricity - eccentricity).abs() < 1e-9);
+
+        let elements = *change_inclination(&orbit, 0.2)
+            .unwrap()
+            .final_elements()
+            .unwrap();
+        assert!((elements.inclination - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn same_plane_needs_no_burn() {
+        let orbit = orbit(7000e3, 0.0, 0.5);
+        let elements = orbit.elements();
+        let maneuver = change_plane(
+            &orbit,
+            elements.inclination,
+            elements.longitude_of_ascending_node,
+        )
+        .unwrap();
+        assert!(maneuver.burns.is_empty());
+    }
+
+    #[test]
+    fn unreachable_nodes_are_an_error() {
+        // Departing hyperbola, past its periapsis on the line of nodes
+        let orbit = orbit(7000e3, 1.53, 0.5).at(51544.1).unwrap();
+        assert_eq!(
+            change_plane(&orbit, 0.0, 0.0),
+            Err(ManeuverError::OpenOrbit)
+        );
+        assert_eq!(
+            transfer_with_plane_change(&orbit, 42_164e3, 0.0, 0.0),
+            Err(ManeuverError::OpenOrbit)
+        );
+    }
+
+    #[test]
+    fn phasing_meets_the_target() {
+        let vessel = orbit(7000e3, 0.0, 0.5);
+        let target = vessel.at(51544.0 + 600.0 / SECONDS_PER_DAY).unwrap();
+        let target = KeplerOrbit::new(target.state, MU_EARTH, 51544.0);
+
+        let maneuver = phasing(&vessel, &target, 3, EARTH_RADIUS).unwrap();
+        let last = maneuver.burns.last().unwrap();
+        let target_position = target.state_at(last.mjd).unwrap().position;
+        assert!((last.state.position - target_position).norm() < 1e-2);
+    }
+
+    #[test]
+    fn phasing_stays_above_the_body() {
+        let vessel = orbit(7000e3, 0.0, 0.5);
+        let period = vessel.elements().period().unwrap();
+        let target = vessel.at(51544.0 + period * 300.0 / 360.0 / SECONDS_PER_DAY);
+        let target = KeplerOrbit::new(target.unwrap().state, MU_EARTH, 51544.0);
+
+        // A sixth of a period would need a semi-major axis below r/2
+        assert_eq!(
+            phasing(&vessel, &target, 1, 0.0),
+            Err(ManeuverError::InvalidTarget)
+        );
+        // Two revolutions stay clear of the center, but not of the surface
+        assert!(phasing(&vessel, &target, 2, 0.0).is_ok());
+        assert_eq!(
+            phasing(&vessel, &target, 2, EARTH_RADIUS),
+            Err(ManeuverError::InvalidTarget)
+        );
+
+        let maneuver = phasing(&vessel, &target, 20, EARTH_RADIUS).unwrap();
+        assert!(maneuver
+            .burns
+            .iter()
+            .all(|burn| burn.delta_v.norm().is_finite()));
+    }
+}
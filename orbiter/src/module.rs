@@ -1,9 +1,14 @@
 #![allow(non_snake_case)]
 
+use crate::time::Mjd;
+#[cfg(feature = "units")]
+use crate::units::ModuleUnits;
+use crate::win::minwindef::{DWORD, UINT};
 use crate::{InstanceHandle, Key, KeyStates, MouseEvent, Vessel};
 use crate::{HINSTANCE, OBJHANDLE};
+use std::cell::{Cell, Ref, RefCell, RefMut};
 use std::os::raw::{c_char, c_int, c_void};
-use winapi::shared::minwindef::{DWORD, UINT};
+use std::rc::Rc;
 
 #[derive(Debug, Copy, Clone, PartialOrd, PartialEq)]
 pub enum RenderMode {
@@ -26,6 +31,16 @@ impl RenderMode {
     }
 }
 
+/// Callbacks of a module.
+///
+/// In the step callbacks, `simt` is the simulation time and `simdt` the
+/// length of the step, in s. `mjd` is the simulation date as a Modified
+/// Julian Date, which can be wrapped in an [`Mjd`] for the conversions of the
+/// [`time`] module, like [`Module::sim_date`] does.
+///
+/// [`Mjd`]: ./time/struct.Mjd.html
+/// [`time`]: ./time/index.html
+/// [`Module::sim_date`]: ./struct.Module.html#method.sim_date
 pub trait ModuleCallbacks {
     fn on_simulation_start(&mut self, _module: &mut Module, _render_mode: RenderMode) {}
     fn on_simulation_end(&mut self, _module: &mut Module) {}
@@ -68,12 +83,16 @@ pub trait ModuleCallbacks {
 
 pub(crate) struct ModuleAdapter {
     module: Module,
-    callbacks: Box<dyn ModuleCallbacks>,
+    callbacks: Option<Rc<RefCell<dyn ModuleCallbacks>>>,
+    state: Rc<RegistrationState>,
 }
 
 impl ModuleAdapter {
     #[allow(clippy::new_ret_no_self)]
-    pub(crate) fn new<M: ModuleCallbacks + 'static>(handle: &InstanceHandle, module: M) {
+    pub(crate) fn new<M: ModuleCallbacks + 'static>(
+        handle: &InstanceHandle,
+        module: M,
+    ) -> ModuleRegistration<M> {
         let c_callbacks = RustModuleCallbacks {
             clbkSimulationStart,
             clbkSimulationEnd,
@@ -91,16 +110,139 @@ impl ModuleAdapter {
             clbkProcessKeyboardBuffered,
             clbkDestroy,
         };
-        let callbacks = Box::new(module);
+        let registration = ModuleRegistration {
+            callbacks: Rc::new(RefCell::new(module)),
+            state: Rc::new(RegistrationState {
+                registered: Cell::new(true),
+                enabled: Cell::new(true),
+            }),
+        };
         let adapter = Box::into_raw(Box::new(ModuleAdapter {
             module: Module(std::ptr::null_mut()),
-            callbacks,
+            callbacks: Some(registration.callbacks.clone()),
+            state: registration.state.clone(),
         }));
 
         unsafe {
             let module = oapic_module_new(c_callbacks, adapter as *mut _, handle.into_raw());
             (*adapter).module = Module(module);
         };
+
+        registration
+    }
+
+    /// Calls the user callbacks, or returns the default value if the module
+    /// is disabled, unregistered or already handling a callback.
+    ///
+    /// A panic of the callbacks, e.g. when using a deleted [`Vessel`], is
+    /// logged and the default value is returned.
+    fn dispatch<R: Default>(
+        &mut self,
+        callback: impl FnOnce(&mut dyn ModuleCallbacks, &mut Module) -> R,
+    ) -> R {
+        if !self.state.registered.get() {
+            self.callbacks = None;
+        }
+
+        match &self.callbacks {
+            Some(callbacks) if self.state.enabled.get() => match callbacks.try_borrow_mut() {
+                Ok(mut callbacks) => {
+                    let module = &mut self.module;
+                    crate::catch_panic("Module callback", || callback(&mut *callbacks, module))
+                        .unwrap_or_default()
+                }
+                Err(_) => R::default(),
+            },
+            _ => R::default(),
+        }
+    }
+}
+
+impl Drop for ModuleAdapter {
+    fn drop(&mut self) {
+        self.state.registered.set(false);
+    }
+}
+
+struct RegistrationState {
+    registered: Cell<bool>,
+    enabled: Cell<bool>,
+}
+
+/// Handle to a module registered with [`InstanceHandle::register_module`].
+///
+/// The handle gives access to the [`ModuleCallbacks`] value of the module, for
+/// example to reconfigure it from another module of the same DLL. Dropping the
+/// handle does not unregister the module.
+///
+/// Orbiter does not allow removing a module before it unloads the DLL. Once
+/// unregistered, the callbacks are not called anymore and the value is dropped
+/// when the last handle is dropped.
+///
+/// [`InstanceHandle::register_module`]: ./struct.InstanceHandle.html#method.register_module
+/// [`ModuleCallbacks`]: ./trait.ModuleCallbacks.html
+pub struct ModuleRegistration<M> {
+    callbacks: Rc<RefCell<M>>,
+    state: Rc<RegistrationState>,
+}
+
+impl<M> ModuleRegistration<M> {
+    /// Stops delivering callbacks to the module.
+    ///
+    /// This cannot be undone. Register the module again instead.
+    pub fn unregister(&self) {
+        self.state.registered.set(false);
+    }
+
+    /// Checks if the module is still registered.
+    ///
+    /// Returns `false` after a call to [`unregister`] or once Orbiter destroyed
+    /// the module.
+    ///
+    /// [`unregister`]: #method.unregister
+    pub fn is_registered(&self) -> bool {
+        self.state.registered.get()
+    }
+
+    /// Enables or disables the delivery of callbacks.
+    ///
+    /// While disabled, mouse and keyboard events are reported as not consumed.
+    pub fn set_enabled(&self, enabled: bool) {
+        self.state.enabled.set(enabled);
+    }
+
+    /// Checks if the delivery of callbacks is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.state.enabled.get()
+    }
+
+    /// Immutably borrows the callbacks of the module.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the callbacks are currently mutably borrowed, which is the
+    /// case while one of the callbacks of this module is running.
+    pub fn callbacks(&self) -> Ref<'_, M> {
+        self.callbacks.borrow()
+    }
+
+    /// Mutably borrows the callbacks of the module.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the callbacks are currently borrowed, which is the case while
+    /// one of the callbacks of this module is running.
+    pub fn callbacks_mut(&self) -> RefMut<'_, M> {
+        self.callbacks.borrow_mut()
+    }
+}
+
+impl<M> Clone for ModuleRegistration<M> {
+    fn clone(&self) -> Self {
+        Self {
+            callbacks: self.callbacks.clone(),
+            state: self.state.clone(),
+        }
     }
 }
 
@@ -123,14 +265,38 @@ impl Module {
         unsafe { oapic_module_get_sim_step(self.0) }
     }
 
+    /// Returns the simulation date as a Modified Julian Date, in days.
+    ///
+    /// See [`sim_date`] for a typed date.
+    ///
+    /// [`sim_date`]: #method.sim_date
     pub fn sim_mjd(&self) -> f64 {
         unsafe { oapic_module_get_sim_mjd(self.0) }
     }
+
+    /// Returns the simulation date.
+    ///
+    /// See the [`time`] module for the conversions of the date.
+    ///
+    /// [`time`]: ./time/index.html
+    pub fn sim_date(&self) -> Mjd {
+        Mjd(self.sim_mjd())
+    }
+
+    /// Returns accessors returning physical quantities.
+    ///
+    /// Only available with the `units` feature. See the [`units`] module.
+    ///
+    /// [`units`]: ./units/index.html
+    #[cfg(feature = "units")]
+    pub fn units(&self) -> ModuleUnits<'_> {
+        ModuleUnits::new(self)
+    }
 }
 
 type RustModule = *mut c_void;
 
-#[link(name = "orbiter_c")]
+#[cfg_attr(windows, link(name = "orbiter_c"))]
 extern "C" {
     fn oapic_module_new(cb: RustModuleCallbacks, ctx: *mut c_void, hDLL: HINSTANCE) -> RustModule;
     fn oapic_module_version(module: RustModule) -> c_int;
@@ -168,68 +334,71 @@ struct RustModuleCallbacks {
 
 extern "C" fn clbkSimulationStart(ctx: *mut c_void, render_mode: c_int) {
     let ctx = unsafe { &mut *(ctx as *mut ModuleAdapter) };
-    ctx.callbacks
-        .on_simulation_start(&mut ctx.module, RenderMode::from(render_mode));
+    ctx.dispatch(|callbacks, module| {
+        callbacks.on_simulation_start(module, RenderMode::from(render_mode))
+    });
 }
 
 extern "C" fn clbkSimulationEnd(ctx: *mut c_void) {
     let ctx = unsafe { &mut *(ctx as *mut ModuleAdapter) };
-    ctx.callbacks.on_simulation_end(&mut ctx.module);
+    ctx.dispatch(|callbacks, module| callbacks.on_simulation_end(module));
 }
 
 extern "C" fn clbkPreStep(ctx: *mut c_void, simt: f64, simdt: f64, mjd: f64) {
     let ctx = unsafe { &mut *(ctx as *mut ModuleAdapter) };
-    ctx.callbacks.on_pre_step(&mut ctx.module, simt, simdt, mjd);
+    ctx.dispatch(|callbacks, module| callbacks.on_pre_step(module, simt, simdt, mjd));
 }
 
 extern "C" fn clbkPostStep(ctx: *mut c_void, simt: f64, simdt: f64, mjd: f64) {
     let ctx = unsafe { &mut *(ctx as *mut ModuleAdapter) };
-    ctx.callbacks
-        .on_post_step(&mut ctx.module, simt, simdt, mjd);
+    ctx.dispatch(|callbacks, module| callbacks.on_post_step(module, simt, simdt, mjd));
 }
 
 extern "C" fn clbkTimeJump(ctx: *mut c_void, simt: f64, simdt: f64, mjd: f64) {
     let ctx = unsafe { &mut *(ctx as *mut ModuleAdapter) };
-    ctx.callbacks
-        .on_time_jump(&mut ctx.module, simt, simdt, mjd);
+    ctx.dispatch(|callbacks, module| callbacks.on_time_jump(module, simt, simdt, mjd));
 }
 
 extern "C" fn clbkFocusChanged(ctx: *mut c_void, new_focus: OBJHANDLE, old_focus: OBJHANDLE) {
     let ctx = unsafe { &mut *(ctx as *mut ModuleAdapter) };
-    ctx.callbacks.on_focus_changed(
-        &mut ctx.module,
-        Vessel::from_obj(new_focus).unwrap(),
-        Vessel::from_obj(old_focus),
-    );
+    ctx.dispatch(|callbacks, module| {
+        callbacks.on_focus_changed(
+            module,
+            Vessel::from_obj(new_focus).unwrap(),
+            Vessel::from_obj(old_focus),
+        )
+    });
 }
 
 extern "C" fn clbkTimeAccChanged(ctx: *mut c_void, new_warp: f64, old_warp: f64) {
     let ctx = unsafe { &mut *(ctx as *mut ModuleAdapter) };
-    ctx.callbacks
-        .on_time_acc_changed(&mut ctx.module, new_warp, old_warp);
+    ctx.dispatch(|callbacks, module| callbacks.on_time_acc_changed(module, new_warp, old_warp));
 }
 
 extern "C" fn clbkNewVessel(ctx: *mut c_void, vessel: OBJHANDLE) {
     let ctx = unsafe { &mut *(ctx as *mut ModuleAdapter) };
-    ctx.callbacks
-        .on_new_vessel(&mut ctx.module, Vessel::from_obj(vessel).unwrap());
+    ctx.dispatch(|callbacks, module| {
+        callbacks.on_new_vessel(module, Vessel::from_obj(vessel).unwrap())
+    });
 }
 
 extern "C" fn clbkDeleteVessel(ctx: *mut c_void, vessel: OBJHANDLE) {
     let ctx = unsafe { &mut *(ctx as *mut ModuleAdapter) };
-    ctx.callbacks
-        .on_delete_vessel(&mut ctx.module, Vessel::from_obj(vessel).unwrap());
+    ctx.dispatch(|callbacks, module| {
+        callbacks.on_delete_vessel(module, Vessel::from_obj(vessel).unwrap())
+    });
 }
 
 extern "C" fn clbkVesselJump(ctx: *mut c_void, vessel: OBJHANDLE) {
     let ctx = unsafe { &mut *(ctx as *mut ModuleAdapter) };
-    ctx.callbacks
-        .on_vessel_jump(&mut ctx.module, Vessel::from_obj(vessel).unwrap());
+    ctx.dispatch(|callbacks, module| {
+        callbacks.on_vessel_jump(module, Vessel::from_obj(vessel).unwrap())
+    });
 }
 
 extern "C" fn clbkPause(ctx: *mut c_void, pause: bool) {
     let ctx = unsafe { &mut *(ctx as *mut ModuleAdapter) };
-    ctx.callbacks.on_pause(&mut ctx.module, pause);
+    ctx.dispatch(|callbacks, module| callbacks.on_pause(module, pause));
 }
 
 extern "C" fn clbkProcessMouse(
@@ -241,7 +410,7 @@ extern "C" fn clbkProcessMouse(
 ) -> bool {
     let ctx = unsafe { &mut *(ctx as *mut ModuleAdapter) };
     let mouse_event = MouseEvent::from(event, state, x, y);
-    ctx.callbacks.on_process_mouse(&mut ctx.module, mouse_event)
+    ctx.dispatch(|callbacks, module| callbacks.on_process_mouse(module, mouse_event))
 }
 
 extern "C" fn clbkProcessKeyboardImmediate(
@@ -250,11 +419,13 @@ extern "C" fn clbkProcessKeyboardImmediate(
     sim_running: bool,
 ) -> bool {
     let ctx = unsafe { &mut *(ctx as *mut ModuleAdapter) };
-    ctx.callbacks.on_process_keyboard_immediate(
-        &mut ctx.module,
-        &mut KeyStates::from(key_states),
-        sim_running,
-    )
+    ctx.dispatch(|callbacks, module| {
+        callbacks.on_process_keyboard_immediate(
+            module,
+            &mut KeyStates::from(key_states),
+            sim_running,
+        )
+    })
 }
 
 extern "C" fn clbkProcessKeyboardBuffered(
@@ -264,17 +435,18 @@ extern "C" fn clbkProcessKeyboardBuffered(
     sim_running: bool,
 ) -> bool {
     let ctx = unsafe { &mut *(ctx as *mut ModuleAdapter) };
-    ctx.callbacks.on_process_keyboard_buffered(
-        &mut ctx.module,
-        Key::from(key as u8),
-        &mut KeyStates::from(key_states),
-        sim_running,
-    )
+    ctx.dispatch(|callbacks, module| {
+        callbacks.on_process_keyboard_buffered(
+            module,
+            Key::from(key as u8),
+            &mut KeyStates::from(key_states),
+            sim_running,
+        )
+    })
 }
 
 extern "C" fn clbkDestroy(ctx: *mut c_void) {
     unsafe {
-        Box::from_raw(ctx as *mut ModuleAdapter);
+        drop(Box::from_raw(ctx as *mut ModuleAdapter));
     }
-    println!("Module destroyed");
 }
@@ -0,0 +1,351 @@
+use crate::win::minwindef::DWORD;
+use crate::{oapic_VECTOR3, Object, Planet, SurfaceBase, Vector3, Vessel, VesselTrait, OBJHANDLE};
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int};
+
+#[doc(hidden)]
+pub enum _NAVHANDLE {}
+pub type NAVHANDLE = *mut _NAVHANDLE;
+
+/// Type of a navigation radio transmitter
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum NavType {
+    /// Omnidirectional beacon at a fixed surface position
+    Vor,
+    /// Vertical takeoff and landing support for a landing pad
+    Vtol,
+    /// Instrument landing system for a runway approach
+    Ils,
+    /// Instrument docking system of a docking port
+    Ids,
+    /// Transponder of a vessel
+    Transponder,
+}
+
+impl NavType {
+    fn from_raw(value: DWORD) -> Option<Self> {
+        match value {
+            1 => Some(Self::Vor),
+            2 => Some(Self::Vtol),
+            3 => Some(Self::Ils),
+            4 => Some(Self::Ids),
+            5 => Some(Self::Transponder),
+            _ => None,
+        }
+    }
+}
+
+/// Object a navigation transmitter is attached to
+pub enum NavTarget {
+    /// VOR beacon on a planet
+    Vor {
+        planet: Planet,
+        latitude: f64,
+        longitude: f64,
+    },
+    /// VTOL transmitter of a landing pad
+    Vtol { base: SurfaceBase, pad: usize },
+    /// ILS transmitter of a runway, with the approach direction in radians
+    Ils {
+        base: SurfaceBase,
+        approach_direction: f64,
+    },
+    /// IDS transmitter of a docking port
+    Ids { vessel: Vessel, dock: usize },
+    /// Transponder of a vessel
+    Transponder { vessel: Vessel },
+}
+
+/// A navigation radio transmitter
+///
+/// Transmitters are received by the navigation radios of vessels when they
+/// are tuned to the frequency of the transmitter and within range.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct NavTransmitter {
+    pub(crate) handle: NAVHANDLE,
+}
+
+impl NavTransmitter {
+    pub(crate) fn from(handle: NAVHANDLE) -> Option<Self> {
+        if handle.is_null() {
+            None
+        } else {
+            Some(Self { handle })
+        }
+    }
+
+    /// Retrieves the navigation transmitters of the simulation.
+    ///
+    /// This includes the transponders and IDS transmitters of the vessels, and
+    /// the VTOL transmitters of the landing pads of surface bases. VOR and ILS
+    /// transmitters are not exposed by the Orbiter API, they can only be found
+    /// with the navigation radios of a vessel.
+    pub fn all() -> Vec<NavTransmitter> {
+        let mut transmitters = Vec::new();
+        for object in Object::all_objects() {
+            match object {
+                Object::Vessel(vessel) => {
+                    transmitters.extend(vessel.transponder());
+                    transmitters
+                        .extend((0..vessel.dock_count()).filter_map(|dock| vessel.ids(dock)));
+                }
+                Object::Planet(planet) => {
+                    for base in planet.bases() {
+                        transmitters.extend(base.transmitters());
+                    }
+                }
+                _ => (),
+            }
+        }
+        transmitters
+    }
+
+    /// Returns the type of the transmitter.
+    pub fn nav_type(&self) -> Option<NavType> {
+        NavType::from_raw(unsafe { oapic_oapiGetNavType(self.handle) })
+    }
+
+    /// Returns the frequency of the transmitter in MHz.
+    pub fn frequency(&self) -> f32 {
+        unsafe { oapic_oapiGetNavFreq(self.handle) }
+    }
+
+    /// Returns the channel of the transmitter.
+    ///
+    /// Channels range from 0 to 639, corresponding to frequencies from
+    /// 108.00 to 139.95 MHz in steps of 0.05 MHz.
+    pub fn channel(&self) -> u32 {
+        unsafe { oapic_oapiGetNavChannel(self.handle) }
+    }
+
+    /// Returns the range of the transmitter in meters.
+    pub fn range(&self) -> f32 {
+        unsafe { oapic_oapiGetNavRange(self.handle) }
+    }
+
+    /// Returns the position of the transmitter in the global frame.
+    pub fn global_position(&self) -> Vector3 {
+        let mut pos = oapic_VECTOR3::new();
+        unsafe { oapic_oapiGetNavPos(self.handle, &mut pos) };
+        pos.into()
+    }
+
+    /// Returns the strength of the signal of the transmitter at a global
+    /// position.
+    ///
+    /// The signal strength is in arbitrary units, and decreases with the
+    /// square of the distance to the transmitter.
+    pub fn signal_strength(&self, global_position: Vector3) -> f64 {
+        let global_position = oapic_VECTOR3::from(global_position);
+        unsafe { oapic_oapiGetNavSignal(self.handle, &global_position) }
+    }
+
+    /// Returns a description of the transmitter, e.g. `"VTOL Pad-1 Brighton Beach"`.
+    pub fn description(&self) -> String {
+        let mut buffer = vec![0; 256];
+        unsafe { oapic_oapiGetNavDescr(self.handle, buffer.as_mut_ptr(), buffer.len() as c_int) };
+        unsafe { CStr::from_ptr(buffer.as_ptr()) }
+            .to_string_lossy()
+            .to_string()
+    }
+
+    /// Returns the object the transmitter is attached to.
+    pub fn target(&self) -> Option<NavTarget> {
+        let mut data = oapic_NAVDATA::new();
+        if !unsafe { oapic_oapiGetNavData(self.handle, &mut data) } {
+            return None;
+        }
+        data.target(Vessel::from_obj)
+    }
+}
+
+/// Signal received by a navigation radio receiver
+///
+/// Returned by [`VesselTrait::nav_signal`].
+///
+/// [`VesselTrait::nav_signal`]: ./trait.VesselTrait.html#method.nav_signal
+pub struct NavSignal {
+    /// Transmitter of the signal
+    pub transmitter: NavTransmitter,
+    /// Type of the transmitter
+    pub nav_type: Option<NavType>,
+    /// Object the transmitter is attached to
+    pub target: Option<NavTarget>,
+    /// Position of the transmitter relative to the receiver, in the global frame
+    pub relative_position: Vector3,
+    /// Strength of the signal at the receiver
+    pub strength: f64,
+}
+
+impl NavSignal {
+    pub(crate) fn new(transmitter: NavTransmitter, receiver_position: Vector3) -> Self {
+        Self {
+            transmitter,
+            nav_type: transmitter.nav_type(),
+            target: transmitter.target(),
+            relative_position: transmitter.global_position() - receiver_position,
+            strength: transmitter.signal_strength(receiver_position),
+        }
+    }
+
+    /// Returns the distance between the receiver and the transmitter, in meters.
+    pub fn distance(&self) -> f64 {
+        self.relative_position.norm()
+    }
+}
+
+#[repr(C)]
+struct oapic_NAVDATA {
+    nav_type: DWORD,
+    obj: OBJHANDLE,
+    lng: f64,
+    lat: f64,
+    appdir: f64,
+    index: DWORD,
+}
+
+impl oapic_NAVDATA {
+    fn new() -> Self {
+        Self {
+            nav_type: 0,
+            obj: std::ptr::null_mut(),
+            lng: 0.0,
+            lat: 0.0,
+            appdir: 0.0,
+            index: 0,
+        }
+    }
+
+    /// Decodes the object the transmitter is attached to, with `vessel`
+    /// looking up the vessels of IDS transmitters and transponders.
+    fn target(&self, vessel: impl Fn(OBJHANDLE) -> Option<Vessel>) -> Option<NavTarget> {
+        let base = || SurfaceBase { handle: self.obj };
+        match NavType::from_raw(self.nav_type)? {
+            NavType::Vor => Some(NavTarget::Vor {
+                planet: Planet { handle: self.obj },
+                latitude: self.lat,
+                longitude: self.lng,
+            }),
+            NavType::Vtol => Some(NavTarget::Vtol {
+                base: base(),
+                pad: self.index as usize,
+            }),
+            NavType::Ils => Some(NavTarget::Ils {
+                base: base(),
+                approach_direction: self.appdir,
+            }),
+            NavType::Ids => Some(NavTarget::Ids {
+                vessel: vessel(self.obj)?,
+                dock: self.index as usize,
+            }),
+            NavType::Transponder => Some(NavTarget::Transponder {
+                vessel: vessel(self.obj)?,
+            }),
+        }
+    }
+}
+
+#[cfg_attr(windows, link(name = "orbiter_c"))]
+extern "C" {
+    fn oapic_oapiGetNavType(handle: NAVHANDLE) -> DWORD;
+    fn oapic_oapiGetNavFreq(handle: NAVHANDLE) -> f32;
+    fn oapic_oapiGetNavChannel(handle: NAVHANDLE) -> DWORD;
+    fn oapic_oapiGetNavRange(handle: NAVHANDLE) -> f32;
+    fn oapic_oapiGetNavPos(handle: NAVHANDLE, pos: *mut oapic_VECTOR3);
+    fn oapic_oapiGetNavDescr(handle: NAVHANDLE, descr: *mut c_char, maxlen: c_int) -> c_int;
+    fn oapic_oapiGetNavSignal(handle: NAVHANDLE, gpos: *const oapic_VECTOR3) -> f64;
+    fn oapic_oapiGetNavData(handle: NAVHANDLE, data: *mut oapic_NAVDATA) -> bool;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(nav_type: DWORD) -> oapic_NAVDATA {
+        oapic_NAVDATA {
+            nav_type,
+            obj: 8 as OBJHANDLE,
+            lng: 0.5,
+            lat: -0.25,
+            appdir: 1.5,
+            index: 3,
+        }
+    }
+
+    #[test]
+    fn nav_types() {
+        assert_eq!(NavType::from_raw(1), Some(NavType::Vor));
+        assert_eq!(NavType::from_raw(2), Some(NavType::Vtol));
+        assert_eq!(NavType::from_raw(3), Some(NavType::Ils));
+        assert_eq!(NavType::from_raw(4), Some(NavType::Ids));
+        assert_eq!(NavType::from_raw(5), Some(NavType::Transponder));
+        assert_eq!(NavType::from_raw(0), None);
+        assert_eq!(NavType::from_raw(6), None);
+    }
+
+    #[test]
+    fn surface_targets() {
+        let handle = 8 as OBJHANDLE;
+        match data(1).target(|_| None) {
+            Some(NavTarget::Vor {
+                planet,
+                latitude,
+                longitude,
+            }) => {
+                assert_eq!(planet.handle, handle);
+                assert_eq!((latitude, longitude), (-0.25, 0.5));
+            }
+            _ => panic!("expected a VOR target"),
+        }
+        match data(2).target(|_| None) {
+            Some(NavTarget::Vtol { base, pad }) => {
+                assert_eq!(base.handle, handle);
+                assert_eq!(pad, 3);
+            }
+            _ => panic!("expected a VTOL target"),
+        }
+        match data(3).target(|_| None) {
+            Some(NavTarget::Ils {
+                base,
+                approach_direction,
+            }) => {
+                assert_eq!(base.handle, handle);
+                assert_eq!(approach_direction, 1.5);
+            }
+            _ => panic!("expected an ILS target"),
+        }
+    }
+
+    #[test]
+    fn unknown_targets() {
+        assert!(oapic_NAVDATA::new().target(|_| None).is_none());
+        assert!(data(42).target(|_| None).is_none());
+        // Deleted vessels
+        assert!(data(4).target(|_| None).is_none());
+        assert!(data(5).target(|_| None).is_none());
+    }
+
+    #[test]
+    fn signal_distance() {
+        let signal = NavSignal {
+            transmitter: NavTransmitter {
+                handle: 16 as NAVHANDLE,
+            },
+            nav_type: Some(NavType::Transponder),
+            target: None,
+            relative_position: Vector3::new(3.0, -4.0, 12.0),
+            strength: 1.0,
+        };
+        assert_eq!(signal.distance(), 13.0);
+    }
+
+    #[test]
+    fn null_transmitters() {
+        assert_eq!(NavTransmitter::from(std::ptr::null_mut()), None);
+        let handle = 16 as NAVHANDLE;
+        assert_eq!(
+            NavTransmitter::from(handle),
+            Some(NavTransmitter { handle })
+        );
+    }
+}
@@ -0,0 +1,666 @@
+//! Numerical propagation under the gravity of several bodies.
+//!
+//! Patched conics only consider one central body at a time, which is not
+//! accurate enough for e.g. lunar trajectories. A [`Propagator`] integrates
+//! the motion of a test particle, of negligible mass, under the gravity of
+//! all the [`Body`] values it is given, optionally with [`ThrustArc`]s.
+//!
+//! Times are in seconds after the epoch of the bodies. Positions share the
+//! inertial frame of the bodies, right-handed like the state vectors of the
+//! [`orbit`] module.
+//!
+//! # Examples
+//!
+//! ```
+//! use orbiter::nbody::{Body, Integrator, Propagator};
+//! use orbiter::orbit::StateVector;
+//! use orbiter::Vector3;
+//!
+//! const MU_EARTH: f64 = 3.986004418e14;
+//!
+//! let earth = Body::new(MU_EARTH, Vector3::zeros(), Vector3::zeros());
+//! let state = StateVector::new(Vector3::new(7000e3, 0.0, 0.0), Vector3::new(0.0, 8000.0, 0.0));
+//! let duration = 20_000.0;
+//!
+//! // With a single body, the trajectory is a Keplerian orbit
+//! let expected = state.propagate(MU_EARTH, duration).unwrap();
+//! for integrator in &[
+//!     Integrator::DormandPrince { tolerance: 1e-12 },
+//!     Integrator::Rk4 { step: 1.0 },
+//!     Integrator::Leapfrog { step: 0.1 },
+//! ] {
+//!     let propagator = Propagator::new(vec![earth]).integrator(*integrator);
+//!     let end = propagator.propagate(&state, duration).unwrap();
+//!     assert!((end.position - expected.position).norm() < 10.0);
+//! }
+//! ```
+//!
+//! [`Propagator`]: ./struct.Propagator.html
+//! [`Body`]: ./struct.Body.html
+//! [`ThrustArc`]: ./struct.ThrustArc.html
+//! [`orbit`]: ../orbit/index.html
+
+use crate::orbit::StateVector;
+use crate::{to_right_handed, ObjectKind, Vector3, WorldSnapshot, GRAVITATIONAL_CONSTANT};
+
+/// Smallest step (in s) of the adaptive integrator
+const MIN_STEP: f64 = 1e-6;
+
+/// Error returned when a trajectory could not be integrated.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum IntegrationError {
+    /// The adaptive step size became too small, e.g. when passing through
+    /// the center of a body
+    StepTooSmall,
+    /// The state became infinite or NaN
+    NonFinite,
+    /// A time, duration, step or tolerance is infinite or NaN, or an
+    /// interval, step or tolerance is not positive
+    InvalidInput,
+}
+
+impl std::fmt::Display for IntegrationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::StepTooSmall => f.write_str("the integration step became too small"),
+            Self::NonFinite => f.write_str("the integrated state is not finite"),
+            Self::InvalidInput => f.write_str("invalid integration parameters"),
+        }
+    }
+}
+
+impl std::error::Error for IntegrationError {}
+
+/// A body attracting the test particle
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Body {
+    /// Gravitational parameter, in m³/s²
+    pub mu: f64,
+    /// Position at the epoch, in m
+    pub position: Vector3,
+    /// Velocity at the epoch, in m/s
+    pub velocity: Vector3,
+}
+
+impl Body {
+    /// Creates a body from its gravitational parameter, position and
+    /// velocity at the epoch.
+    pub fn new(mu: f64, position: Vector3, velocity: Vector3) -> Self {
+        Self {
+            mu,
+            position,
+            velocity,
+        }
+    }
+
+    /// Returns the stars and planets of a snapshot, in the right-handed
+    /// global frame.
+    ///
+    /// Vessels and surface bases are skipped, as their gravity is
+    /// negligible.
+    pub fn from_snapshot(snapshot: &WorldSnapshot) -> Vec<Body> {
+        (0..snapshot.len())
+            .filter(|&i| {
+                matches!(
+                    snapshot.kind(i),
+                    Some(ObjectKind::Star) | Some(ObjectKind::Planet)
+                )
+            })
+            .map(|i| {
+                Self::new(
+                    GRAVITATIONAL_CONSTANT * snapshot.mass(i),
+                    to_right_handed(snapshot.global_pos(i)),
+                    to_right_handed(snapshot.global_velocity(i)),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Motion of the bodies during the propagation
+pub enum BodyMotion {
+    /// The bodies stay at their position at the epoch
+    Frozen,
+    /// The bodies move in straight lines at their velocity at the epoch
+    ///
+    /// Accurate for spans that are short compared to the orbital periods of
+    /// the bodies.
+    Linear,
+    /// The position of each body is given by a function of the index of the
+    /// body and the time
+    Ephemeris(Box<dyn Fn(usize, f64) -> Vector3>),
+}
+
+/// Numerical integration method
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Integrator {
+    /// Classical 4th order Runge-Kutta method, with a fixed step in s
+    Rk4 { step: f64 },
+    /// Dormand-Prince 5(4) method, with an adaptive step
+    ///
+    /// The tolerance applies to the error of each step, relative to the
+    /// position and velocity.
+    DormandPrince { tolerance: f64 },
+    /// Symplectic leapfrog (velocity Verlet) method, with a fixed step in s
+    ///
+    /// Only 2nd order, but the energy does not drift over long spans.
+    Leapfrog { step: f64 },
+}
+
+/// Constant acceleration applied between two times, e.g. an engine burn
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ThrustArc {
+    /// Start of the arc, in s after the epoch
+    pub start: f64,
+    /// End of the arc, in s after the epoch
+    pub end: f64,
+    /// Acceleration along the arc, in m/s²
+    pub acceleration: Vector3,
+}
+
+impl ThrustArc {
+    /// Creates a thrust arc.
+    pub fn new(start: f64, end: f64, acceleration: Vector3) -> Self {
+        Self {
+            start,
+            end,
+            acceleration,
+        }
+    }
+}
+
+/// Propagates test particles under the gravity of several bodies
+///
+/// Uses the Dormand-Prince integrator with a tolerance of 1e-10 and frozen
+/// bodies by default.
+pub struct Propagator {
+    bodies: Vec<Body>,
+    motion: BodyMotion,
+    integrator: Integrator,
+    arcs: Vec<ThrustArc>,
+}
+
+impl Propagator {
+    /// Creates a propagator under the gravity of bodies.
+    pub fn new(bodies: Vec<Body>) -> Self {
+        Self {
+            bodies,
+            motion: BodyMotion::Frozen,
+            integrator: Integrator::DormandPrince { tolerance: 1e-10 },
+            arcs: Vec::new(),
+        }
+    }
+
+    /// Sets the motion of the bodies.
+    pub fn motion(mut self, motion: BodyMotion) -> Self {
+        self.motion = motion;
+        self
+    }
+
+    /// Sets the integration method.
+    pub fn integrator(mut self, integrator: Integrator) -> Self {
+        self.integrator = integrator;
+        self
+    }
+
+    /// Adds a thrust arc.
+    ///
+    /// The integration steps stop at the start and end of the arcs, so that
+    /// they are accurately resolved.
+    pub fn thrust_arc(mut self, arc: ThrustArc) -> Self {
+        self.arcs.push(arc);
+        self
+    }
+
+    /// Returns the bodies.
+    pub fn bodies(&self) -> &[Body] {
+        &self.bodies
+    }
+
+    /// Returns the position of a body at a time.
+    pub fn body_position(&self, index: usize, time: f64) -> Vector3 {
+        let body = &self.bodies[index];
+        match &self.motion {
+            BodyMotion::Frozen => body.position,
+            BodyMotion::Linear => body.position + body.velocity * time,
+            BodyMotion::Ephemeris(ephemeris) => ephemeris(index, time),
+        }
+    }
+
+    /// Returns the acceleration of the test particle at a position and a
+    /// time, from the gravity of the bodies and the thrust arcs.
+    pub fn acceleration(&self, time: f64, position: &Vector3) -> Vector3 {
+        self.gravity(time, position) + self.thrust(time)
+    }
+
+    fn gravity(&self, time: f64, position: &Vector3) -> Vector3 {
+        (0..self.bodies.len())
+            .map(|i| {
+                let r = self.body_position(i, time) - position;
+                let distance = r.norm();
+                r * (self.bodies[i].mu / (distance * distance * distance))
+            })
+            .sum()
+    }
+
+    fn thrust(&self, time: f64) -> Vector3 {
+        self.arcs
+            .iter()
+            .filter(|arc| arc.start <= time && time < arc.end)
+            .map(|arc| arc.acceleration)
+            .sum()
+    }
+
+    /// Propagates a state from the epoch by a duration (in s), which is
+    /// negative to propagate backward.
+    pub fn propagate(
+        &self,
+        state: &StateVector,
+        duration: f64,
+    ) -> Result<StateVector, IntegrationError> {
+        self.propagate_between(state, 0.0, duration)
+    }
+
+    /// Propagates a state from a time to another (in s after the epoch).
+    pub fn propagate_between(
+        &self,
+        state: &StateVector,
+        start: f64,
+        end: f64,
+    ) -> Result<StateVector, IntegrationError> {
+        if !start.is_finite() || !end.is_finite() {
+            return Err(IntegrationError::InvalidInput);
+        }
+        let mut step = None;
+        self.integrate(state, start, end, &mut step)
+    }
+
+    /// Propagates a state from the epoch and returns the states every
+    /// `interval` seconds, including the initial state and the state after
+    /// `duration`.
+    ///
+    /// The interval must be positive, even when propagating backward.
+    pub fn trajectory(
+        &self,
+        state: &StateVector,
+        duration: f64,
+        interval: f64,
+    ) -> Result<Vec<(f64, StateVector)>, IntegrationError> {
+        let count = (duration.abs() / interval).ceil().max(1.0);
+        if !duration.is_finite() || interval.is_nan() || interval <= 0.0 || !count.is_finite() {
+            return Err(IntegrationError::InvalidInput);
+        }
+        let count = count as usize;
+        let mut states = vec![(0.0, *state)];
+
+        let mut current = *state;
+        let mut step = None;
+        for i in 1..=count {
+            let start = states[i - 1].0;
+            let end = if i == count {
+                duration
+            } else {
+                i as f64 * interval * duration.signum()
+            };
+            current = self.integrate(&current, start, end, &mut step)?;
+            states.push((end, current));
+        }
+        Ok(states)
+    }
+
+    /// Integrates between two times, stopping at the boundaries of the
+    /// thrust arcs.
+    ///
+    /// `step` keeps the adaptive step size between calls.
+    fn integrate(
+        &self,
+        state: &StateVector,
+        start: f64,
+        end: f64,
+        step: &mut Option<f64>,
+    ) -> Result<StateVector, IntegrationError> {
+        let mut breaks: Vec<f64> = self
+            .arcs
+            .iter()
+            .flat_map(|arc| vec![arc.start, arc.end])
+            .filter(|&time| (time - start) * (end - time) > 0.0)
+            .collect();
+        breaks.push(end);
+        if end >= start {
+            breaks.sort_by(|a, b| a.total_cmp(b));
+        } else {
+            breaks.sort_by(|a, b| b.total_cmp(a));
+        }
+
+        let valid = match self.integrator {
+            Integrator::Rk4 { step } | Integrator::Leapfrog { step } => step > 0.0,
+            Integrator::DormandPrince { tolerance } => tolerance > 0.0,
+        };
+        if !valid {
+            return Err(IntegrationError::InvalidInput);
+        }
+
+        let mut current = *state;
+        let mut time = start;
+        for next in breaks {
+            // The thrust is constant between the breaks
+            let thrust = self.thrust((time + next) / 2.0);
+            current = match self.integrator {
+                Integrator::Rk4 { step } => {
+                    self.fixed_steps(&current, time, next, step, &thrust, Self::rk4)
+                }
+                Integrator::Leapfrog { step } => {
+                    self.fixed_steps(&current, time, next, step, &thrust, Self::leapfrog)
+                }
+                Integrator::DormandPrince { tolerance } => {
+                    self.dormand_prince(&current, time, next, tolerance, &thrust, step)
+                }
+            }?;
+            time = next;
+        }
+        Ok(current)
+    }
+
+    /// Returns the time derivative of a state.
+    fn derivative(&self, time: f64, state: &StateVector, thrust: &Vector3) -> StateVector {
+        StateVector::new(state.velocity, self.gravity(time, &state.position) + thrust)
+    }
+
+    fn fixed_steps(
+        &self,
+        state: &StateVector,
+        start: f64,
+        end: f64,
+        step: f64,
+        thrust: &Vector3,
+        method: fn(&Self, &StateVector, f64, f64, &Vector3) -> StateVector,
+    ) -> Result<StateVector, IntegrationError> {
+        let count = ((end - start).abs() / step).ceil().max(1.0);
+        let h = (end - start) / count;
+        let mut current = *state;
+        for i in 0..count as usize {
+            // Time of each step computed from the start to avoid accumulating
+            // rounding errors
+            current = method(self, &current, start + i as f64 * h, h, thrust);
+            if !is_finite(&current) {
+                return Err(IntegrationError::NonFinite);
+            }
+        }
+        Ok(current)
+    }
+
+    fn rk4(&self, state: &StateVector, time: f64, h: f64, thrust: &Vector3) -> StateVector {
+        let k1 = self.derivative(time, state, thrust);
+        let k2 = self.derivative(time + h / 2.0, &(*state + k1 * (h / 2.0)), thrust);
+        let k3 = self.derivative(time + h / 2.0, &(*state + k2 * (h / 2.0)), thrust);
+        let k4 = self.derivative(time + h, &(*state + k3 * h), thrust);
+        combine(
+            *state,
+            &[
+                (h / 6.0, &k1),
+                (h / 3.0, &k2),
+                (h / 3.0, &k3),
+                (h / 6.0, &k4),
+            ],
+        )
+    }
+
+    fn leapfrog(&self, state: &StateVector, time: f64, h: f64, thrust: &Vector3) -> StateVector {
+        let half = state.velocity + (self.gravity(time, &state.position) + thrust) * (h / 2.0);
+        let position = state.position + half * h;
+        let velocity = half + (self.gravity(time + h, &position) + thrust) * (h / 2.0);
+        StateVector::new(position, velocity)
+    }
+
+    fn dormand_prince(
+        &self,
+        state: &StateVector,
+        start: f64,
+        end: f64,
+        tolerance: f64,
+        thrust: &Vector3,
+        step: &mut Option<f64>,
+    ) -> Result<StateVector, IntegrationError> {
+        let direction = (end - start).signum();
+        let mut current = *state;
+        let mut time = start;
+        let mut k1 = self.derivative(time, &current, thrust);
+        let mut h = step.unwrap_or_else(|| {
+            // Time for the velocity to change by 1%
+            let scale = k1.position.norm() / k1.velocity.norm();
+            if scale.is_finite() && scale > 0.0 {
+                0.01 * scale
+            } else {
+                (end - start).abs()
+            }
+        });
+
+        while (end - time) * direction > 0.0 {
+            let last = h >= (end - time).abs();
+            let dt = if last { end - time } else { h * direction };
+
+            let k2 = self.derivative(time + dt / 5.0, &(current + k1 * (dt / 5.0)), thrust);
+            let k3 = self.derivative(
+                time + dt * 3.0 / 10.0,
+                &combine(current, &[(dt * 3.0 / 40.0, &k1), (dt * 9.0 / 40.0, &k2)]),
+                thrust,
+            );
+            let k4 = self.derivative(
+                time + dt * 4.0 / 5.0,
+                &combine(
+                    current,
+                    &[
+                        (dt * 44.0 / 45.0, &k1),
+                        (dt * -56.0 / 15.0, &k2),
+                        (dt * 32.0 / 9.0, &k3),
+                    ],
+                ),
+                thrust,
+            );
+            let k5 = self.derivative(
+                time + dt * 8.0 / 9.0,
+                &combine(
+                    current,
+                    &[
+                        (dt * 19372.0 / 6561.0, &k1),
+                        (dt * -25360.0 / 2187.0, &k2),
+                        (dt * 64448.0 / 6561.0, &k3),
+                        (dt * -212.0 / 729.0, &k4),
+                    ],
+                ),
+                thrust,
+            );
+            let k6 = self.derivative(
+                time + dt,
+                &combine(
+                    current,
+                    &[
+                        (dt * 9017.0 / 3168.0, &k1),
+                        (dt * -355.0 / 33.0, &k2),
+                        (dt * 46732.0 / 5247.0, &k3),
+                        (dt * 49.0 / 176.0, &k4),
+                        (dt * -5103.0 / 18656.0, &k5),
+                    ],
+                ),
+                thrust,
+            );
+            let next = combine(
+                current,
+                &[
+                    (dt * 35.0 / 384.0, &k1),
+                    (dt * 500.0 / 1113.0, &k3),
+                    (dt * 125.0 / 192.0, &k4),
+                    (dt * -2187.0 / 6784.0, &k5),
+                    (dt * 11.0 / 84.0, &k6),
+                ],
+            );
+            if !is_finite(&next) {
+                return Err(IntegrationError::NonFinite);
+            }
+            let k7 = self.derivative(time + dt, &next, thrust);
+
+            // Difference between the 5th and 4th order solutions
+            let error = combine(
+                StateVector::new(Vector3::zeros(), Vector3::zeros()),
+                &[
+                    (dt * 71.0 / 57600.0, &k1),
+                    (dt * -71.0 / 16695.0, &k3),
+                    (dt * 71.0 / 1920.0, &k4),
+                    (dt * -17253.0 / 339200.0, &k5),
+                    (dt * 22.0 / 525.0, &k6),
+                    (-dt / 40.0, &k7),
+                ],
+            );
+            // Scales of at least 1 m and 1 m/s, for states close to the origin
+            let position_scale = current.position.norm().max(next.position.norm()).max(1.0);
+            let velocity_scale = current.velocity.norm().max(next.velocity.norm()).max(1.0);
+            let ratio = (error.position.norm() / (tolerance * position_scale))
+                .max(error.velocity.norm() / (tolerance * velocity_scale));
+
+            if ratio <= 1.0 {
+                current = next;
+                time = if last { end } else { time + dt };
+                k1 = k7;
+            }
+            let factor = if ratio > 0.0 {
+                (0.9 * ratio.powf(-0.2)).clamp(0.2, 5.0)
+            } else {
+                5.0
+            };
+            // Keep the full step size when the last step was shortened
+            if !(last && ratio <= 1.0) {
+                h = dt.abs() * factor;
+            }
+            if h < MIN_STEP {
+                return Err(IntegrationError::StepTooSmall);
+            }
+        }
+
+        *step = Some(h);
+        Ok(current)
+    }
+}
+
+/// Returns `state + Σ factor × derivative`.
+fn combine(state: StateVector, terms: &[(f64, &StateVector)]) -> StateVector {
+    terms.iter().fold(state, |sum, (factor, derivative)| {
+        sum + **derivative * *factor
+    })
+}
+
+fn is_finite(state: &StateVector) -> bool {
+    state
+        .position
+        .iter()
+        .chain(state.velocity.iter())
+        .all(|x| x.is_finite())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{periapsis_state, MU_EARTH};
+
+    fn earth() -> Body {
+        Body::new(MU_EARTH, Vector3::zeros(), Vector3::zeros())
+    }
+
+    fn leo() -> StateVector {
+        periapsis_state(7000e3, 0.1, 0.1)
+    }
+
+    fn rest() -> StateVector {
+        StateVector::new(Vector3::zeros(), Vector3::zeros())
+    }
+
+    #[test]
+    fn single_body_matches_kepler() {
+        let state = leo();
+        let duration = 3.0 * state.to_elements(MU_EARTH).period().unwrap();
+        let expected = state.propagate(MU_EARTH, duration).unwrap();
+        for (integrator, tolerance) in &[
+            (Integrator::DormandPrince { tolerance: 1e-12 }, 1.0),
+            (Integrator::Rk4 { step: 2.0 }, 1.0),
+            (Integrator::Leapfrog { step: 0.5 }, 100.0),
+        ] {
+            let propagator = Propagator::new(vec![earth()]).integrator(*integrator);
+            let end = propagator.propagate(&state, duration).unwrap();
+            assert!(
+                (end.position - expected.position).norm() < *tolerance,
+                "{:?}",
+                integrator
+            );
+        }
+    }
+
+    #[test]
+    fn backward_propagation_returns_to_start() {
+        let propagator = Propagator::new(vec![earth()])
+            .integrator(Integrator::DormandPrince { tolerance: 1e-12 });
+        let end = propagator.propagate(&leo(), 10_000.0).unwrap();
+        let start = propagator.propagate_between(&end, 10_000.0, 0.0).unwrap();
+        assert!((start.position - leo().position).norm() < 0.1);
+        assert!((start.velocity - leo().velocity).norm() < 1e-4);
+    }
+
+    #[test]
+    fn thrust_arc_is_resolved_exactly() {
+        let arc = ThrustArc::new(10.0, 20.0, Vector3::new(1.0, 0.0, 0.0));
+        for integrator in &[
+            Integrator::DormandPrince { tolerance: 1e-10 },
+            Integrator::Rk4 { step: 3.0 },
+            Integrator::Leapfrog { step: 3.0 },
+        ] {
+            let propagator = Propagator::new(vec![])
+                .integrator(*integrator)
+                .thrust_arc(arc);
+            let end = propagator.propagate(&rest(), 30.0).unwrap();
+            assert!((end.position - Vector3::new(150.0, 0.0, 0.0)).norm() < 1e-9);
+            assert!((end.velocity - Vector3::new(10.0, 0.0, 0.0)).norm() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn trajectory_includes_both_ends() {
+        let propagator = Propagator::new(vec![]);
+        let state = StateVector::new(Vector3::zeros(), Vector3::new(1.0, 0.0, 0.0));
+
+        let times: Vec<f64> = propagator
+            .trajectory(&state, 30.0, 7.0)
+            .unwrap()
+            .iter()
+            .map(|(time, _)| *time)
+            .collect();
+        assert_eq!(times, vec![0.0, 7.0, 14.0, 21.0, 28.0, 30.0]);
+
+        let backward = propagator.trajectory(&state, -10.0, 5.0).unwrap();
+        assert_eq!(backward.len(), 3);
+        assert_eq!(backward[2].0, -10.0);
+        assert!((backward[2].1.position.x + 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn invalid_inputs_are_rejected() {
+        let propagator = Propagator::new(vec![earth()]);
+        for interval in &[0.0, -1.0, f64::NAN] {
+            assert_eq!(
+                propagator.trajectory(&leo(), 100.0, *interval),
+                Err(IntegrationError::InvalidInput)
+            );
+        }
+        assert_eq!(
+            propagator.trajectory(&leo(), f64::INFINITY, 10.0),
+            Err(IntegrationError::InvalidInput)
+        );
+        assert_eq!(
+            propagator.propagate(&leo(), f64::NAN),
+            Err(IntegrationError::InvalidInput)
+        );
+        assert_eq!(
+            Propagator::new(vec![earth()])
+                .integrator(Integrator::Rk4 { step: 0.0 })
+                .propagate(&leo(), 100.0),
+            Err(IntegrationError::InvalidInput)
+        );
+    }
+}
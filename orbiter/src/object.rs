@@ -1,8 +1,12 @@
-use crate::{oapic_VECTOR3, Vector3};
+use crate::frame::Framed;
+#[cfg(feature = "units")]
+use crate::units::Units;
+use crate::win::minwindef::DWORD;
+use crate::{oapic_MATRIX3, oapic_VECTOR3, Matrix3, Vector3};
 use crate::{Planet, Star, SurfaceBase, Vessel};
+use nalgebra::{Rotation3, UnitQuaternion};
 use std::ffi::{CStr, CString};
 use std::os::raw::{c_char, c_int};
-use winapi::shared::minwindef::DWORD;
 
 #[doc(hidden)]
 pub enum _OBJHANDLE {}
@@ -77,8 +81,141 @@ pub trait ObjectTrait {
         unsafe { oapic_oapiGetRelativeVel(self.handle(), reference.handle(), &mut vel) };
         vel.into()
     }
+
+    /// Returns the rotation matrix of the object in the global frame.
+    ///
+    /// The matrix transforms vectors from the object's local frame to the
+    /// global frame. Both frames use Orbiter's left-handed convention, use
+    /// [`global_rotation`] for a right-handed rotation.
+    ///
+    /// [`global_rotation`]: #method.global_rotation
+    fn rotation_matrix(&self) -> Matrix3 {
+        let mut mat = oapic_MATRIX3::new();
+        unsafe { oapic_oapiGetRotationMatrix(self.handle(), &mut mat) };
+        mat.into()
+    }
+
+    /// Returns the orientation of the object in the global frame, converted
+    /// to right-handed frames.
+    ///
+    /// The rotation transforms vectors from the object's local frame to the
+    /// global frame, where both frames were converted with [`to_right_handed`].
+    ///
+    /// [`to_right_handed`]: ./fn.to_right_handed.html
+    fn global_rotation(&self) -> Rotation3<f64> {
+        rotation_to_right_handed(&self.rotation_matrix())
+    }
+
+    /// Returns the orientation of the object in the global frame as a
+    /// quaternion, converted to right-handed frames.
+    ///
+    /// See [`global_rotation`].
+    ///
+    /// [`global_rotation`]: #method.global_rotation
+    fn global_attitude(&self) -> UnitQuaternion<f64> {
+        UnitQuaternion::from_rotation_matrix(&self.global_rotation())
+    }
+
+    /// Returns accessors returning vectors tagged with their reference frame.
+    ///
+    /// See the [`frame`] module.
+    ///
+    /// [`frame`]: ./frame/index.html
+    fn framed(&self) -> Framed<'_, Self>
+    where
+        Self: Sized,
+    {
+        Framed::new(self)
+    }
+
+    /// Returns accessors returning physical quantities.
+    ///
+    /// Only available with the `units` feature. See the [`units`] module.
+    ///
+    /// [`units`]: ./units/index.html
+    #[cfg(feature = "units")]
+    fn units(&self) -> Units<'_, Self>
+    where
+        Self: Sized,
+    {
+        Units::new(self)
+    }
+}
+
+/// Converts a vector from Orbiter's left-handed frames to right-handed frames.
+///
+/// Orbiter uses left-handed frames, while nalgebra assumes right-handed ones.
+/// The conversion swaps the y and z axes, so that the z axis of the global
+/// frame points to the ecliptic north. The conversion is its own inverse.
+///
+/// Rotation matrices are converted with [`rotation_to_right_handed`], and
+/// pseudovectors such as angular velocities with
+/// [`pseudovector_to_right_handed`].
+///
+/// [`rotation_to_right_handed`]: ./fn.rotation_to_right_handed.html
+/// [`pseudovector_to_right_handed`]: ./fn.pseudovector_to_right_handed.html
+///
+/// # Examples
+///
+/// ```
+/// use orbiter::{rotation_to_right_handed, to_right_handed, Matrix3, Vector3};
+///
+/// let v = Vector3::new(1.0, 2.0, 3.0);
+/// assert_eq!(to_right_handed(v), Vector3::new(1.0, 3.0, 2.0));
+/// assert_eq!(to_right_handed(to_right_handed(v)), v);
+///
+/// // Rotation by 90° around the y axis in Orbiter's convention
+/// let rot = Matrix3::new(0.0, 0.0, -1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 0.0);
+/// let rh = rotation_to_right_handed(&rot);
+/// assert!((rh * to_right_handed(v) - to_right_handed(rot * v)).norm() < 1e-12);
+/// ```
+pub fn to_right_handed(v: Vector3) -> Vector3 {
+    Vector3::new(v.x, v.z, v.y)
 }
 
+/// Converts a vector from right-handed frames to Orbiter's left-handed frames.
+///
+/// This is the same conversion as [`to_right_handed`].
+///
+/// [`to_right_handed`]: ./fn.to_right_handed.html
+pub fn to_left_handed(v: Vector3) -> Vector3 {
+    to_right_handed(v)
+}
+
+/// Converts a pseudovector, e.g. an angular velocity, from Orbiter's
+/// left-handed frames to right-handed frames.
+///
+/// Pseudovectors change sign when the handedness of the frame changes, in
+/// addition to the axes swap of [`to_right_handed`]. The conversion is its
+/// own inverse.
+///
+/// [`to_right_handed`]: ./fn.to_right_handed.html
+pub fn pseudovector_to_right_handed(v: Vector3) -> Vector3 {
+    -to_right_handed(v)
+}
+
+/// Converts a rotation matrix between Orbiter's left-handed frames to a
+/// rotation between right-handed frames.
+///
+/// `mat` must be a rotation matrix, i.e. orthogonal with a determinant of 1,
+/// as returned by Orbiter. The result is not renormalized.
+pub fn rotation_to_right_handed(mat: &Matrix3) -> Rotation3<f64> {
+    let swap = Matrix3::new(1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0);
+    // Both swaps flip the sign of the determinant, so the result is a proper
+    // rotation whenever `mat` is one
+    debug_assert!(
+        (mat.transpose() * mat - Matrix3::identity()).norm() < 1e-6
+            && (mat.determinant() - 1.0).abs() < 1e-6,
+        "not a rotation matrix: {}",
+        mat
+    );
+    Rotation3::from_matrix_unchecked(swap * mat * swap)
+}
+
+/// An object of the simulation.
+///
+/// Objects are compared by identity, so they can be used as map keys.
+#[derive(PartialEq, Eq, Hash)]
 pub enum Object {
     /// A star
     Star(Star),
@@ -108,7 +245,7 @@ impl ObjectTrait for Object {
 }
 
 impl Object {
-    fn from(handle: OBJHANDLE) -> Option<Self> {
+    pub(crate) fn from(handle: OBJHANDLE) -> Option<Self> {
         if handle.is_null() {
             return None;
         }
@@ -162,11 +299,11 @@ impl Iterator for ObjectIterator {
     type Item = Object;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.0.next().map(Object::from).flatten()
+        self.0.next().and_then(Object::from)
     }
 }
 
-#[link(name = "orbiter_c")]
+#[cfg_attr(windows, link(name = "orbiter_c"))]
 extern "C" {
     fn oapic_oapiGetObjectByName(name: *const c_char) -> OBJHANDLE;
     fn oapic_oapiGetObjectByIndex(index: c_int) -> OBJHANDLE;
@@ -179,4 +316,65 @@ extern "C" {
     fn oapic_oapiGetGlobalVel(handle: OBJHANDLE, vel: *mut oapic_VECTOR3);
     fn oapic_oapiGetRelativePos(handle: OBJHANDLE, reference: OBJHANDLE, pos: *mut oapic_VECTOR3);
     fn oapic_oapiGetRelativeVel(handle: OBJHANDLE, reference: OBJHANDLE, vel: *mut oapic_VECTOR3);
+    fn oapic_oapiGetRotationMatrix(handle: OBJHANDLE, mat: *mut oapic_MATRIX3);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Rotation by 90° around the y axis in Orbiter's convention
+    fn rotation() -> Matrix3 {
+        Matrix3::new(0.0, 0.0, -1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 0.0)
+    }
+
+    #[test]
+    fn conversions_are_their_own_inverse() {
+        let v = Vector3::new(1.0, -2.0, 3.0);
+        assert_eq!(to_left_handed(to_right_handed(v)), v);
+        assert_eq!(to_right_handed(to_left_handed(v)), v);
+        assert_eq!(
+            pseudovector_to_right_handed(pseudovector_to_right_handed(v)),
+            v
+        );
+    }
+
+    #[test]
+    fn rotations_swap_their_axes() {
+        // Rotation by 90° around the right-handed z axis
+        let rh = rotation_to_right_handed(&rotation());
+        let expected = Matrix3::new(0.0, -1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0);
+        assert!((rh.matrix() - expected).norm() < 1e-12);
+        assert!((rh.matrix().determinant() - 1.0).abs() < 1e-12);
+
+        let v = Vector3::new(1.0, 2.0, 3.0);
+        assert!((rh * to_right_handed(v) - to_right_handed(rotation() * v)).norm() < 1e-12);
+    }
+
+    #[test]
+    fn angular_velocities_change_sign() {
+        // Spin around Orbiter's y axis, the right-handed z axis
+        let angular_velocity = Vector3::new(0.0, 0.5, 0.0);
+        assert_eq!(
+            pseudovector_to_right_handed(angular_velocity),
+            Vector3::new(0.0, 0.0, -0.5)
+        );
+
+        // Velocities of a rotating point agree in both frames
+        let position = Vector3::new(2.0, 1.0, -3.0);
+        let velocity = angular_velocity.cross(&position);
+        let rh = pseudovector_to_right_handed(angular_velocity).cross(&to_right_handed(position));
+        assert!((rh - to_right_handed(velocity)).norm() < 1e-12);
+    }
+
+    #[test]
+    fn attitude_quaternions_match_the_rotation() {
+        let rh = rotation_to_right_handed(&rotation());
+        let attitude = UnitQuaternion::from_rotation_matrix(&rh);
+        assert!((attitude.to_rotation_matrix().matrix() - rh.matrix()).norm() < 1e-12);
+        assert!((attitude.angle() - std::f64::consts::FRAC_PI_2).abs() < 1e-12);
+
+        let v = Vector3::new(1.0, 2.0, 3.0);
+        assert!((attitude * to_right_handed(v) - to_right_handed(rotation() * v)).norm() < 1e-12);
+    }
 }
@@ -0,0 +1,559 @@
+//! Keplerian orbits.
+//!
+//! Conversions between state vectors and classical orbital elements, for the
+//! two-body problem. The functions of this module are pure Rust and do not
+//! call Orbiter, so they can be used offline, e.g. in tests.
+//!
+//! Unlike the rest of the crate, this module uses right-handed frames: the
+//! reference plane is the xy plane and the z axis points to its north. Use
+//! [`StateVector::relative`] or [`to_right_handed`] to convert vectors
+//! returned by Orbiter. With Orbiter's global frame, the reference plane is
+//! the ecliptic.
+//!
+//! Angles are in radians, distances in meters and times in seconds.
+//!
+//! # Examples
+//!
+//! ```
+//! use orbiter::orbit::{OrbitalElements, StateVector};
+//! use orbiter::Vector3;
+//!
+//! const MU_EARTH: f64 = 3.986004418e14;
+//!
+//! // Circular orbit at 400 km altitude, inclined by 51.6°
+//! let radius = 6_771e3;
+//! let speed = (MU_EARTH / radius).sqrt();
+//! let inclination = 51.6f64.to_radians();
+//! let state = StateVector::new(
+//!     Vector3::new(radius, 0.0, 0.0),
+//!     Vector3::new(0.0, speed * inclination.cos(), speed * inclination.sin()),
+//! );
+//!
+//! let elements = state.to_elements(MU_EARTH);
+//! assert!((elements.semi_major_axis() - radius).abs() < 1e-3);
+//! assert!(elements.eccentricity < 1e-9);
+//! assert!((elements.inclination - inclination).abs() < 1e-12);
+//! assert!((elements.period().unwrap() - 5_544.0).abs() < 1.0);
+//!
+//! let back = elements.to_state_vector();
+//! assert!((back.position - state.position).norm() < 1e-6);
+//! assert!((back.velocity - state.velocity).norm() < 1e-9);
+//! ```
+//!
+//! [`StateVector::relative`]: ./struct.StateVector.html#method.relative
+//! [`to_right_handed`]: ../fn.to_right_handed.html
+
+use crate::{to_right_handed, CelestialBodyTrait, Matrix3, ObjectTrait, Vector3, Vessel};
+use nalgebra::Rotation3;
+use std::f64::consts::PI;
+use std::ops::{Add, Mul, Sub};
+
+/// Tolerance below which an orbit is considered circular, equatorial or
+/// parabolic
+const TOLERANCE: f64 = 1e-11;
+
+/// Position and velocity of an object relative to a central body
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct StateVector {
+    /// Position, in m
+    pub position: Vector3,
+    /// Velocity, in m/s
+    pub velocity: Vector3,
+}
+
+impl StateVector {
+    /// Creates a state vector.
+    pub fn new(position: Vector3, velocity: Vector3) -> Self {
+        Self { position, velocity }
+    }
+
+    /// Returns the state of an object relative to a reference object.
+    ///
+    /// The vectors are converted from Orbiter's global frame to a
+    /// right-handed ecliptic frame, with the z axis pointing to the ecliptic
+    /// north.
+    pub fn relative(object: &dyn ObjectTrait, reference: &dyn ObjectTrait) -> Self {
+        Self {
+            position: to_right_handed(object.relative_position(reference)),
+            velocity: to_right_handed(object.relative_velocity(reference)),
+        }
+    }
+
+    /// Returns the rotation from the orbital frame of the state to the
+    /// reference frame.
+    ///
+    /// The x axis of the orbital frame points along the velocity (prograde),
+    /// the y axis along the angular momentum (orbit normal) and the z axis
+    /// completes the frame, pointing away from the central body (radial).
+    pub fn orbital_frame(&self) -> Rotation3<f64> {
+        let prograde = self.velocity.normalize();
+        let normal = self.position.cross(&self.velocity).normalize();
+        let radial = prograde.cross(&normal);
+        Rotation3::from_matrix_unchecked(Matrix3::from_columns(&[prograde, normal, radial]))
+    }
+
+    /// Converts the state vector to orbital elements around a central body
+    /// with the gravitational parameter `mu` (in m³/s²).
+    pub fn to_elements(&self, mu: f64) -> OrbitalElements {
+        OrbitalElements::from_state_vector(self, mu)
+    }
+}
+
+impl Add for StateVector {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(
+            self.position + other.position,
+            self.velocity + other.velocity,
+        )
+    }
+}
+
+impl Sub for StateVector {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(
+            self.position - other.position,
+            self.velocity - other.velocity,
+        )
+    }
+}
+
+impl Mul<f64> for StateVector {
+    type Output = Self;
+
+    fn mul(self, factor: f64) -> Self {
+        Self::new(self.position * factor, self.velocity * factor)
+    }
+}
+
+/// Classical orbital elements
+///
+/// The size of the orbit is stored as the semi-latus rectum rather than the
+/// semi-major axis, so that parabolic orbits can be represented.
+///
+/// Elements that are undefined are set to zero:
+/// * for equatorial orbits, the longitude of the ascending node, and the
+///   argument of periapsis is measured from the x axis;
+/// * for circular orbits, the argument of periapsis, and the true anomaly is
+///   measured from the ascending node, or from the x axis if the orbit is also
+///   equatorial.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct OrbitalElements {
+    /// Gravitational parameter of the central body, in m³/s²
+    pub mu: f64,
+    /// Semi-latus rectum, in m
+    pub semi_latus_rectum: f64,
+    /// Eccentricity
+    pub eccentricity: f64,
+    /// Inclination, between 0 and π
+    pub inclination: f64,
+    /// Longitude of the ascending node (Ω), between 0 and 2π
+    pub longitude_of_ascending_node: f64,
+    /// Argument of periapsis (ω), between 0 and 2π
+    pub argument_of_periapsis: f64,
+    /// True anomaly (ν), between 0 and 2π
+    pub true_anomaly: f64,
+}
+
+impl OrbitalElements {
+    /// Creates orbital elements from the semi-major axis.
+    ///
+    /// The semi-major axis is negative for hyperbolic orbits. Parabolic orbits
+    /// cannot be created this way, set the semi-latus rectum instead.
+    pub fn new(
+        mu: f64,
+        semi_major_axis: f64,
+        eccentricity: f64,
+        inclination: f64,
+        longitude_of_ascending_node: f64,
+        argument_of_periapsis: f64,
+        true_anomaly: f64,
+    ) -> Self {
+        Self {
+            mu,
+            semi_latus_rectum: semi_major_axis * (1.0 - eccentricity * eccentricity),
+            eccentricity,
+            inclination,
+            longitude_of_ascending_node,
+            argument_of_periapsis,
+            true_anomaly,
+        }
+    }
+
+    /// Computes the orbital elements of a state vector around a central body
+    /// with the gravitational parameter `mu` (in m³/s²).
+    ///
+    /// The state must have a nonzero angular momentum. Radial trajectories,
+    /// including states at rest or at the center of the body, have no orbit
+    /// plane and give NaN elements. This is only checked in debug builds.
+    pub fn from_state_vector(state: &StateVector, mu: f64) -> Self {
+        let r = state.position;
+        let v = state.velocity;
+
+        let h = r.cross(&v);
+        let h_norm = h.norm();
+        debug_assert!(
+            h_norm > 0.0,
+            "no orbital elements for a state without angular momentum"
+        );
+        let h_unit = h / h_norm;
+        let e = ((v.norm_squared() - mu / r.norm()) * r - r.dot(&v) * v) / mu;
+        let eccentricity = e.norm();
+
+        // Reference direction for the angles in the orbit plane
+        let node = Vector3::z().cross(&h);
+        let equatorial = node.norm() < TOLERANCE * h_norm;
+        let (longitude_of_ascending_node, reference) = if equatorial {
+            (0.0, Vector3::x())
+        } else {
+            (normalize_angle(node.y.atan2(node.x)), node)
+        };
+
+        let circular = eccentricity < TOLERANCE;
+        let (argument_of_periapsis, true_anomaly) = if circular {
+            (0.0, angle_between(&reference, &r, &h_unit))
+        } else {
+            (
+                angle_between(&reference, &e, &h_unit),
+                angle_between(&e, &r, &h_unit),
+            )
+        };
+
+        Self {
+            mu,
+            semi_latus_rectum: h_norm * h_norm / mu,
+            eccentricity,
+            inclination: (h.z / h_norm).clamp(-1.0, 1.0).acos(),
+            longitude_of_ascending_node,
+            argument_of_periapsis,
+            true_anomaly,
+        }
+    }
+
+    /// Converts the orbital elements to a state vector.
+    pub fn to_state_vector(&self) -> StateVector {
+        let p = self.semi_latus_rectum;
+        let e = self.eccentricity;
+        let (sin_nu, cos_nu) = self.true_anomaly.sin_cos();
+
+        let radius = p / (1.0 + e * cos_nu);
+        let position = Vector3::new(radius * cos_nu, radius * sin_nu, 0.0);
+        let velocity = (self.mu / p).sqrt() * Vector3::new(-sin_nu, e + cos_nu, 0.0);
+
+        let rotation = self.perifocal_rotation();
+        StateVector::new(rotation * position, rotation * velocity)
+    }
+
+    /// Returns the rotation from the perifocal frame to the reference frame.
+    ///
+    /// In the perifocal frame, the x axis points to the periapsis and the z
+    /// axis along the angular momentum.
+    pub fn perifocal_rotation(&self) -> Rotation3<f64> {
+        Rotation3::from_axis_angle(&Vector3::z_axis(), self.longitude_of_ascending_node)
+            * Rotation3::from_axis_angle(&Vector3::x_axis(), self.inclination)
+            * Rotation3::from_axis_angle(&Vector3::z_axis(), self.argument_of_periapsis)
+    }
+
+    /// Checks if the orbit is parabolic.
+    pub fn is_parabolic(&self) -> bool {
+        (self.eccentricity - 1.0).abs() < TOLERANCE
+    }
+
+    /// Checks if the orbit is hyperbolic.
+    pub fn is_hyperbolic(&self) -> bool {
+        !self.is_parabolic() && self.eccentricity > 1.0
+    }
+
+    /// Checks if the orbit is closed, i.e. circular or elliptic.
+    pub fn is_closed(&self) -> bool {
+        !self.is_parabolic() && self.eccentricity < 1.0
+    }
+
+    /// Returns the semi-major axis (a), in m.
+    ///
+    /// The semi-major axis is negative for hyperbolic orbits, and infinite for
+    /// parabolic orbits.
+    pub fn semi_major_axis(&self) -> f64 {
+        if self.is_parabolic() {
+            f64::INFINITY
+        } else {
+            self.semi_latus_rectum / (1.0 - self.eccentricity * self.eccentricity)
+        }
+    }
+
+    /// Returns the distance of the periapsis from the center of the central
+    /// body, in m.
+    pub fn periapsis(&self) -> f64 {
+        self.semi_latus_rectum / (1.0 + self.eccentricity)
+    }
+
+    /// Returns the distance of the apoapsis from the center of the central
+    /// body, in m, or `None` if the orbit is open.
+    pub fn apoapsis(&self) -> Option<f64> {
+        if self.is_closed() {
+            Some(self.semi_latus_rectum / (1.0 - self.eccentricity))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the orbital period, in s, or `None` if the orbit is open.
+    pub fn period(&self) -> Option<f64> {
+        if self.is_closed() {
+            Some(2.0 * PI / self.mean_motion())
+        } else {
+            None
+        }
+    }
+
+    /// Returns the mean motion, in rad/s.
+    ///
+    /// For parabolic orbits, this is the rate of the parabolic mean anomaly.
+    pub fn mean_motion(&self) -> f64 {
+        if self.is_parabolic() {
+            2.0 * (self.mu / self.semi_latus_rectum.powi(3)).sqrt()
+        } else {
+            (self.mu / self.semi_major_axis().abs().powi(3)).sqrt()
+        }
+    }
+
+    /// Returns the specific orbital energy, in J/kg.
+    pub fn specific_energy(&self) -> f64 {
+        (self.eccentricity * self.eccentricity - 1.0) * self.mu / (2.0 * self.semi_latus_rectum)
+    }
+
+    /// Returns the eccentric anomaly.
+    ///
+    /// For hyperbolic orbits, this is the hyperbolic anomaly (H), and for
+    /// parabolic orbits the parabolic anomaly (D = tan(ν/2)). The elliptic
+    /// eccentric anomaly is between 0 and 2π.
+    pub fn eccentric_anomaly(&self) -> f64 {
+        let e = self.eccentricity;
+        let nu = self.true_anomaly;
+        if self.is_parabolic() {
+            (nu / 2.0).tan()
+        } else if e < 1.0 {
+            let (sin_nu, cos_nu) = nu.sin_cos();
+            normalize_angle(((1.0 - e * e).sqrt() * sin_nu).atan2(e + cos_nu))
+        } else {
+            2.0 * (((e - 1.0) / (e + 1.0)).sqrt() * (nu / 2.0).tan()).atanh()
+        }
+    }
+
+    /// Returns the mean anomaly (M).
+    ///
+    /// For closed orbits, the mean anomaly is between 0 and 2π. For open
+    /// orbits, it is negative before the periapsis.
+    pub fn mean_anomaly(&self) -> f64 {
+        let e = self.eccentricity;
+        let anomaly = self.eccentric_anomaly();
+        if self.is_parabolic() {
+            anomaly + anomaly.powi(3) / 3.0
+        } else if e < 1.0 {
+            normalize_angle(anomaly - e * anomaly.sin())
+        } else {
+            e * anomaly.sinh() - anomaly
+        }
+    }
+
+    /// Returns the time since the last periapsis passage, in s.
+    ///
+    /// For open orbits, the time is negative before the periapsis.
+    pub fn time_since_periapsis(&self) -> f64 {
+        self.mean_anomaly() / self.mean_motion()
+    }
+}
+
+impl Vessel {
+    /// Returns the orbital elements of the vessel around a celestial body.
+    ///
+    /// The elements are relative to the ecliptic, see [`StateVector::relative`].
+    ///
+    /// [`StateVector::relative`]: ./orbit/struct.StateVector.html#method.relative
+    pub fn orbit_around<B: CelestialBodyTrait>(&self, body: &B) -> OrbitalElements {
+        StateVector::relative(self, body).to_elements(body.gravitational_parameter())
+    }
+}
+
+/// Normalizes an angle between 0 and 2π.
+pub(crate) fn normalize_angle(angle: f64) -> f64 {
+    let angle = angle % (2.0 * PI);
+    if angle < 0.0 {
+        angle + 2.0 * PI
+    } else {
+        angle
+    }
+}
+
+/// Returns the angle from `from` to `to` around the `axis` unit vector,
+/// between 0 and 2π.
+pub(crate) fn angle_between(from: &Vector3, to: &Vector3, axis: &Vector3) -> f64 {
+    normalize_angle(from.cross(to).dot(axis).atan2(from.dot(to)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::MU_EARTH;
+
+    const RADIUS: f64 = 7000e3;
+
+    /// Returns a state at a radius and an angle from the x axis in the xy
+    /// plane, with a speed relative to the circular speed and a flight path
+    /// angle (positive when ascending).
+    fn planar_state(
+        angle: f64,
+        speed_ratio: f64,
+        flight_path: f64,
+        retrograde: bool,
+    ) -> StateVector {
+        let radial = Vector3::new(angle.cos(), angle.sin(), 0.0);
+        let mut horizontal = Vector3::new(-angle.sin(), angle.cos(), 0.0);
+        if retrograde {
+            horizontal = -horizontal;
+        }
+        let speed = speed_ratio * (MU_EARTH / RADIUS).sqrt();
+        StateVector::new(
+            RADIUS * radial,
+            speed * (flight_path.cos() * horizontal + flight_path.sin() * radial),
+        )
+    }
+
+    fn assert_round_trip(state: &StateVector) -> OrbitalElements {
+        let elements = state.to_elements(MU_EARTH);
+        let back = elements.to_state_vector();
+        assert!(
+            (back.position - state.position).norm() < 1e-6,
+            "{:?} {:?}",
+            back,
+            state
+        );
+        assert!((back.velocity - state.velocity).norm() < 1e-9);
+        elements
+    }
+
+    #[test]
+    fn circular_equatorial() {
+        let angle = 30.0_f64.to_radians();
+        let elements = assert_round_trip(&planar_state(angle, 1.0, 0.0, false));
+        assert!(elements.eccentricity < TOLERANCE);
+        assert!((elements.semi_major_axis() - RADIUS).abs() < 1e-3);
+        assert_eq!(elements.inclination, 0.0);
+        assert_eq!(elements.longitude_of_ascending_node, 0.0);
+        assert_eq!(elements.argument_of_periapsis, 0.0);
+        // Measured from the x axis
+        assert!((elements.true_anomaly - angle).abs() < 1e-12);
+        assert!((elements.mean_anomaly() - angle).abs() < 1e-12);
+    }
+
+    #[test]
+    fn retrograde_equatorial() {
+        let state = planar_state(30.0_f64.to_radians(), 1.2, 0.3, true);
+        let elements = assert_round_trip(&state);
+        assert!((elements.inclination - PI).abs() < 1e-12);
+        assert_eq!(elements.longitude_of_ascending_node, 0.0);
+        assert!(elements.eccentricity > 0.1);
+        assert!(elements.is_closed());
+    }
+
+    #[test]
+    fn polar() {
+        // Crosses the equator northward along the y axis
+        let speed = 1.1 * (MU_EARTH / RADIUS).sqrt();
+        let state = StateVector::new(
+            Vector3::new(0.0, RADIUS, 0.0),
+            Vector3::new(0.0, 0.0, speed),
+        );
+        let elements = assert_round_trip(&state);
+        assert!((elements.inclination - PI / 2.0).abs() < 1e-12);
+        assert!((elements.longitude_of_ascending_node - PI / 2.0).abs() < 1e-12);
+        // Starts at the periapsis, on the ascending node
+        assert!(
+            elements
+                .argument_of_periapsis
+                .min(2.0 * PI - elements.argument_of_periapsis)
+                < 1e-9
+        );
+        assert!(elements.true_anomaly.min(2.0 * PI - elements.true_anomaly) < 1e-9);
+        assert!((elements.periapsis() - RADIUS).abs() < 1e-3);
+    }
+
+    #[test]
+    fn parabolic() {
+        let escape = 2.0_f64.sqrt();
+        for flight_path in [-0.4, 0.0, 0.4] {
+            let state = planar_state(1.0, escape, flight_path, false);
+            let elements = assert_round_trip(&state);
+            assert!(elements.is_parabolic());
+            assert!(!elements.is_closed() && !elements.is_hyperbolic());
+            assert_eq!(elements.semi_major_axis(), f64::INFINITY);
+            assert_eq!(elements.period(), None);
+            assert!(elements.specific_energy().abs() < 1e-9 * MU_EARTH / RADIUS);
+            assert_time_since_periapsis(&elements, flight_path);
+        }
+    }
+
+    #[test]
+    fn hyperbolic() {
+        // Inbound, at the periapsis and outbound
+        for flight_path in [-0.4, 0.0, 0.4] {
+            let state = planar_state(1.0, 2.0, flight_path, false);
+            let elements = assert_round_trip(&state);
+            assert!(elements.is_hyperbolic());
+            assert!(elements.semi_major_axis() < 0.0);
+            assert_eq!(elements.apoapsis(), None);
+            assert_time_since_periapsis(&elements, flight_path);
+        }
+    }
+
+    #[test]
+    fn elliptic_time_since_periapsis() {
+        let after = planar_state(1.0, 1.1, 0.01, false).to_elements(MU_EARTH);
+        let before = planar_state(1.0, 1.1, -0.01, false).to_elements(MU_EARTH);
+        let period = after.period().unwrap();
+        // Closed orbits count from the last periapsis
+        assert!(after.time_since_periapsis() > 0.0);
+        assert!(after.time_since_periapsis() < 0.01 * period);
+        assert!(before.time_since_periapsis() > 0.99 * period);
+        assert!(before.time_since_periapsis() < period);
+        assert!((before.eccentric_anomaly() + after.eccentric_anomaly() - 2.0 * PI).abs() < 1e-9);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "without angular momentum")]
+    fn radial_trajectories_have_no_elements() {
+        // Falling straight down
+        let position = Vector3::new(RADIUS, 0.0, 0.0);
+        let state = StateVector::new(position, -1e-3 * position);
+        state.to_elements(MU_EARTH);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "without angular momentum")]
+    fn the_center_has_no_elements() {
+        let state = StateVector::new(Vector3::zeros(), Vector3::new(0.0, 7000.0, 0.0));
+        state.to_elements(MU_EARTH);
+    }
+
+    /// Checks the sign of the time since the periapsis of an open orbit, and
+    /// that going back by this time reaches the periapsis.
+    fn assert_time_since_periapsis(elements: &OrbitalElements, flight_path: f64) {
+        let time = elements.time_since_periapsis();
+        if flight_path < 0.0 {
+            assert!(time < 0.0);
+            assert!(elements.eccentric_anomaly() < 0.0);
+        } else if flight_path > 0.0 {
+            assert!(time > 0.0);
+            assert!(elements.eccentric_anomaly() > 0.0);
+        } else {
+            assert!(time.abs() < 1e-6);
+        }
+        let periapsis = elements.propagate(-time).unwrap();
+        assert!((periapsis.to_state_vector().position.norm() - elements.periapsis()).abs() < 1e-3);
+    }
+}
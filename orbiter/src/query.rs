@@ -0,0 +1,391 @@
+use crate::{Object, ObjectTrait, Planet, Vector3, Vessel, VesselTrait, OBJHANDLE};
+#[cfg(feature = "regex")]
+use regex::Regex;
+use std::os::raw::c_int;
+
+/// Kind of an [`Object`]
+///
+/// [`Object`]: ./enum.Object.html
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ObjectKind {
+    /// A star
+    Star,
+    /// A planet, moon, comet, etc.
+    Planet,
+    /// A vessel
+    Vessel,
+    /// A surface base
+    SurfaceBase,
+}
+
+impl ObjectKind {
+    pub(crate) fn from_raw(value: c_int) -> Option<Self> {
+        match value {
+            3 => Some(Self::Star),
+            4 => Some(Self::Planet),
+            10 => Some(Self::Vessel),
+            20 => Some(Self::SurfaceBase),
+            _ => None,
+        }
+    }
+}
+
+impl Object {
+    /// Returns the kind of the object.
+    pub fn kind(&self) -> ObjectKind {
+        match self {
+            Self::Star(_) => ObjectKind::Star,
+            Self::Planet(_) => ObjectKind::Planet,
+            Self::Vessel(_) => ObjectKind::Vessel,
+            Self::SurfaceBase(_) => ObjectKind::SurfaceBase,
+        }
+    }
+
+    /// Creates a query over the objects of the simulation.
+    ///
+    /// See [`ObjectQuery`].
+    ///
+    /// [`ObjectQuery`]: ./struct.ObjectQuery.html
+    pub fn query<'a>() -> ObjectQuery<'a> {
+        ObjectQuery::new()
+    }
+}
+
+impl Planet {
+    /// Returns the vessels inside the sphere of influence of the planet.
+    ///
+    /// A vessel is in the sphere of influence of the planet when the planet
+    /// is its gravity reference, see [`VesselTrait::gravity_ref`]. Vessels
+    /// orbiting a moon of the planet are not included.
+    ///
+    /// [`VesselTrait::gravity_ref`]: ./trait.VesselTrait.html#method.gravity_ref
+    pub fn vessels_in_soi(&self) -> Vec<Vessel> {
+        Object::all_objects()
+            .filter_map(|object| match object {
+                Object::Vessel(vessel) => Some(vessel),
+                _ => None,
+            })
+            .filter(|vessel| {
+                vessel
+                    .gravity_ref()
+                    .is_some_and(|body| body.handle() == self.handle)
+            })
+            .collect()
+    }
+}
+
+/// Query over the objects of the simulation
+///
+/// Filters are combined: an object is returned only if it matches all of
+/// them. Calling [`kind`] several times matches any of the given kinds.
+///
+/// # Examples
+///
+/// ```no_run
+/// use orbiter::*;
+///
+/// // All the vessels within 50 km of the focus vessel, nearest first
+/// fn traffic(focus: &Vessel) -> Vec<Object> {
+///     Object::query()
+///         .kind(ObjectKind::Vessel)
+///         .within(focus, 50e3)
+///         .excluding(focus)
+///         .sorted_by_distance(focus)
+///         .run()
+/// }
+/// ```
+///
+/// [`kind`]: #method.kind
+#[derive(Default)]
+pub struct ObjectQuery<'a> {
+    kinds: Vec<ObjectKind>,
+    class_name: Option<String>,
+    name_glob: Option<String>,
+    #[cfg(feature = "regex")]
+    name_regex: Option<Regex>,
+    within: Option<(&'a dyn ObjectTrait, f64)>,
+    excluded: Vec<OBJHANDLE>,
+    sort_reference: Option<&'a dyn ObjectTrait>,
+    limit: Option<usize>,
+}
+
+impl<'a> ObjectQuery<'a> {
+    /// Creates a query matching all the objects.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only matches objects of the given kind.
+    pub fn kind(mut self, kind: ObjectKind) -> Self {
+        self.kinds.push(kind);
+        self
+    }
+
+    /// Only matches vessels of the given class, e.g. `"DeltaGlider"`.
+    ///
+    /// The class name is compared case-insensitively.
+    pub fn vessel_class(mut self, class_name: &str) -> Self {
+        self.class_name = Some(class_name.to_string());
+        self
+    }
+
+    /// Only matches objects whose name matches a glob pattern.
+    ///
+    /// See [`matches_name`] for the syntax of the pattern.
+    ///
+    /// [`matches_name`]: #method.matches_name
+    pub fn name_glob(mut self, pattern: &str) -> Self {
+        self.name_glob = Some(pattern.to_string());
+        self
+    }
+
+    /// Only matches objects whose name matches a regular expression.
+    ///
+    /// Only available with the `regex` feature.
+    #[cfg(feature = "regex")]
+    pub fn name_regex(mut self, regex: Regex) -> Self {
+        self.name_regex = Some(regex);
+        self
+    }
+
+    /// Only matches objects within a distance (in meters) of a reference
+    /// object.
+    ///
+    /// The reference object itself is matched, use [`excluding`] to leave it
+    /// out.
+    ///
+    /// [`excluding`]: #method.excluding
+    pub fn within(mut self, reference: &'a dyn ObjectTrait, radius: f64) -> Self {
+        self.within = Some((reference, radius));
+        self
+    }
+
+    /// Never matches the given object.
+    pub fn excluding(mut self, object: &dyn ObjectTrait) -> Self {
+        self.excluded.push(object.handle());
+        self
+    }
+
+    /// Sorts the results by distance to a reference object, nearest first.
+    pub fn sorted_by_distance(mut self, reference: &'a dyn ObjectTrait) -> Self {
+        self.sort_reference = Some(reference);
+        self
+    }
+
+    /// Only returns the `count` objects nearest to the reference object,
+    /// sorted by distance.
+    pub fn nearest(mut self, reference: &'a dyn ObjectTrait, count: usize) -> Self {
+        self.sort_reference = Some(reference);
+        self.limit = Some(count);
+        self
+    }
+
+    /// Runs the query over the current objects of the simulation.
+    pub fn run(&self) -> Vec<Object> {
+        let within = self
+            .within
+            .map(|(reference, radius)| (reference.global_pos(), radius));
+        let sort_reference = self.sort_reference.map(|reference| reference.global_pos());
+        let distance =
+            |object: &Object, reference: Vector3| (object.global_pos() - reference).norm();
+        let results: Vec<(Object, f64)> = Object::all_objects()
+            .filter(|object| self.selects(object.kind(), object.handle()))
+            .filter(|object| {
+                within.map_or(true, |(reference, radius)| {
+                    distance(object, reference) <= radius
+                })
+            })
+            .filter(|object| self.matches(object))
+            .map(|object| {
+                let key = sort_reference.map_or(0.0, |reference| distance(&object, reference));
+                (object, key)
+            })
+            .collect();
+        rank(results, self.sort_reference.is_some(), self.limit)
+    }
+
+    /// Checks the kind of an object and if it is excluded, without calling
+    /// Orbiter.
+    fn selects(&self, kind: ObjectKind, handle: OBJHANDLE) -> bool {
+        (self.kinds.is_empty() || self.kinds.contains(&kind)) && !self.excluded.contains(&handle)
+    }
+
+    fn matches(&self, object: &Object) -> bool {
+        if let Some(class_name) = &self.class_name {
+            let class_matches = match object {
+                Object::Vessel(vessel) => vessel
+                    .class_name()
+                    .is_some_and(|name| name.eq_ignore_ascii_case(class_name)),
+                _ => false,
+            };
+            if !class_matches {
+                return false;
+            }
+        }
+
+        if self.name_glob.is_none() && !self.has_name_regex() {
+            return true;
+        }
+
+        let name = object.name();
+        if let Some(pattern) = &self.name_glob {
+            if !Self::matches_name(pattern, &name) {
+                return false;
+            }
+        }
+        #[cfg(feature = "regex")]
+        {
+            if let Some(regex) = &self.name_regex {
+                if !regex.is_match(&name) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    #[cfg(feature = "regex")]
+    fn has_name_regex(&self) -> bool {
+        self.name_regex.is_some()
+    }
+
+    #[cfg(not(feature = "regex"))]
+    fn has_name_regex(&self) -> bool {
+        false
+    }
+
+    /// Checks if a name matches a glob pattern.
+    ///
+    /// `*` matches any sequence of characters and `?` matches a single
+    /// character. The comparison is case-insensitive, like object names in
+    /// Orbiter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use orbiter::ObjectQuery;
+    ///
+    /// assert!(ObjectQuery::matches_name("GL-*", "GL-01"));
+    /// assert!(ObjectQuery::matches_name("iss", "ISS"));
+    /// assert!(ObjectQuery::matches_name("Luna-?", "Luna-1"));
+    /// assert!(!ObjectQuery::matches_name("Luna-?", "Luna-12"));
+    /// ```
+    pub fn matches_name(pattern: &str, name: &str) -> bool {
+        let pattern: Vec<char> = pattern.chars().flat_map(char::to_lowercase).collect();
+        let name: Vec<char> = name.chars().flat_map(char::to_lowercase).collect();
+
+        // Backtrack to the last `*` on a mismatch
+        let (mut p, mut n) = (0, 0);
+        let mut star = None;
+        while n < name.len() {
+            if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+                p += 1;
+                n += 1;
+            } else if p < pattern.len() && pattern[p] == '*' {
+                star = Some((p, n));
+                p += 1;
+            } else if let Some((star_p, star_n)) = star {
+                p = star_p + 1;
+                n = star_n + 1;
+                star = Some((star_p, star_n + 1));
+            } else {
+                return false;
+            }
+        }
+        pattern[p..].iter().all(|&c| c == '*')
+    }
+}
+
+/// Sorts results by their distance, nearest first, if `sorted` and keeps the
+/// first `limit` of them.
+fn rank<T>(mut results: Vec<(T, f64)>, sorted: bool, limit: Option<usize>) -> Vec<T> {
+    if sorted {
+        results.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+    }
+    if let Some(limit) = limit {
+        results.truncate(limit);
+    }
+    results.into_iter().map(|(result, _)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_stars() {
+        assert!(ObjectQuery::matches_name("*-01", "GL-01"));
+        assert!(ObjectQuery::matches_name("GL*01", "GL-01"));
+        assert!(ObjectQuery::matches_name("GL*01", "GL01"));
+        assert!(ObjectQuery::matches_name("GL-*", "GL-"));
+        assert!(ObjectQuery::matches_name("*a*b*", "xaybz"));
+        assert!(!ObjectQuery::matches_name("GL*01", "GL-02"));
+        assert!(!ObjectQuery::matches_name("*-01", "GL-011"));
+        // Backtracking past a partial match
+        assert!(ObjectQuery::matches_name("*ab", "aab"));
+        assert!(ObjectQuery::matches_name("*a?c", "abbabc"));
+    }
+
+    #[test]
+    fn glob_empty_names() {
+        assert!(ObjectQuery::matches_name("*", ""));
+        assert!(ObjectQuery::matches_name("**", ""));
+        assert!(ObjectQuery::matches_name("", ""));
+        assert!(!ObjectQuery::matches_name("", "ISS"));
+        assert!(!ObjectQuery::matches_name("?", ""));
+    }
+
+    #[test]
+    fn glob_multibyte_characters() {
+        assert!(ObjectQuery::matches_name("?", "日"));
+        assert!(!ObjectQuery::matches_name("??", "日"));
+        assert!(ObjectQuery::matches_name("Sonde-?", "Sonde-é"));
+        assert!(ObjectQuery::matches_name("мир-?", "МИР-1"));
+        assert!(ObjectQuery::matches_name("*ö", "Malmö"));
+    }
+
+    #[test]
+    fn selects_kinds_and_exclusions() {
+        let handle = 8 as OBJHANDLE;
+        let other = 16 as OBJHANDLE;
+
+        let query = ObjectQuery::new();
+        assert!(query.selects(ObjectKind::Star, handle));
+
+        let query = ObjectQuery::new()
+            .kind(ObjectKind::Vessel)
+            .kind(ObjectKind::SurfaceBase);
+        assert!(query.selects(ObjectKind::Vessel, handle));
+        assert!(query.selects(ObjectKind::SurfaceBase, handle));
+        assert!(!query.selects(ObjectKind::Planet, handle));
+
+        let query = ObjectQuery {
+            excluded: vec![handle],
+            ..ObjectQuery::new().kind(ObjectKind::Vessel)
+        };
+        assert!(!query.selects(ObjectKind::Vessel, handle));
+        assert!(query.selects(ObjectKind::Vessel, other));
+    }
+
+    #[test]
+    fn ranks_by_distance() {
+        let results = vec![("far", 300.0), ("near", 10.0), ("middle", 20.0)];
+        assert_eq!(
+            rank(results.clone(), false, None),
+            vec!["far", "near", "middle"]
+        );
+        assert_eq!(
+            rank(results.clone(), true, None),
+            vec!["near", "middle", "far"]
+        );
+        assert_eq!(rank(results.clone(), true, Some(2)), vec!["near", "middle"]);
+        assert_eq!(rank(results.clone(), true, Some(0)), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn nearest_with_few_results() {
+        let results = vec![("far", 300.0), ("near", 10.0)];
+        assert_eq!(rank(results, true, Some(5)), vec!["near", "far"]);
+        assert!(rank(Vec::<((), f64)>::new(), true, Some(5)).is_empty());
+    }
+}
@@ -0,0 +1,523 @@
+//! Relative motion for rendezvous and proximity operations.
+//!
+//! Close to a target on a circular orbit, the motion of a chaser relative to
+//! the target follows the Clohessy-Wiltshire (Hill) equations, which have a
+//! closed-form solution. This module computes the relative state of a
+//! chaser, propagates it, solves two-impulse transfers between relative
+//! positions and plans V-bar and R-bar approach sequences.
+//!
+//! Relative states use the local-vertical local-horizontal (LVLH) frame of
+//! the target: the x axis points away from the central body (radial, R-bar),
+//! the y axis along the velocity of the target (along-track, V-bar) and the z
+//! axis along the orbit normal (cross-track), which makes it right-handed
+//! like the frames of the [`orbit`] module.
+//!
+//! # Examples
+//!
+//! ```
+//! use orbiter::orbit::StateVector;
+//! use orbiter::rendezvous::ClohessyWiltshire;
+//! use orbiter::Vector3;
+//!
+//! // Target on a 400 km orbit, chaser 1 km behind at rest
+//! let cw = ClohessyWiltshire::new(1.13e-3);
+//! let chaser = StateVector::new(Vector3::new(0.0, -1000.0, 0.0), Vector3::zeros());
+//!
+//! // Transfer to 100 m behind the target in 20 minutes
+//! let transfer = cw
+//!     .two_impulse(&chaser, &Vector3::new(0.0, -100.0, 0.0), 1200.0)
+//!     .unwrap();
+//! let arrival = cw.propagate(&transfer.departure_state(&chaser), 1200.0);
+//! assert!((arrival.position - Vector3::new(0.0, -100.0, 0.0)).norm() < 1e-6);
+//! assert!((arrival.velocity + transfer.arrival_delta_v).norm() < 1e-9);
+//! ```
+//!
+//! [`orbit`]: ../orbit/index.html
+
+use crate::orbit::StateVector;
+use crate::{as_celestial, to_right_handed, Matrix3, ObjectTrait, Vector3, VesselTrait};
+use nalgebra::Rotation3;
+
+/// Time to go (in s) under which the guidance only cancels the relative
+/// velocity
+const MIN_TIME_TO_GO: f64 = 1.0;
+
+/// Tolerance on the determinant of a transition matrix, relative to the
+/// norms of its columns, below which a transfer is considered singular
+const SINGULARITY_TOLERANCE: f64 = 1e-9;
+
+/// Error returned when a relative motion cannot be planned.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RendezvousError {
+    /// The orbit of the target is open
+    OpenOrbit,
+    /// The transfer is singular, e.g. with a time of flight of zero or of a
+    /// multiple of half a period
+    Singular,
+}
+
+impl std::fmt::Display for RendezvousError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OpenOrbit => f.write_str("the orbit of the target is open"),
+            Self::Singular => f.write_str("the transfer is singular"),
+        }
+    }
+}
+
+impl std::error::Error for RendezvousError {}
+
+/// State of a chaser relative to a target
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RelativeMotion {
+    /// State of the target relative to the central body
+    pub target: StateVector,
+    /// Gravitational parameter of the central body, in m³/s²
+    pub mu: f64,
+    /// State of the chaser in the LVLH frame of the target
+    pub state: StateVector,
+}
+
+impl RelativeMotion {
+    /// Computes the relative state of a chaser from the states of the target
+    /// and the chaser relative to the central body.
+    pub fn new(target: StateVector, chaser: StateVector, mu: f64) -> Self {
+        let frame = lvlh_frame(&target);
+        let offset = chaser.position - target.position;
+        let velocity = chaser.velocity - target.velocity - angular_velocity(&target).cross(&offset);
+        Self {
+            target,
+            mu,
+            state: StateVector::new(frame.inverse() * offset, frame.inverse() * velocity),
+        }
+    }
+
+    /// Computes the relative state of a chaser around a target vessel, from
+    /// their current states in the simulation.
+    ///
+    /// Returns `None` if the gravity reference of the target is not a
+    /// celestial body.
+    pub fn between<C: ObjectTrait, T: VesselTrait>(chaser: &C, target: &T) -> Option<Self> {
+        let reference = target.gravity_ref()?;
+        let mu = as_celestial(&reference)?.gravitational_parameter();
+        let target_state = StateVector::relative(target, &reference);
+        let chaser_state = StateVector::new(
+            target_state.position + to_right_handed(chaser.relative_position(target)),
+            target_state.velocity + to_right_handed(chaser.relative_velocity(target)),
+        );
+        Some(Self::new(target_state, chaser_state, mu))
+    }
+
+    /// Returns the rotation from the LVLH frame to the reference frame of
+    /// the central body.
+    ///
+    /// Use it to convert the velocity changes of the planner to inertial
+    /// directions.
+    pub fn frame(&self) -> Rotation3<f64> {
+        lvlh_frame(&self.target)
+    }
+
+    /// Returns the Clohessy-Wiltshire model for the orbit of the target.
+    pub fn model(&self) -> Result<ClohessyWiltshire, RendezvousError> {
+        ClohessyWiltshire::from_target(&self.target, self.mu)
+    }
+
+    /// Returns the state of the chaser relative to the central body.
+    pub fn chaser(&self) -> StateVector {
+        let frame = self.frame();
+        let offset = frame * self.state.position;
+        StateVector::new(
+            self.target.position + offset,
+            self.target.velocity
+                + frame * self.state.velocity
+                + angular_velocity(&self.target).cross(&offset),
+        )
+    }
+}
+
+/// Returns the rotation from the LVLH frame of a target to its reference
+/// frame.
+pub fn lvlh_frame(target: &StateVector) -> Rotation3<f64> {
+    let radial = target.position.normalize();
+    let normal = target.position.cross(&target.velocity).normalize();
+    let along_track = normal.cross(&radial);
+    Rotation3::from_matrix_unchecked(Matrix3::from_columns(&[radial, along_track, normal]))
+}
+
+/// Returns the angular velocity of the LVLH frame of a target.
+fn angular_velocity(target: &StateVector) -> Vector3 {
+    target.position.cross(&target.velocity) / target.position.norm_squared()
+}
+
+/// Closed-form solution of the Clohessy-Wiltshire equations
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ClohessyWiltshire {
+    /// Mean motion of the target, in rad/s
+    pub mean_motion: f64,
+}
+
+impl ClohessyWiltshire {
+    /// Creates the model for a target with a mean motion (in rad/s).
+    pub fn new(mean_motion: f64) -> Self {
+        Self { mean_motion }
+    }
+
+    /// Creates the model for a target from its state relative to a central
+    /// body with the gravitational parameter `mu`.
+    ///
+    /// The model assumes that the orbit of the target is circular.
+    pub fn from_target(target: &StateVector, mu: f64) -> Result<Self, RendezvousError> {
+        let elements = target.to_elements(mu);
+        if !elements.is_closed() {
+            return Err(RendezvousError::OpenOrbit);
+        }
+        Ok(Self::new(elements.mean_motion()))
+    }
+
+    /// Returns the period of the orbit of the target, in s.
+    pub fn period(&self) -> f64 {
+        2.0 * std::f64::consts::PI / self.mean_motion
+    }
+
+    /// Returns the state transition matrices after a time (in s), as
+    /// `[[Φrr, Φrv], [Φvr, Φvv]]`.
+    pub fn transition(&self, dt: f64) -> [[Matrix3; 2]; 2] {
+        let n = self.mean_motion;
+        let (s, c) = (n * dt).sin_cos();
+        #[rustfmt::skip]
+        let rr = Matrix3::new(
+            4.0 - 3.0 * c,         0.0, 0.0,
+            6.0 * (s - n * dt),    1.0, 0.0,
+            0.0,                   0.0, c,
+        );
+        #[rustfmt::skip]
+        let rv = Matrix3::new(
+            s / n,                 2.0 * (1.0 - c) / n,         0.0,
+            -2.0 * (1.0 - c) / n,  (4.0 * s - 3.0 * n * dt) / n, 0.0,
+            0.0,                   0.0,                         s / n,
+        );
+        #[rustfmt::skip]
+        let vr = Matrix3::new(
+            3.0 * n * s,           0.0, 0.0,
+            -6.0 * n * (1.0 - c),  0.0, 0.0,
+            0.0,                   0.0, -n * s,
+        );
+        #[rustfmt::skip]
+        let vv = Matrix3::new(
+            c,                     2.0 * s,           0.0,
+            -2.0 * s,              4.0 * c - 3.0,     0.0,
+            0.0,                   0.0,               c,
+        );
+        [[rr, rv], [vr, vv]]
+    }
+
+    /// Propagates a relative state by a time (in s).
+    pub fn propagate(&self, state: &StateVector, dt: f64) -> StateVector {
+        let [[rr, rv], [vr, vv]] = self.transition(dt);
+        StateVector::new(
+            rr * state.position + rv * state.velocity,
+            vr * state.position + vv * state.velocity,
+        )
+    }
+
+    /// Plans a two-impulse transfer from a relative state to a relative
+    /// position in a time (in s), arriving at rest relative to the target.
+    pub fn two_impulse(
+        &self,
+        state: &StateVector,
+        position: &Vector3,
+        time_of_flight: f64,
+    ) -> Result<TwoImpulse, RendezvousError> {
+        let [[rr, rv], [vr, vv]] = self.transition(time_of_flight);
+        // The in-plane and cross-track motions are independent. Relative to
+        // the scale of the matrix, their determinants do not depend on the
+        // time scale, so that short legs are not singular.
+        let in_plane = (rv[(0, 0)] * rv[(1, 1)] - rv[(0, 1)] * rv[(1, 0)]).abs()
+            / (rv[(0, 0)].hypot(rv[(1, 0)]) * rv[(0, 1)].hypot(rv[(1, 1)]));
+        let cross_track = (rv[(2, 2)] / time_of_flight).abs();
+        if [in_plane, cross_track]
+            .iter()
+            .any(|ratio| ratio.is_nan() || *ratio < SINGULARITY_TOLERANCE)
+        {
+            return Err(RendezvousError::Singular);
+        }
+        let departure_velocity =
+            rv.try_inverse().ok_or(RendezvousError::Singular)? * (position - rr * state.position);
+        let arrival_velocity = vr * state.position + vv * departure_velocity;
+        Ok(TwoImpulse {
+            time_of_flight,
+            departure_delta_v: departure_velocity - state.velocity,
+            arrival_delta_v: -arrival_velocity,
+        })
+    }
+}
+
+/// Two-impulse transfer between relative positions
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TwoImpulse {
+    /// Time between the impulses, in s
+    pub time_of_flight: f64,
+    /// Velocity change at departure, in the LVLH frame, in m/s
+    pub departure_delta_v: Vector3,
+    /// Velocity change at arrival, in the LVLH frame, in m/s
+    pub arrival_delta_v: Vector3,
+}
+
+impl TwoImpulse {
+    /// Returns the total velocity change, in m/s.
+    pub fn total_delta_v(&self) -> f64 {
+        self.departure_delta_v.norm() + self.arrival_delta_v.norm()
+    }
+
+    /// Returns the relative state right after the departure impulse.
+    pub fn departure_state(&self, state: &StateVector) -> StateVector {
+        StateVector::new(state.position, state.velocity + self.departure_delta_v)
+    }
+}
+
+/// Axis of an approach to the target
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ApproachAxis {
+    /// Along the velocity vector of the target, from ahead or behind
+    VBar,
+    /// Along the radius vector of the target, from above or below
+    RBar,
+}
+
+/// Leg of an approach between two waypoints
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ApproachStep {
+    /// Start of the leg, in s after the start of the approach
+    pub start: f64,
+    /// End of the leg, in s after the start of the approach
+    pub end: f64,
+    /// Relative position reached at the end of the leg
+    pub waypoint: Vector3,
+    /// Transfer flown during the leg
+    pub transfer: TwoImpulse,
+}
+
+/// Guidance at a point of an approach
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Guidance {
+    /// Index of the current step
+    pub step: usize,
+    /// Waypoint of the current step
+    pub waypoint: Vector3,
+    /// Time until the end of the step, in s
+    pub time_to_go: f64,
+    /// Velocity change to apply now to reach the waypoint at the end of the
+    /// step, in the LVLH frame, in m/s
+    pub delta_v: Vector3,
+    /// Distance to the target, in m
+    pub range: f64,
+    /// Rate of change of the distance to the target, in m/s
+    pub range_rate: f64,
+}
+
+/// Sequence of waypoints approaching the target along an axis
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApproachPlan {
+    model: ClohessyWiltshire,
+    /// Legs of the approach, in chronological order
+    pub steps: Vec<ApproachStep>,
+}
+
+impl ApproachPlan {
+    /// Plans an approach from a relative state through waypoints at
+    /// decreasing distances (in m) from the target along an axis, each
+    /// reached after `leg_time` seconds.
+    ///
+    /// The waypoints are on the side of the target where the chaser is,
+    /// e.g. behind the target on the V-bar if the chaser trails it. A final
+    /// distance of zero ends the approach at the target.
+    pub fn new(
+        model: ClohessyWiltshire,
+        state: &StateVector,
+        axis: ApproachAxis,
+        distances: &[f64],
+        leg_time: f64,
+    ) -> Result<Self, RendezvousError> {
+        let direction = match axis {
+            ApproachAxis::VBar => Vector3::y() * state.position.y.signum(),
+            ApproachAxis::RBar => Vector3::x() * state.position.x.signum(),
+        };
+
+        let mut steps = Vec::with_capacity(distances.len());
+        let mut current = *state;
+        let mut time = 0.0;
+        for distance in distances {
+            let waypoint = direction * *distance;
+            let transfer = model.two_impulse(&current, &waypoint, leg_time)?;
+            steps.push(ApproachStep {
+                start: time,
+                end: time + leg_time,
+                waypoint,
+                transfer,
+            });
+            current = StateVector::new(waypoint, Vector3::zeros());
+            time += leg_time;
+        }
+        Ok(Self { model, steps })
+    }
+
+    /// Returns the total velocity change of the approach, in m/s.
+    ///
+    /// The arrival impulse of each leg and the departure impulse of the next
+    /// one happen at the same time, so they count as a single burn.
+    pub fn total_delta_v(&self) -> f64 {
+        let (total, last) =
+            self.steps
+                .iter()
+                .fold((0.0, Vector3::zeros()), |(total, arrival), step| {
+                    (
+                        total + (arrival + step.transfer.departure_delta_v).norm(),
+                        step.transfer.arrival_delta_v,
+                    )
+                });
+        total + last.norm()
+    }
+
+    /// Returns the duration of the approach, in s.
+    pub fn duration(&self) -> f64 {
+        self.steps.last().map_or(0.0, |step| step.end)
+    }
+
+    /// Returns the guidance for a relative state at a time (in s after the
+    /// start of the approach), retargeting the waypoint of the current step.
+    ///
+    /// Returns `Ok(None)` once the approach is over.
+    pub fn guidance(
+        &self,
+        elapsed: f64,
+        state: &StateVector,
+    ) -> Result<Option<Guidance>, RendezvousError> {
+        let (index, step) = match self
+            .steps
+            .iter()
+            .enumerate()
+            .find(|(_, step)| elapsed < step.end)
+        {
+            Some(step) => step,
+            None => return Ok(None),
+        };
+
+        let time_to_go = step.end - elapsed;
+        let delta_v = if time_to_go < MIN_TIME_TO_GO {
+            -state.velocity
+        } else {
+            self.model
+                .two_impulse(state, &step.waypoint, time_to_go)?
+                .departure_delta_v
+        };
+        let range = state.position.norm();
+        Ok(Some(Guidance {
+            step: index,
+            waypoint: step.waypoint,
+            time_to_go,
+            delta_v,
+            range,
+            range_rate: if range > 0.0 {
+                state.position.dot(&state.velocity) / range
+            } else {
+                0.0
+            },
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Mean motion of a geostationary orbit, in rad/s
+    const GEO: f64 = 7.2921e-5;
+
+    /// Mean motion of a 400 km orbit, in rad/s
+    const LEO: f64 = 1.13e-3;
+
+    fn behind(distance: f64) -> StateVector {
+        StateVector::new(Vector3::new(0.0, -distance, 0.0), Vector3::zeros())
+    }
+
+    #[test]
+    fn short_legs_are_not_singular() {
+        let cw = ClohessyWiltshire::new(GEO);
+        for &time in &[0.5, 1.0, 5.0, 13.0, 60.0] {
+            let target = Vector3::new(0.0, -9.0, 0.0);
+            let transfer = cw.two_impulse(&behind(10.0), &target, time).unwrap();
+            let arrival = cw.propagate(&transfer.departure_state(&behind(10.0)), time);
+            assert!((arrival.position - target).norm() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn multiples_of_half_a_period_are_singular() {
+        for &n in &[GEO, LEO] {
+            let cw = ClohessyWiltshire::new(n);
+            for &time in &[0.0, cw.period() / 2.0, cw.period(), 2.0 * cw.period()] {
+                assert_eq!(
+                    cw.two_impulse(&behind(1000.0), &Vector3::zeros(), time),
+                    Err(RendezvousError::Singular)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn guidance_reaches_the_end_of_each_leg() {
+        let cw = ClohessyWiltshire::new(GEO);
+        let plan = ApproachPlan::new(
+            cw,
+            &behind(1000.0),
+            ApproachAxis::VBar,
+            &[100.0, 0.0],
+            3600.0,
+        )
+        .unwrap();
+        for &elapsed in &[3590.0, 3599.5, 7195.0, 7199.5] {
+            let guidance = plan.guidance(elapsed, &behind(100.0)).unwrap().unwrap();
+            assert!(guidance.time_to_go <= 10.0);
+        }
+        assert_eq!(plan.guidance(7200.0, &behind(0.0)), Ok(None));
+    }
+
+    #[test]
+    fn approach_merges_consecutive_impulses() {
+        let cw = ClohessyWiltshire::new(LEO);
+        let plan = ApproachPlan::new(
+            cw,
+            &behind(1000.0),
+            ApproachAxis::VBar,
+            &[500.0, 100.0],
+            1200.0,
+        )
+        .unwrap();
+        let [first, second] = [plan.steps[0].transfer, plan.steps[1].transfer];
+        let expected = first.departure_delta_v.norm()
+            + (first.arrival_delta_v + second.departure_delta_v).norm()
+            + second.arrival_delta_v.norm();
+        assert!((plan.total_delta_v() - expected).abs() < 1e-12);
+        assert!(plan.total_delta_v() < first.total_delta_v() + second.total_delta_v());
+    }
+
+    #[test]
+    fn relative_state_round_trip() {
+        let mu = 3.986004418e14;
+        let radius: f64 = 6778e3;
+        let speed = (mu / radius).sqrt();
+        let target = StateVector::new(
+            Vector3::new(radius, 0.0, 0.0),
+            Vector3::new(0.0, speed * 0.9f64.cos(), speed * 0.9f64.sin()),
+        );
+        let state = StateVector::new(
+            Vector3::new(-200.0, -1000.0, 50.0),
+            Vector3::new(0.1, 0.35, -0.05),
+        );
+        let motion = RelativeMotion { target, mu, state };
+        let back = RelativeMotion::new(target, motion.chaser(), mu);
+        assert!((back.state.position - state.position).norm() < 1e-6);
+        assert!((back.state.velocity - state.velocity).norm() < 1e-9);
+        assert!((motion.model().unwrap().mean_motion - (mu / radius.powi(3)).sqrt()).abs() < 1e-12);
+    }
+}
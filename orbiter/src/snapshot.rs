@@ -0,0 +1,270 @@
+use crate::{oapic_MATRIX3, oapic_VECTOR3, Matrix3, Object, ObjectKind, Vector3, OBJHANDLE};
+use std::borrow::Cow;
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int};
+
+/// Maximum length of the names in a snapshot, including the terminating null
+const NAME_LENGTH: usize = 256;
+
+/// State of all the objects of the simulation at a point in time
+///
+/// The state of every object is fetched in a single call into Orbiter, which
+/// is much faster than calling the [`ObjectTrait`] methods on each object when
+/// scanning hundreds of vessels. The values are stored in arrays indexed by
+/// object, and the arrays are reused by [`refresh`] to avoid allocating
+/// every frame.
+///
+/// Positions, velocities and rotation matrices use Orbiter's global frame, as
+/// the corresponding [`ObjectTrait`] methods.
+///
+/// # Examples
+///
+/// ```no_run
+/// use orbiter::*;
+///
+/// struct Radar {
+///     snapshot: WorldSnapshot,
+/// }
+///
+/// impl Radar {
+///     // Returns the names of the vessels within 10 km of a position
+///     fn scan(&mut self, position: Vector3) -> Vec<String> {
+///         self.snapshot.refresh();
+///         (0..self.snapshot.len())
+///             .filter(|&i| self.snapshot.kind(i) == Some(ObjectKind::Vessel))
+///             .filter(|&i| (self.snapshot.global_pos(i) - position).norm() < 10e3)
+///             .map(|i| self.snapshot.name(i).into_owned())
+///             .collect()
+///     }
+/// }
+/// ```
+///
+/// [`ObjectTrait`]: ./trait.ObjectTrait.html
+/// [`refresh`]: #method.refresh
+#[derive(Default)]
+pub struct WorldSnapshot {
+    len: usize,
+    handles: Vec<OBJHANDLE>,
+    types: Vec<c_int>,
+    names: Vec<c_char>,
+    masses: Vec<f64>,
+    sizes: Vec<f64>,
+    positions: Vec<oapic_VECTOR3>,
+    velocities: Vec<oapic_VECTOR3>,
+    rotations: Vec<oapic_MATRIX3>,
+}
+
+impl WorldSnapshot {
+    /// Creates an empty snapshot.
+    ///
+    /// Use [`refresh`] to fill it.
+    ///
+    /// [`refresh`]: #method.refresh
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Captures the current state of all the objects of the simulation.
+    pub fn capture() -> Self {
+        let mut snapshot = Self::new();
+        snapshot.refresh();
+        snapshot
+    }
+
+    /// Replaces the content of the snapshot with the current state of the
+    /// objects of the simulation.
+    ///
+    /// The buffers only grow when objects were added since the last refresh.
+    pub fn refresh(&mut self) {
+        loop {
+            let count = unsafe {
+                oapic_world_snapshot(
+                    self.handles.len() as c_int,
+                    self.handles.as_mut_ptr(),
+                    self.types.as_mut_ptr(),
+                    self.names.as_mut_ptr(),
+                    NAME_LENGTH as c_int,
+                    self.masses.as_mut_ptr(),
+                    self.sizes.as_mut_ptr(),
+                    self.positions.as_mut_ptr(),
+                    self.velocities.as_mut_ptr(),
+                    self.rotations.as_mut_ptr(),
+                )
+            } as usize;
+
+            if count <= self.handles.len() {
+                self.len = count;
+                return;
+            }
+            self.reserve(count);
+        }
+    }
+
+    fn reserve(&mut self, capacity: usize) {
+        self.handles.resize(capacity, std::ptr::null_mut());
+        self.types.resize(capacity, 0);
+        self.names.resize(capacity * NAME_LENGTH, 0);
+        self.masses.resize(capacity, 0.0);
+        self.sizes.resize(capacity, 0.0);
+        self.positions.resize(capacity, oapic_VECTOR3::new());
+        self.velocities.resize(capacity, oapic_VECTOR3::new());
+        self.rotations.resize(capacity, oapic_MATRIX3::new());
+    }
+
+    /// Returns the number of objects in the snapshot.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the snapshot contains no objects.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the object at an index.
+    ///
+    /// The object is a live handle: its methods return the current state of
+    /// the object, not the one stored in the snapshot.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds, as do all the accessors indexed
+    /// by object.
+    pub fn object(&self, index: usize) -> Option<Object> {
+        Object::from(self.handles[..self.len][index])
+    }
+
+    /// Returns the kind of the object at an index.
+    pub fn kind(&self, index: usize) -> Option<ObjectKind> {
+        ObjectKind::from_raw(self.types[..self.len][index])
+    }
+
+    /// Returns the name of the object at an index.
+    pub fn name(&self, index: usize) -> Cow<'_, str> {
+        let start = index * NAME_LENGTH;
+        let name = &self.names[..self.len * NAME_LENGTH][start..start + NAME_LENGTH];
+        unsafe { CStr::from_ptr(name.as_ptr()) }.to_string_lossy()
+    }
+
+    /// Returns the index of the first object with the given name.
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        (0..self.len).find(|&index| self.name(index).eq_ignore_ascii_case(name))
+    }
+
+    /// Returns the mass of the object at an index, in kilograms.
+    pub fn mass(&self, index: usize) -> f64 {
+        self.masses()[index]
+    }
+
+    /// Returns the masses of all the objects, in kilograms.
+    pub fn masses(&self) -> &[f64] {
+        &self.masses[..self.len]
+    }
+
+    /// Returns the size (mean radius) of the object at an index, in meters.
+    pub fn size(&self, index: usize) -> f64 {
+        self.sizes()[index]
+    }
+
+    /// Returns the sizes (mean radii) of all the objects, in meters.
+    pub fn sizes(&self) -> &[f64] {
+        &self.sizes[..self.len]
+    }
+
+    /// Returns the position of the object at an index in the global frame.
+    pub fn global_pos(&self, index: usize) -> Vector3 {
+        self.positions[..self.len][index].into()
+    }
+
+    /// Returns the velocity of the object at an index in the global frame.
+    pub fn global_velocity(&self, index: usize) -> Vector3 {
+        self.velocities[..self.len][index].into()
+    }
+
+    /// Returns the rotation matrix of the object at an index.
+    ///
+    /// See [`ObjectTrait::rotation_matrix`].
+    ///
+    /// [`ObjectTrait::rotation_matrix`]: ./trait.ObjectTrait.html#method.rotation_matrix
+    pub fn rotation_matrix(&self, index: usize) -> Matrix3 {
+        self.rotations[..self.len][index].into()
+    }
+}
+
+#[cfg_attr(windows, link(name = "orbiter_c"))]
+extern "C" {
+    fn oapic_world_snapshot(
+        capacity: c_int,
+        handles: *mut OBJHANDLE,
+        types: *mut c_int,
+        names: *mut c_char,
+        name_len: c_int,
+        masses: *mut f64,
+        sizes: *mut f64,
+        positions: *mut oapic_VECTOR3,
+        velocities: *mut oapic_VECTOR3,
+        rotations: *mut oapic_MATRIX3,
+    ) -> c_int;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a snapshot of objects with the given names, as filled by
+    /// Orbiter, with room for one more object.
+    fn snapshot(names: &[&str]) -> WorldSnapshot {
+        let mut snapshot = WorldSnapshot::new();
+        snapshot.reserve(names.len() + 1);
+        for (index, name) in names.iter().enumerate() {
+            let start = index * NAME_LENGTH;
+            for (i, &byte) in name.as_bytes().iter().enumerate() {
+                snapshot.names[start + i] = byte as c_char;
+            }
+            snapshot.masses[index] = index as f64;
+        }
+        snapshot.names[names.len() * NAME_LENGTH] = b'X' as c_char;
+        snapshot.len = names.len();
+        snapshot
+    }
+
+    #[test]
+    fn names_are_sliced_by_object() {
+        let long = "L".repeat(NAME_LENGTH - 1);
+        let snapshot = snapshot(&["Earth", "GL-01", &long]);
+        assert_eq!(snapshot.name(0), "Earth");
+        assert_eq!(snapshot.name(1), "GL-01");
+        assert_eq!(snapshot.name(2), long);
+    }
+
+    #[test]
+    fn index_of_ignores_case() {
+        let snapshot = snapshot(&["Earth", "ISS", "iss"]);
+        assert_eq!(snapshot.index_of("iss"), Some(1));
+        assert_eq!(snapshot.index_of("EARTH"), Some(0));
+        assert_eq!(snapshot.index_of("Mir"), None);
+    }
+
+    #[test]
+    fn accessors_stop_at_the_length() {
+        let snapshot = snapshot(&["Earth", "ISS"]);
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(snapshot.masses(), &[0.0, 1.0]);
+        assert_eq!(snapshot.sizes().len(), 2);
+        // The buffers have room for a third object
+        assert_eq!(snapshot.index_of("X"), None);
+        assert!(WorldSnapshot::new().is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn names_past_the_length_panic() {
+        snapshot(&["Earth"]).name(1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn kinds_past_the_length_panic() {
+        snapshot(&["Earth"]).kind(1);
+    }
+}
@@ -0,0 +1,299 @@
+use crate::win::minwindef::DWORD;
+use crate::{GeoPosition, ObjectTrait, Planet, Vector3, OBJHANDLE};
+use std::f64::consts::PI;
+use std::os::raw::c_int;
+
+/// Distance between the samples used to compute surface normals, in meters
+const NORMAL_SAMPLE_DISTANCE: f64 = 10.0;
+
+/// Reference for vessel altitudes
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AltitudeMode {
+    /// Altitude above the mean radius of the planet
+    MeanRadius,
+    /// Altitude above the terrain under the vessel
+    Ground,
+}
+
+impl AltitudeMode {
+    pub(crate) fn to_raw(self) -> c_int {
+        match self {
+            Self::MeanRadius => 0,
+            Self::Ground => 1,
+        }
+    }
+}
+
+impl Planet {
+    /// Returns the elevation of the surface above the mean radius of the
+    /// planet, in meters.
+    ///
+    /// This uses the elevation data of the planet, available since Orbiter 2016.
+    /// The elevation is 0 if the planet has no elevation data.
+    pub fn surface_elevation(&self, latitude: f64, longitude: f64) -> f64 {
+        unsafe { oapic_oapiSurfaceElevation(self.handle, longitude, latitude) }
+    }
+
+    /// Returns the position of the surface at the given coordinates.
+    pub fn surface_position(&self, latitude: f64, longitude: f64) -> GeoPosition {
+        let radius = self.size() + self.surface_elevation(latitude, longitude);
+        GeoPosition::new(latitude, longitude, radius)
+    }
+
+    /// Returns the normal of the surface at the given coordinates.
+    ///
+    /// The normal is a unit vector in the local frame of the planet, computed
+    /// from the elevation of neighbouring points. Use [`rotation_matrix`] to
+    /// convert it to the global frame.
+    ///
+    /// [`rotation_matrix`]: ./trait.ObjectTrait.html#method.rotation_matrix
+    pub fn surface_normal(&self, latitude: f64, longitude: f64) -> Vector3 {
+        let radius = self.size();
+        let dlat = NORMAL_SAMPLE_DISTANCE / radius;
+        let dlng = dlat / latitude.cos().max(dlat);
+
+        let east_slope = (self.surface_elevation(latitude, longitude + dlng)
+            - self.surface_elevation(latitude, longitude - dlng))
+            / (2.0 * NORMAL_SAMPLE_DISTANCE);
+        let north_slope = (self.surface_elevation(latitude + dlat, longitude)
+            - self.surface_elevation(latitude - dlat, longitude))
+            / (2.0 * NORMAL_SAMPLE_DISTANCE);
+        slope_normal(latitude, longitude, east_slope, north_slope)
+    }
+
+    /// Samples the surface elevation over a grid.
+    ///
+    /// The elevations of the grid are replaced, so the same grid can be
+    /// refreshed without allocating.
+    pub fn sample_elevation_grid(&self, grid: &mut ElevationGrid) {
+        unsafe {
+            oapic_surface_elevation_grid(
+                self.handle,
+                grid.latitude,
+                grid.longitude,
+                grid.latitude_step,
+                grid.longitude_step,
+                grid.rows as DWORD,
+                grid.columns as DWORD,
+                grid.elevations.as_mut_ptr(),
+            )
+        };
+    }
+}
+
+/// Returns the normal of a surface rising by `east_slope` and `north_slope`
+/// meters per meter, in the local frame of the planet.
+fn slope_normal(latitude: f64, longitude: f64, east_slope: f64, north_slope: f64) -> Vector3 {
+    let (sin_lat, cos_lat) = latitude.sin_cos();
+    let (sin_lng, cos_lng) = longitude.sin_cos();
+    let up = Vector3::new(cos_lat * cos_lng, sin_lat, cos_lat * sin_lng);
+    let east = Vector3::new(-sin_lng, 0.0, cos_lng);
+    let north = Vector3::new(-sin_lat * cos_lng, cos_lat, -sin_lat * sin_lng);
+
+    (up - east_slope * east - north_slope * north).normalize()
+}
+
+/// Surface elevations sampled over a regular latitude/longitude grid.
+///
+/// Elevations are stored row by row, from south to north, and from west to
+/// east within a row. Fill the grid with [`Planet::sample_elevation_grid`].
+///
+/// [`Planet::sample_elevation_grid`]: ./struct.Planet.html#method.sample_elevation_grid
+///
+/// # Examples
+///
+/// ```
+/// use orbiter::ElevationGrid;
+///
+/// let grid = ElevationGrid::new(0.0, 0.0, 0.001, 0.001, 2, 2);
+/// let (latitude, longitude) = grid.coordinates(1, 1);
+/// assert!((latitude - 0.001).abs() < 1e-12 && (longitude - 0.001).abs() < 1e-12);
+/// assert_eq!(grid.interpolate(0.0005, 0.0005), Some(0.0));
+/// assert_eq!(grid.interpolate(0.002, 0.0), None);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElevationGrid {
+    latitude: f64,
+    longitude: f64,
+    latitude_step: f64,
+    longitude_step: f64,
+    rows: usize,
+    columns: usize,
+    elevations: Vec<f64>,
+}
+
+impl ElevationGrid {
+    /// Creates a grid between two corners, with all elevations set to 0.
+    ///
+    /// Longitudes increase towards the east, so a grid crossing the ±180°
+    /// meridian has a western longitude greater than its eastern longitude.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rows` or `columns` is less than 2.
+    pub fn new(
+        south_latitude: f64,
+        west_longitude: f64,
+        north_latitude: f64,
+        east_longitude: f64,
+        rows: usize,
+        columns: usize,
+    ) -> Self {
+        assert!(
+            rows >= 2 && columns >= 2,
+            "elevation grids need at least 2 rows and 2 columns"
+        );
+        let mut longitude_span = (east_longitude - west_longitude).rem_euclid(2.0 * PI);
+        if longitude_span == 0.0 && east_longitude != west_longitude {
+            longitude_span = 2.0 * PI;
+        }
+        Self {
+            latitude: south_latitude,
+            longitude: west_longitude,
+            latitude_step: (north_latitude - south_latitude) / (rows - 1) as f64,
+            longitude_step: longitude_span / (columns - 1) as f64,
+            rows,
+            columns,
+            elevations: vec![0.0; rows * columns],
+        }
+    }
+
+    /// Returns the number of rows (latitudes) of the grid.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the number of columns (longitudes) of the grid.
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// Returns all elevations of the grid, row by row.
+    pub fn elevations(&self) -> &[f64] {
+        &self.elevations
+    }
+
+    /// Returns the elevation of a grid point.
+    pub fn elevation(&self, row: usize, column: usize) -> f64 {
+        assert!(row < self.rows && column < self.columns);
+        self.elevations[row * self.columns + column]
+    }
+
+    /// Returns the latitude and longitude of a grid point.
+    pub fn coordinates(&self, row: usize, column: usize) -> (f64, f64) {
+        let latitude = self.latitude + row as f64 * self.latitude_step;
+        let longitude = self.longitude + column as f64 * self.longitude_step;
+        (latitude, (longitude + PI).rem_euclid(2.0 * PI) - PI)
+    }
+
+    /// Returns the elevation at the given coordinates, bilinearly interpolated
+    /// between the grid points.
+    ///
+    /// Returns `None` if the coordinates are outside of the grid.
+    pub fn interpolate(&self, latitude: f64, longitude: f64) -> Option<f64> {
+        let row = (latitude - self.latitude) / self.latitude_step;
+        let column = (longitude - self.longitude).rem_euclid(2.0 * PI) / self.longitude_step;
+        let max_row = (self.rows - 1) as f64;
+        let max_column = (self.columns - 1) as f64;
+        if !(0.0..=max_row).contains(&row) || !(0.0..=max_column).contains(&column) {
+            return None;
+        }
+
+        let row0 = (row.floor() as usize).min(self.rows - 2);
+        let column0 = (column.floor() as usize).min(self.columns - 2);
+        let row_frac = row - row0 as f64;
+        let column_frac = column - column0 as f64;
+
+        let south = self.elevation(row0, column0) * (1.0 - column_frac)
+            + self.elevation(row0, column0 + 1) * column_frac;
+        let north = self.elevation(row0 + 1, column0) * (1.0 - column_frac)
+            + self.elevation(row0 + 1, column0 + 1) * column_frac;
+        Some(south * (1.0 - row_frac) + north * row_frac)
+    }
+}
+
+#[cfg_attr(windows, link(name = "orbiter_c"))]
+extern "C" {
+    fn oapic_oapiSurfaceElevation(handle: OBJHANDLE, lng: f64, lat: f64) -> f64;
+    fn oapic_surface_elevation_grid(
+        handle: OBJHANDLE,
+        lat: f64,
+        lng: f64,
+        dlat: f64,
+        dlng: f64,
+        rows: DWORD,
+        columns: DWORD,
+        elevations: *mut f64,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::FRAC_PI_4;
+
+    #[test]
+    fn flat_ground_points_up() {
+        let normal = slope_normal(0.0, 0.0, 0.0, 0.0);
+        assert!((normal - Vector3::x()).norm() < 1e-12);
+        let normal = slope_normal(FRAC_PI_4, 0.0, 0.0, 0.0);
+        assert!((normal - Vector3::new(1.0, 1.0, 0.0).normalize()).norm() < 1e-12);
+        // Orbiter's frames are left-handed: the east is along z at the
+        // equator
+        let normal = slope_normal(0.0, -PI / 2.0, 0.0, 0.0);
+        assert!((normal + Vector3::z()).norm() < 1e-12);
+    }
+
+    #[test]
+    fn slopes_tilt_the_normal_downhill() {
+        // Rising by 1 m per m toward the east
+        let normal = slope_normal(0.0, 0.0, 1.0, 0.0);
+        assert!((normal - Vector3::new(1.0, 0.0, -1.0).normalize()).norm() < 1e-12);
+        // Rising toward the north
+        let normal = slope_normal(0.0, 0.0, 0.0, 0.5);
+        assert!((normal - Vector3::new(1.0, -0.5, 0.0).normalize()).norm() < 1e-12);
+    }
+
+    #[test]
+    fn grids_across_the_antimeridian() {
+        let grid = ElevationGrid::new(0.0, 3.0, 0.1, -3.0, 2, 3);
+        let (_, middle) = grid.coordinates(0, 1);
+        assert!((middle.abs() - PI).abs() < 1e-12);
+        let (_, east) = grid.coordinates(0, 2);
+        assert!((east + 3.0).abs() < 1e-12);
+
+        assert_eq!(grid.interpolate(0.05, PI), Some(0.0));
+        assert_eq!(grid.interpolate(0.05, -PI), Some(0.0));
+        assert_eq!(grid.interpolate(0.05, 0.0), None);
+    }
+
+    #[test]
+    fn grids_around_the_planet() {
+        let grid = ElevationGrid::new(-0.1, -PI, 0.1, PI, 2, 5);
+        let (_, middle) = grid.coordinates(0, 2);
+        assert!(middle.abs() < 1e-12);
+        let (_, east) = grid.coordinates(0, 4);
+        assert!((east.abs() - PI).abs() < 1e-12);
+        assert!(grid.interpolate(0.0, -2.0).is_some());
+        assert!(grid.interpolate(0.0, 3.0).is_some());
+    }
+
+    #[test]
+    fn bilinear_interpolation() {
+        let mut grid = ElevationGrid::new(0.0, 0.0, 1.0, 1.0, 2, 2);
+        grid.elevations = vec![0.0, 100.0, 200.0, 400.0];
+        assert_eq!(grid.elevation(1, 0), 200.0);
+        assert_eq!(grid.interpolate(0.0, 0.0), Some(0.0));
+        assert_eq!(grid.interpolate(1.0, 1.0), Some(400.0));
+        assert_eq!(grid.interpolate(0.0, 0.5), Some(50.0));
+        assert_eq!(grid.interpolate(0.5, 0.5), Some(175.0));
+        assert_eq!(grid.interpolate(-0.1, 0.5), None);
+        assert_eq!(grid.interpolate(1.1, 0.5), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least 2 rows")]
+    fn grids_need_two_rows() {
+        ElevationGrid::new(0.0, 0.0, 1.0, 1.0, 1, 2);
+    }
+}
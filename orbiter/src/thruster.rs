@@ -0,0 +1,55 @@
+use std::os::raw::c_int;
+
+/// Logical group of thrusters, controlled together
+///
+/// Vessels assign their thrusters to groups, which map to the standard user
+/// controls, e.g. the main engines to the throttle.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ThrusterGroup {
+    /// Main engines, thrusting forward
+    Main,
+    /// Retro engines, thrusting backward
+    Retro,
+    /// Hover engines, thrusting upward
+    Hover,
+}
+
+impl ThrusterGroup {
+    pub(crate) fn to_raw(self) -> c_int {
+        match self {
+            Self::Main => 0,
+            Self::Retro => 1,
+            Self::Hover => 2,
+        }
+    }
+}
+
+/// Mode of the attitude thrusters (RCS)
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AttitudeMode {
+    /// Attitude thrusters disabled
+    Disabled,
+    /// Attitude thrusters used to rotate the vessel
+    Rotational,
+    /// Attitude thrusters used to translate the vessel
+    Linear,
+}
+
+impl AttitudeMode {
+    pub(crate) fn to_raw(self) -> c_int {
+        match self {
+            Self::Disabled => 0,
+            Self::Rotational => 1,
+            Self::Linear => 2,
+        }
+    }
+
+    pub(crate) fn from_raw(value: c_int) -> Option<Self> {
+        match value {
+            0 => Some(Self::Disabled),
+            1 => Some(Self::Rotational),
+            2 => Some(Self::Linear),
+            _ => None,
+        }
+    }
+}
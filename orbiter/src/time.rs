@@ -0,0 +1,357 @@
+//! Time scales and date conversions.
+//!
+//! Orbiter measures dates in Modified Julian Date (MJD), e.g. in
+//! [`Module::sim_mjd`] and the `mjd` parameter of the step callbacks. The
+//! [`Mjd`] newtype wraps these dates, as returned by [`Module::sim_date`] or
+//! with `Mjd(mjd)`, and converts them to UTC, Julian dates, seconds since
+//! J2000 and the TAI and TT time scales, and computes sidereal times.
+//! [`MissionElapsedTime`] formats durations for display.
+//!
+//! Like Orbiter, this module treats MJD as a uniform UTC-based time scale:
+//! leap seconds only appear in the offsets of the TAI and TT time scales.
+//! This module is pure Rust.
+//!
+//! # Examples
+//!
+//! ```
+//! use chrono::{TimeZone, Utc};
+//! use orbiter::time::{MissionElapsedTime, Mjd};
+//!
+//! let launch = Mjd::from_utc(&Utc.with_ymd_and_hms(2000, 1, 1, 12, 0, 0).unwrap());
+//! assert_eq!(launch.julian_date(), 2451545.0);
+//!
+//! // TT is ahead of UTC by 32 leap seconds and 32.184 s, so the J2000 epoch
+//! // (12:00 TT) is 64.184 s before noon UTC
+//! assert!((launch.tt().seconds_since(launch) - 64.184).abs() < 1e-4);
+//! assert!((launch.j2000_seconds() - 64.184).abs() < 1e-4);
+//!
+//! let now = launch.add_seconds(753.0);
+//! assert_eq!(now.elapsed_since(launch).to_string(), "T+ 00:12:33");
+//! ```
+//!
+//! [`Module::sim_mjd`]: ../struct.Module.html#method.sim_mjd
+//! [`Module::sim_date`]: ../struct.Module.html#method.sim_date
+//! [`Mjd`]: ./struct.Mjd.html
+//! [`MissionElapsedTime`]: ./struct.MissionElapsedTime.html
+
+use crate::orbit::normalize_angle;
+use chrono::{DateTime, Utc};
+
+/// Number of seconds in a day
+pub(crate) const SECONDS_PER_DAY: f64 = 86400.0;
+
+/// Difference between a Julian date and an MJD, in days
+const JULIAN_DATE_OFFSET: f64 = 2400000.5;
+
+/// MJD of the Unix epoch, 1970-01-01 00:00 UTC
+const UNIX_EPOCH: f64 = 40587.0;
+
+/// MJD of 2000-01-01 12:00, the J2000 epoch in TT and the reference of
+/// sidereal times in UT
+const J2000_NOON: f64 = 51544.5;
+
+/// Number of days in a Julian century
+const DAYS_PER_CENTURY: f64 = 36525.0;
+
+/// Offset of TT from TAI, in s
+const TT_OFFSET: f64 = 32.184;
+
+/// Offsets of TAI from UTC (in s), from the MJD they apply
+const LEAP_SECONDS: [(f64, f64); 28] = [
+    (41317.0, 10.0),
+    (41499.0, 11.0),
+    (41683.0, 12.0),
+    (42048.0, 13.0),
+    (42413.0, 14.0),
+    (42778.0, 15.0),
+    (43144.0, 16.0),
+    (43509.0, 17.0),
+    (43874.0, 18.0),
+    (44239.0, 19.0),
+    (44786.0, 20.0),
+    (45151.0, 21.0),
+    (45516.0, 22.0),
+    (46247.0, 23.0),
+    (47161.0, 24.0),
+    (47892.0, 25.0),
+    (48257.0, 26.0),
+    (48804.0, 27.0),
+    (49169.0, 28.0),
+    (49534.0, 29.0),
+    (50083.0, 30.0),
+    (50630.0, 31.0),
+    (51179.0, 32.0),
+    (53736.0, 33.0),
+    (54832.0, 34.0),
+    (56109.0, 35.0),
+    (57204.0, 36.0),
+    (57754.0, 37.0),
+];
+
+/// Modified Julian Date, in days
+#[derive(Debug, Copy, Clone, Default, PartialEq, PartialOrd)]
+pub struct Mjd(pub f64);
+
+impl Mjd {
+    /// J2000 epoch, 2000-01-01 12:00 TT, i.e. 11:58:55.816 UTC
+    pub const J2000: Mjd = Mjd(J2000_NOON - (32.0 + TT_OFFSET) / SECONDS_PER_DAY);
+
+    /// Creates a date from a Julian date.
+    pub fn from_julian_date(julian_date: f64) -> Self {
+        Self(julian_date - JULIAN_DATE_OFFSET)
+    }
+
+    /// Returns the Julian date.
+    pub fn julian_date(self) -> f64 {
+        self.0 + JULIAN_DATE_OFFSET
+    }
+
+    /// Creates a date from a number of TT seconds since the J2000 epoch.
+    pub fn from_j2000_seconds(seconds: f64) -> Self {
+        Self::from_tt(Mjd(J2000_NOON).add_seconds(seconds))
+    }
+
+    /// Returns the number of TT seconds since the J2000 epoch, as used by
+    /// ephemerides.
+    pub fn j2000_seconds(self) -> f64 {
+        self.tt().seconds_since(Mjd(J2000_NOON))
+    }
+
+    /// Creates a date from a UTC date and time.
+    pub fn from_utc(date: &DateTime<Utc>) -> Self {
+        let seconds = date.timestamp() as f64 + f64::from(date.timestamp_subsec_nanos()) * 1e-9;
+        Self(UNIX_EPOCH + seconds / SECONDS_PER_DAY)
+    }
+
+    /// Returns the UTC date and time, rounded to the microsecond.
+    ///
+    /// Returns `None` if the date is out of the range of `chrono`.
+    pub fn to_utc(self) -> Option<DateTime<Utc>> {
+        let microseconds = ((self.0 - UNIX_EPOCH) * SECONDS_PER_DAY * 1e6).round();
+        if !microseconds.is_finite() || microseconds.abs() > i64::MAX as f64 {
+            return None;
+        }
+        DateTime::from_timestamp_micros(microseconds as i64)
+    }
+
+    /// Returns the date a number of seconds later.
+    pub fn add_seconds(self, seconds: f64) -> Self {
+        Self(self.0 + seconds / SECONDS_PER_DAY)
+    }
+
+    /// Returns the number of seconds since an earlier date.
+    pub fn seconds_since(self, earlier: Mjd) -> f64 {
+        (self.0 - earlier.0) * SECONDS_PER_DAY
+    }
+
+    /// Returns the mission elapsed time since a launch date.
+    pub fn elapsed_since(self, launch: Mjd) -> MissionElapsedTime {
+        MissionElapsedTime(self.seconds_since(launch))
+    }
+
+    /// Returns the offset of TAI from UTC at this date (in s), i.e. the
+    /// number of leap seconds plus 10 s.
+    ///
+    /// Before 1972, UTC did not use leap seconds; the offset is then taken as
+    /// 10 s.
+    pub fn tai_offset(self) -> f64 {
+        LEAP_SECONDS
+            .iter()
+            .rev()
+            .find(|(start, _)| self.0 >= *start)
+            .map_or(LEAP_SECONDS[0].1, |(_, offset)| *offset)
+    }
+
+    /// Returns the offset of TT from UTC at this date, in s.
+    pub fn tt_offset(self) -> f64 {
+        self.tai_offset() + TT_OFFSET
+    }
+
+    /// Returns the date in the TAI time scale.
+    pub fn tai(self) -> Self {
+        self.add_seconds(self.tai_offset())
+    }
+
+    /// Creates a date from a date in the TAI time scale.
+    pub fn from_tai(tai: Mjd) -> Self {
+        let estimate = tai.add_seconds(-tai.tai_offset());
+        tai.add_seconds(-estimate.tai_offset())
+    }
+
+    /// Returns the date in the TT time scale.
+    pub fn tt(self) -> Self {
+        self.add_seconds(self.tt_offset())
+    }
+
+    /// Creates a date from a date in the TT time scale.
+    pub fn from_tt(tt: Mjd) -> Self {
+        Self::from_tai(tt.add_seconds(-TT_OFFSET))
+    }
+
+    /// Returns the Greenwich mean sidereal time (in rad, between 0 and 2π).
+    ///
+    /// Uses the IAU 1982 model, taking UT1 as UTC.
+    pub fn greenwich_sidereal_time(self) -> f64 {
+        let days = self.0 - J2000_NOON;
+        let centuries = days / DAYS_PER_CENTURY;
+        let degrees = 280.46061837 + 360.98564736629 * days + 0.000387933 * centuries.powi(2)
+            - centuries.powi(3) / 38710000.0;
+        normalize_angle(degrees.to_radians())
+    }
+
+    /// Returns the local mean sidereal time (in rad, between 0 and 2π) at a
+    /// longitude (in rad, positive east).
+    pub fn local_sidereal_time(self, longitude: f64) -> f64 {
+        normalize_angle(self.greenwich_sidereal_time() + longitude)
+    }
+}
+
+impl From<f64> for Mjd {
+    fn from(mjd: f64) -> Self {
+        Self(mjd)
+    }
+}
+
+impl From<Mjd> for f64 {
+    fn from(mjd: Mjd) -> Self {
+        mjd.0
+    }
+}
+
+impl std::fmt::Display for Mjd {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "MJD {:.*}", f.precision().unwrap_or(5), self.0)
+    }
+}
+
+/// Mission elapsed time, in s
+///
+/// Displays as `T+ 00:12:33`, with a day count past one day
+/// (`T+ 2d 03:04:05`) and `T-` before the launch. Partial seconds are
+/// truncated after rounding to the microsecond.
+#[derive(Debug, Copy, Clone, Default, PartialEq, PartialOrd)]
+pub struct MissionElapsedTime(pub f64);
+
+impl MissionElapsedTime {
+    /// Returns the days, hours, minutes and seconds of the absolute time.
+    pub fn components(self) -> (u64, u64, u64, u64) {
+        // Rounding first avoids e.g. displaying 1.9999999 s as 1 s
+        let seconds = (self.0.abs() * 1e6).round() as u64 / 1_000_000;
+        (
+            seconds / 86400,
+            seconds / 3600 % 24,
+            seconds / 60 % 60,
+            seconds % 60,
+        )
+    }
+}
+
+impl std::fmt::Display for MissionElapsedTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.0 < 0.0 { '-' } else { '+' };
+        let (days, hours, minutes, seconds) = self.components();
+        write!(f, "T{} ", sign)?;
+        if days > 0 {
+            write!(f, "{}d ", days)?;
+        }
+        write!(f, "{:02}:{:02}:{:02}", hours, minutes, seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn utc_round_trip() {
+        let date = Utc.with_ymd_and_hms(2023, 2, 25, 2, 57, 46).unwrap();
+        let mjd = Mjd::from_utc(&date);
+        assert!((mjd.0 - 60000.123449074).abs() < 1e-9);
+        assert_eq!(mjd.to_utc(), Some(date));
+
+        let mjd = Mjd(60000.123456789);
+        let back = Mjd::from_utc(&mjd.to_utc().unwrap());
+        assert!(back.seconds_since(mjd).abs() < 1e-6);
+
+        assert_eq!(
+            Mjd(UNIX_EPOCH).to_utc(),
+            Some(Utc.timestamp_opt(0, 0).unwrap())
+        );
+        assert_eq!(Mjd(f64::NAN).to_utc(), None);
+    }
+
+    #[test]
+    fn julian_date_and_j2000_seconds() {
+        assert_eq!(Mjd(51544.5).julian_date(), 2451545.0);
+        assert_eq!(Mjd::from_julian_date(2451545.0), Mjd(51544.5));
+        assert!(Mjd::J2000.j2000_seconds().abs() < 1e-5);
+        assert!(Mjd::J2000.tt().seconds_since(Mjd(51544.5)).abs() < 1e-5);
+
+        let date = Mjd::from_j2000_seconds(7.5e8);
+        assert!((date.j2000_seconds() - 7.5e8).abs() < 1e-4);
+    }
+
+    #[test]
+    fn leap_seconds() {
+        assert_eq!(Mjd(41316.0).tai_offset(), 10.0);
+        assert_eq!(Mjd(51544.5).tai_offset(), 32.0);
+        assert_eq!(Mjd(57753.99).tai_offset(), 36.0);
+        assert_eq!(Mjd(57754.0).tai_offset(), 37.0);
+        assert_eq!(Mjd(60000.0).tt_offset(), 69.184);
+    }
+
+    #[test]
+    fn tai_and_tt_are_inverted_across_a_leap_second() {
+        for &seconds in &[-60.0, -1.0, -0.5, 0.0, 0.5, 1.0, 60.0] {
+            let utc = Mjd(57754.0).add_seconds(seconds);
+            assert!(Mjd::from_tai(utc.tai()).seconds_since(utc).abs() < 1e-5);
+            assert!(Mjd::from_tt(utc.tt()).seconds_since(utc).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn sidereal_time() {
+        // Vallado, Fundamentals of Astrodynamics, example 3-5
+        let date = Mjd::from_utc(&Utc.with_ymd_and_hms(1992, 8, 20, 12, 14, 0).unwrap());
+        let gmst = date.greenwich_sidereal_time().to_degrees();
+        assert!((gmst - 152.578787810).abs() < 1e-6);
+
+        let lst = date
+            .local_sidereal_time((-104.0f64).to_radians())
+            .to_degrees();
+        assert!((lst - 48.578787810).abs() < 1e-6);
+
+        let gmst = Mjd(51544.5).greenwich_sidereal_time().to_degrees();
+        assert!((gmst - 280.46061837).abs() < 1e-8);
+    }
+
+    #[test]
+    fn mission_elapsed_time() {
+        for seconds in 0..100_000 {
+            let elapsed = Mjd::J2000
+                .add_seconds(f64::from(seconds))
+                .elapsed_since(Mjd::J2000);
+            let (_, _, _, field) = elapsed.components();
+            assert_eq!(field, seconds as u64 % 60);
+        }
+
+        assert_eq!(MissionElapsedTime(753.0).to_string(), "T+ 00:12:33");
+        assert_eq!(MissionElapsedTime(753.9).to_string(), "T+ 00:12:33");
+        assert_eq!(MissionElapsedTime(-10.5).to_string(), "T- 00:00:10");
+        assert_eq!(
+            MissionElapsedTime(2.0 * 86400.0 + 3.0 * 3600.0 + 4.0 * 60.0 + 5.0).to_string(),
+            "T+ 2d 03:04:05"
+        );
+        assert_eq!(
+            MissionElapsedTime(-(86400.0 + 1.0)).to_string(),
+            "T- 1d 00:00:01"
+        );
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Mjd(51544.5).to_string(), "MJD 51544.50000");
+        assert_eq!(format!("{:.1}", Mjd(51544.5)), "MJD 51544.5");
+    }
+}
@@ -0,0 +1,1043 @@
+//! Trajectory prediction with patched conics.
+//!
+//! A [`System`] describes the celestial bodies, each with its sphere of
+//! influence (SOI) and its Keplerian orbit around its parent. A
+//! [`Predictor`] follows a vessel along Keplerian arcs around one body at a
+//! time, switching to the parent or to a child body when the vessel leaves or
+//! enters an SOI, and reports the events along the way: SOI transitions,
+//! periapsis passages and impacts. [`Trajectory::closest_approach`] then finds
+//! the closest approach to another object.
+//!
+//! Dates are MJD, and each arc is expressed in the right-handed frame of its
+//! central body, as in the [`orbit`] module.
+//!
+//! # Examples
+//!
+//! ```
+//! use orbiter::kepler::KeplerOrbit;
+//! use orbiter::orbit::StateVector;
+//! use orbiter::trajectory::{EventKind, Predictor, System};
+//! use orbiter::Vector3;
+//!
+//! const MU_SUN: f64 = 1.32712440018e20;
+//! const MU_EARTH: f64 = 3.986004418e14;
+//! const AU: f64 = 1.495978707e11;
+//!
+//! let mut system = System::new();
+//! let sun = system.add_root("Sun", MU_SUN, 696e6);
+//! let earth_state = StateVector::new(
+//!     Vector3::new(AU, 0.0, 0.0),
+//!     Vector3::new(0.0, (MU_SUN / AU).sqrt(), 0.0),
+//! );
+//! let earth_orbit = KeplerOrbit::new(earth_state, MU_SUN, 51544.0);
+//! let earth = system.add_body("Earth", MU_EARTH, 6371e3, sun, earth_orbit);
+//!
+//! // Escape trajectory from a low orbit
+//! let state = StateVector::new(Vector3::new(6678e3, 0.0, 0.0), Vector3::new(0.0, 11.5e3, 0.0));
+//! let orbit = KeplerOrbit::new(state, MU_EARTH, 51544.0);
+//! let trajectory = Predictor::new(&system).predict(earth, orbit).unwrap();
+//!
+//! let exit = trajectory
+//!     .events
+//!     .iter()
+//!     .find(|event| event.kind == EventKind::SoiExit)
+//!     .unwrap();
+//! assert_eq!(exit.body, earth);
+//! assert!((exit.state.position.norm() - system.body(earth).soi_radius).abs() < 1.0);
+//! assert_eq!(trajectory.segments.last().unwrap().body, sun);
+//! ```
+//!
+//! [`System`]: ./struct.System.html
+//! [`Predictor`]: ./struct.Predictor.html
+//! [`Trajectory::closest_approach`]: ./struct.Trajectory.html#method.closest_approach
+//! [`orbit`]: ../orbit/index.html
+
+use crate::kepler::{ConvergenceError, KeplerOrbit};
+use crate::orbit::{OrbitalElements, StateVector};
+use crate::time::SECONDS_PER_DAY;
+use crate::{as_celestial, Object, ObjectTrait, Vector3, VesselTrait};
+use std::f64::consts::PI;
+
+/// Number of samples per orbit when searching for SOI entries and closest
+/// approaches
+const SAMPLES_PER_ORBIT: f64 = 360.0;
+
+/// Maximum number of samples per search
+const MAX_SAMPLES: f64 = 100_000.0;
+
+/// Maximum number of segments of a trajectory
+const MAX_SEGMENTS: usize = 64;
+
+/// Precision of the event dates, in days (about 1 ms)
+const DATE_TOLERANCE: f64 = 1e-8;
+
+/// A celestial body of a [`System`]
+///
+/// [`System`]: ./struct.System.html
+#[derive(Debug, Clone, PartialEq)]
+pub struct SystemBody {
+    /// Name of the body
+    pub name: String,
+    /// Gravitational parameter, in m³/s²
+    pub mu: f64,
+    /// Mean radius, in m
+    pub radius: f64,
+    /// Radius of the sphere of influence, in m
+    ///
+    /// Infinite for the roots of the system.
+    pub soi_radius: f64,
+    /// Index of the parent body, or `None` for the roots of the system
+    pub parent: Option<usize>,
+    /// Orbit around the parent body
+    pub orbit: Option<KeplerOrbit>,
+}
+
+/// Hierarchy of celestial bodies orbiting each other
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct System {
+    bodies: Vec<SystemBody>,
+}
+
+impl System {
+    /// Creates an empty system.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a system from the stars and planets of the simulation, with
+    /// their orbits at a date (MJD).
+    pub fn from_simulation(mjd: f64) -> Self {
+        let objects: Vec<Object> = Object::all_objects()
+            .filter(|object| as_celestial(object).is_some())
+            .collect();
+        let parents: Vec<Option<usize>> = objects
+            .iter()
+            .map(|object| {
+                let parent = as_celestial(object)?.parent()?;
+                objects.iter().position(|other| *other == parent)
+            })
+            .collect();
+
+        // Parents must be added before their children
+        let mut system = Self::new();
+        let mut indices = vec![None; objects.len()];
+        let mut added = true;
+        while added {
+            added = false;
+            for (i, object) in objects.iter().enumerate() {
+                if indices[i].is_some() {
+                    continue;
+                }
+                let body = as_celestial(object).unwrap();
+                let mu = body.gravitational_parameter();
+                let radius = body.equatorial_radius();
+                indices[i] = match parents[i] {
+                    None => Some(system.add_root(&object.name(), mu, radius)),
+                    Some(parent) => indices[parent].map(|parent_index| {
+                        let state = StateVector::relative(object, &objects[parent]);
+                        let orbit =
+                            KeplerOrbit::new(state, system.bodies[parent_index].mu + mu, mjd);
+                        system.add_body(&object.name(), mu, radius, parent_index, orbit)
+                    }),
+                };
+                added |= indices[i].is_some();
+            }
+        }
+        system
+    }
+
+    /// Adds a root body, e.g. the central star, and returns its index.
+    pub fn add_root(&mut self, name: &str, mu: f64, radius: f64) -> usize {
+        self.bodies.push(SystemBody {
+            name: name.to_string(),
+            mu,
+            radius,
+            soi_radius: f64::INFINITY,
+            parent: None,
+            orbit: None,
+        });
+        self.bodies.len() - 1
+    }
+
+    /// Adds a body orbiting another one and returns its index.
+    ///
+    /// The radius of the sphere of influence is computed from the
+    /// semi-major axis of the orbit, or from the current distance if the
+    /// orbit is open.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parent index is out of bounds.
+    pub fn add_body(
+        &mut self,
+        name: &str,
+        mu: f64,
+        radius: f64,
+        parent: usize,
+        orbit: KeplerOrbit,
+    ) -> usize {
+        let elements = orbit.elements();
+        let distance = if elements.is_closed() {
+            elements.semi_major_axis()
+        } else {
+            orbit.state.position.norm()
+        };
+        let soi_radius = distance * (mu / self.bodies[parent].mu).powf(0.4);
+        self.bodies.push(SystemBody {
+            name: name.to_string(),
+            mu,
+            radius,
+            soi_radius,
+            parent: Some(parent),
+            orbit: Some(orbit),
+        });
+        self.bodies.len() - 1
+    }
+
+    /// Returns the bodies of the system.
+    pub fn bodies(&self) -> &[SystemBody] {
+        &self.bodies
+    }
+
+    /// Returns the body at an index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn body(&self, index: usize) -> &SystemBody {
+        &self.bodies[index]
+    }
+
+    /// Returns the index of the body with the given name.
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.bodies
+            .iter()
+            .position(|body| body.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Returns the indices of the bodies orbiting a body.
+    pub fn children(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        (0..self.bodies.len()).filter(move |&i| self.bodies[i].parent == Some(index))
+    }
+
+    /// Returns the state of a body relative to its root at a date (MJD).
+    pub fn state_at(&self, index: usize, mjd: f64) -> Result<StateVector, ConvergenceError> {
+        let mut state = StateVector::new(Vector3::zeros(), Vector3::zeros());
+        let mut body = &self.bodies[index];
+        while let (Some(parent), Some(orbit)) = (body.parent, &body.orbit) {
+            state = state + orbit.state_at(mjd)?;
+            body = &self.bodies[parent];
+        }
+        Ok(state)
+    }
+
+    /// Returns the body of the system that a vessel orbits and the orbit of
+    /// the vessel around it at a date (MJD).
+    ///
+    /// Returns `None` if the gravity reference of the vessel is not in the
+    /// system.
+    pub fn orbit_of<V: VesselTrait>(&self, vessel: &V, mjd: f64) -> Option<(usize, KeplerOrbit)> {
+        let reference = vessel.gravity_ref()?;
+        let index = self.index_of(&reference.name())?;
+        let state = StateVector::relative(vessel, &reference);
+        Some((index, KeplerOrbit::new(state, self.bodies[index].mu, mjd)))
+    }
+}
+
+/// Kind of an [`Event`]
+///
+/// [`Event`]: ./struct.Event.html
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EventKind {
+    /// The vessel enters the sphere of influence of the body
+    SoiEntry,
+    /// The vessel leaves the sphere of influence of the body
+    SoiExit,
+    /// The vessel passes the periapsis of its orbit around the body
+    Periapsis,
+    /// The vessel hits the surface of the body
+    Impact,
+}
+
+/// An event along a trajectory
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Event {
+    /// Kind of the event
+    pub kind: EventKind,
+    /// Index of the body of the event
+    pub body: usize,
+    /// Date of the event (MJD)
+    pub mjd: f64,
+    /// State of the vessel relative to the body
+    pub state: StateVector,
+}
+
+/// Part of a trajectory along a Keplerian orbit around a single body
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Segment {
+    /// Index of the central body
+    pub body: usize,
+    /// Orbit around the body, with its epoch at the start of the segment
+    pub orbit: KeplerOrbit,
+    /// Date of the start of the segment (MJD)
+    pub start: f64,
+    /// Date of the end of the segment (MJD)
+    pub end: f64,
+}
+
+/// Object to search the closest approach to
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Target {
+    /// A body of the system
+    Body(usize),
+    /// An object orbiting a body of the system, e.g. another vessel
+    Orbit {
+        /// Index of the central body
+        body: usize,
+        /// Orbit around the body
+        orbit: KeplerOrbit,
+    },
+}
+
+/// Closest approach between a trajectory and a target
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Approach {
+    /// Date of the closest approach (MJD)
+    pub mjd: f64,
+    /// Distance between the centers, in m
+    pub distance: f64,
+    /// Relative speed, in m/s
+    pub relative_speed: f64,
+}
+
+/// Predicted trajectory of a vessel
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Trajectory {
+    /// Keplerian segments, in chronological order
+    pub segments: Vec<Segment>,
+    /// Events, in chronological order
+    pub events: Vec<Event>,
+}
+
+impl Trajectory {
+    /// Returns the date of the end of the prediction (MJD).
+    pub fn end(&self) -> Option<f64> {
+        self.segments.last().map(|segment| segment.end)
+    }
+
+    /// Returns the segment containing a date (MJD).
+    pub fn segment_at(&self, mjd: f64) -> Option<&Segment> {
+        self.segments
+            .iter()
+            .find(|segment| segment.start <= mjd && mjd <= segment.end)
+    }
+
+    /// Returns the index of the central body and the state of the vessel
+    /// relative to it at a date (MJD).
+    ///
+    /// Returns `Ok(None)` if the date is outside of the prediction.
+    pub fn state_at(&self, mjd: f64) -> Result<Option<(usize, StateVector)>, ConvergenceError> {
+        match self.segment_at(mjd) {
+            Some(segment) => Ok(Some((segment.body, segment.orbit.state_at(mjd)?))),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the first impact, if any.
+    pub fn impact(&self) -> Option<&Event> {
+        self.events
+            .iter()
+            .find(|event| event.kind == EventKind::Impact)
+    }
+
+    /// Finds the closest approach to a target over the whole trajectory.
+    ///
+    /// Returns `Ok(None)` if the trajectory is empty.
+    pub fn closest_approach(
+        &self,
+        system: &System,
+        target: &Target,
+    ) -> Result<Option<Approach>, ConvergenceError> {
+        let target_state = |mjd: f64| match target {
+            Target::Body(body) => system.state_at(*body, mjd),
+            Target::Orbit { body, orbit } => {
+                Ok(system.state_at(*body, mjd)? + orbit.state_at(mjd)?)
+            }
+        };
+        let target_period = match target {
+            Target::Body(body) => system.bodies[*body]
+                .orbit
+                .and_then(|orbit| orbit.elements().period()),
+            Target::Orbit { orbit, .. } => orbit.elements().period(),
+        };
+
+        let mut best: Option<Approach> = None;
+        for segment in &self.segments {
+            let relative = |mjd: f64| -> Result<StateVector, ConvergenceError> {
+                let vessel = system.state_at(segment.body, mjd)? + segment.orbit.state_at(mjd)?;
+                Ok(vessel - target_state(mjd)?)
+            };
+            let distance = |mjd: f64| Ok(relative(mjd)?.position.norm());
+
+            let periods = [segment.orbit.elements().period(), target_period];
+            let step = sample_step(segment.start, segment.end, &periods);
+            if let Some((mjd, _)) = minimum(distance, segment.start, segment.end, step)? {
+                let state = relative(mjd)?;
+                let approach = Approach {
+                    mjd,
+                    distance: state.position.norm(),
+                    relative_speed: state.velocity.norm(),
+                };
+                if best.map_or(true, |best| approach.distance < best.distance) {
+                    best = Some(approach);
+                }
+            }
+        }
+        Ok(best)
+    }
+}
+
+/// Predicts trajectories across spheres of influence
+///
+/// By default, the prediction covers one revolution, and at most one year.
+pub struct Predictor<'a> {
+    system: &'a System,
+    orbits: f64,
+    max_duration: f64,
+}
+
+impl<'a> Predictor<'a> {
+    /// Creates a predictor for the bodies of a system.
+    pub fn new(system: &'a System) -> Self {
+        Self {
+            system,
+            orbits: 1.0,
+            max_duration: 365.0 * SECONDS_PER_DAY,
+        }
+    }
+
+    /// Sets the number of revolutions to predict.
+    ///
+    /// Only closed orbits count toward the revolutions. The prediction stops
+    /// earlier on impacts and after the maximum duration.
+    pub fn orbits(mut self, orbits: usize) -> Self {
+        self.orbits = orbits as f64;
+        self
+    }
+
+    /// Sets the maximum duration of the prediction, in s.
+    pub fn max_duration(mut self, duration: f64) -> Self {
+        self.max_duration = duration;
+        self
+    }
+
+    /// Predicts the trajectory of a vessel from its orbit around a body of
+    /// the system.
+    pub fn predict(&self, body: usize, orbit: KeplerOrbit) -> Result<Trajectory, ConvergenceError> {
+        let bodies = &self.system.bodies;
+        let final_mjd = orbit.epoch + self.max_duration / SECONDS_PER_DAY;
+        let mut trajectory = Trajectory::default();
+        let mut revolutions = 0.0;
+        let mut body = body;
+        let mut orbit = orbit;
+
+        while trajectory.segments.len() < MAX_SEGMENTS {
+            let elements = orbit.elements();
+            let central = &bodies[body];
+            let start = orbit.epoch;
+            let mut end = final_mjd;
+            if let Some(period) = elements.period() {
+                end = end.min(start + (self.orbits - revolutions) * period / SECONDS_PER_DAY);
+            }
+
+            let mut transition = None;
+            let mut candidates = Vec::new();
+            if elements.periapsis() < central.radius {
+                candidates.push((
+                    inbound_time(&elements, central.radius),
+                    EventKind::Impact,
+                    body,
+                ));
+            }
+            if let Some(parent) = central.parent {
+                candidates.push((
+                    outbound_time(&elements, central.soi_radius),
+                    EventKind::SoiExit,
+                    parent,
+                ));
+            }
+            for (time, kind, next) in candidates {
+                if let Some(time) = time {
+                    let mjd = start + time / SECONDS_PER_DAY;
+                    if mjd < end {
+                        end = mjd;
+                        transition = Some((kind, next));
+                    }
+                }
+            }
+            for child in self.system.children(body) {
+                if let Some(mjd) = entry_date(&bodies[child], &orbit, start, end)? {
+                    end = mjd;
+                    transition = Some((EventKind::SoiEntry, child));
+                }
+            }
+
+            if let Some(time) = elements.time_to_periapsis() {
+                let mut mjd = start + time / SECONDS_PER_DAY;
+                while mjd < end {
+                    trajectory.events.push(Event {
+                        kind: EventKind::Periapsis,
+                        body,
+                        mjd,
+                        state: orbit.state_at(mjd)?,
+                    });
+                    match elements.period() {
+                        Some(period) => mjd += period / SECONDS_PER_DAY,
+                        None => break,
+                    }
+                }
+            }
+
+            trajectory.segments.push(Segment {
+                body,
+                orbit,
+                start,
+                end,
+            });
+            if let Some(period) = elements.period() {
+                revolutions += (end - start) * SECONDS_PER_DAY / period;
+            }
+
+            let (kind, next) = match transition {
+                Some(transition) => transition,
+                None => break,
+            };
+            // State relative to the next central body
+            let state = orbit.state_at(end)?;
+            let next_state = match kind {
+                EventKind::SoiExit => state + central.orbit.unwrap().state_at(end)?,
+                EventKind::SoiEntry => state - bodies[next].orbit.unwrap().state_at(end)?,
+                _ => state,
+            };
+            trajectory.events.push(match kind {
+                EventKind::SoiEntry => Event {
+                    kind,
+                    body: next,
+                    mjd: end,
+                    state: next_state,
+                },
+                _ => Event {
+                    kind,
+                    body,
+                    mjd: end,
+                    state,
+                },
+            });
+            if kind == EventKind::Impact {
+                break;
+            }
+            body = next;
+            orbit = KeplerOrbit::new(next_state, bodies[next].mu, end);
+        }
+        Ok(trajectory)
+    }
+}
+
+/// Returns the true anomaly at a radius on the outbound leg of an orbit.
+fn true_anomaly_at(elements: &OrbitalElements, radius: f64) -> Option<f64> {
+    let cos_nu = (elements.semi_latus_rectum / radius - 1.0) / elements.eccentricity;
+    if (-1.0..=1.0).contains(&cos_nu) {
+        Some(cos_nu.acos())
+    } else {
+        None
+    }
+}
+
+/// Returns the time (in s) until the orbit next reaches a radius while
+/// descending.
+fn inbound_time(elements: &OrbitalElements, radius: f64) -> Option<f64> {
+    let nu = true_anomaly_at(elements, radius)?;
+    elements.time_to_true_anomaly(2.0 * PI - nu)
+}
+
+/// Returns the time (in s) until the orbit next reaches a radius while
+/// ascending.
+fn outbound_time(elements: &OrbitalElements, radius: f64) -> Option<f64> {
+    let nu = true_anomaly_at(elements, radius)?;
+    elements.time_to_true_anomaly(nu)
+}
+
+/// Returns the step (in days) to sample a span, from the periods of the
+/// moving objects.
+fn sample_step(start: f64, end: f64, periods: &[Option<f64>]) -> f64 {
+    let span = end - start;
+    let step = periods
+        .iter()
+        .flatten()
+        .map(|period| period / SECONDS_PER_DAY / SAMPLES_PER_ORBIT)
+        .fold(span / SAMPLES_PER_ORBIT, f64::min);
+    step.max(span / MAX_SAMPLES)
+}
+
+/// Returns the date (MJD) at which an orbit enters the sphere of influence
+/// of a body between two dates, if it does.
+fn entry_date(
+    body: &SystemBody,
+    orbit: &KeplerOrbit,
+    start: f64,
+    end: f64,
+) -> Result<Option<f64>, ConvergenceError> {
+    let body_orbit = match &body.orbit {
+        Some(body_orbit) => body_orbit,
+        None => return Ok(None),
+    };
+    let distance = |mjd: f64| -> Result<f64, ConvergenceError> {
+        let offset = orbit.state_at(mjd)? - body_orbit.state_at(mjd)?;
+        Ok(offset.position.norm() - body.soi_radius)
+    };
+
+    let periods = [orbit.elements().period(), body_orbit.elements().period()];
+    let step = sample_step(start, end, &periods);
+    let mut before: Option<(f64, f64)> = None;
+    let mut previous = (start, distance(start)?);
+    while previous.0 < end {
+        let mjd = (previous.0 + step).min(end);
+        let current = (mjd, distance(mjd)?);
+        if previous.1 > 0.0 && current.1 <= 0.0 {
+            return bisect(distance, previous.0, current.0).map(Some);
+        }
+        // Grazing entry between two samples
+        if let Some(before) = before {
+            if before.1 > previous.1 && current.1 > previous.1 && previous.1 > 0.0 {
+                let (closest, value) = golden_section(distance, before.0, current.0)?;
+                if value <= 0.0 {
+                    return bisect(distance, before.0, closest).map(Some);
+                }
+            }
+        }
+        before = Some(previous);
+        previous = current;
+    }
+    Ok(None)
+}
+
+/// Finds the date in `[low, high]` at which a function becomes non-positive,
+/// given that it is positive at `low` and non-positive at `high`.
+fn bisect<F>(f: F, mut low: f64, mut high: f64) -> Result<f64, ConvergenceError>
+where
+    F: Fn(f64) -> Result<f64, ConvergenceError>,
+{
+    while high - low > DATE_TOLERANCE {
+        let middle = (low + high) / 2.0;
+        if f(middle)? > 0.0 {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    Ok(high)
+}
+
+/// Finds the minimum of a unimodal function in `[low, high]` and returns its
+/// date and value.
+fn golden_section<F>(f: F, mut low: f64, mut high: f64) -> Result<(f64, f64), ConvergenceError>
+where
+    F: Fn(f64) -> Result<f64, ConvergenceError>,
+{
+    let ratio = (5.0_f64.sqrt() - 1.0) / 2.0;
+    let mut left = high - ratio * (high - low);
+    let mut right = low + ratio * (high - low);
+    let mut f_left = f(left)?;
+    let mut f_right = f(right)?;
+    while high - low > DATE_TOLERANCE {
+        if f_left < f_right {
+            high = right;
+            right = left;
+            f_right = f_left;
+            left = high - ratio * (high - low);
+            f_left = f(left)?;
+        } else {
+            low = left;
+            left = right;
+            f_left = f_right;
+            right = low + ratio * (high - low);
+            f_right = f(right)?;
+        }
+    }
+    let middle = (low + high) / 2.0;
+    Ok((middle, f(middle)?))
+}
+
+/// Finds the global minimum of a function in `[start, end]` by sampling it
+/// and refining the local minima.
+fn minimum<F>(f: F, start: f64, end: f64, step: f64) -> Result<Option<(f64, f64)>, ConvergenceError>
+where
+    F: Fn(f64) -> Result<f64, ConvergenceError>,
+{
+    if end < start {
+        return Ok(None);
+    }
+    let mut samples = vec![(start, f(start)?)];
+    let mut mjd = start;
+    while mjd < end {
+        mjd = (mjd + step).min(end);
+        samples.push((mjd, f(mjd)?));
+    }
+
+    let mut best = samples
+        .iter()
+        .copied()
+        .fold((start, f64::INFINITY), |best, sample| {
+            if sample.1 < best.1 {
+                sample
+            } else {
+                best
+            }
+        });
+    for window in samples.windows(3) {
+        if window[1].1 <= window[0].1 && window[1].1 <= window[2].1 {
+            let local = golden_section(&f, window[0].0, window[2].0)?;
+            if local.1 < best.1 {
+                best = local;
+            }
+        }
+    }
+    Ok(Some(best))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::MU_EARTH;
+    use nalgebra::Rotation3;
+
+    const MU_SUN: f64 = 1.32712440018e20;
+    const MU_MOON: f64 = 4.9028e12;
+    const AU: f64 = 1.495978707e11;
+    const MOON_DISTANCE: f64 = 384_400e3;
+    const EPOCH: f64 = 51544.0;
+
+    /// Sun, Earth and Moon on circular orbits
+    struct EarthMoon {
+        system: System,
+        earth: usize,
+        moon: usize,
+    }
+
+    impl EarthMoon {
+        fn new() -> Self {
+            let mut system = System::new();
+            let sun = system.add_root("Sun", MU_SUN, 696e6);
+            let earth_orbit = KeplerOrbit::new(circular(MU_SUN, AU), MU_SUN, EPOCH);
+            let earth = system.add_body("Earth", MU_EARTH, 6371e3, sun, earth_orbit);
+            let moon_orbit = KeplerOrbit::new(circular(MU_EARTH, MOON_DISTANCE), MU_EARTH, EPOCH);
+            let moon = system.add_body("Moon", MU_MOON, 1737e3, earth, moon_orbit);
+            Self {
+                system,
+                earth,
+                moon,
+            }
+        }
+
+        fn moon_soi(&self) -> f64 {
+            self.system.body(self.moon).soi_radius
+        }
+
+        fn moon_state(&self, mjd: f64) -> StateVector {
+            let orbit = self.system.body(self.moon).orbit.unwrap();
+            orbit.state_at(mjd).unwrap()
+        }
+
+        /// Returns an orbit around the Earth which would pass at an offset
+        /// from the Moon with a relative velocity one day after its epoch, if
+        /// the Moon had no gravity.
+        fn flyby(&self, offset: Vector3, relative_velocity: Vector3) -> KeplerOrbit {
+            let moon = self.moon_state(EPOCH + 1.0);
+            let state = StateVector::new(moon.position + offset, moon.velocity + relative_velocity);
+            KeplerOrbit::new(state, MU_EARTH, EPOCH + 1.0)
+                .at(EPOCH)
+                .unwrap()
+        }
+
+        fn predict(&self, body: usize, orbit: KeplerOrbit) -> Trajectory {
+            Predictor::new(&self.system)
+                .max_duration(3.0 * SECONDS_PER_DAY)
+                .predict(body, orbit)
+                .unwrap()
+        }
+    }
+
+    fn circular(mu: f64, radius: f64) -> StateVector {
+        StateVector::new(
+            Vector3::new(radius, 0.0, 0.0),
+            Vector3::new(0.0, (mu / radius).sqrt(), 0.0),
+        )
+    }
+
+    fn transitions(trajectory: &Trajectory) -> Vec<(EventKind, usize)> {
+        trajectory
+            .events
+            .iter()
+            .filter(|event| event.kind != EventKind::Periapsis)
+            .map(|event| (event.kind, event.body))
+            .collect()
+    }
+
+    /// Checks that an SOI transition continues the trajectory from the same
+    /// position and velocity.
+    fn assert_continuous(bodies: &EarthMoon, trajectory: &Trajectory, event: &Event) {
+        let (before, after) = match trajectory
+            .segments
+            .windows(2)
+            .find(|segments| segments[1].start == event.mjd)
+        {
+            Some(segments) => (segments[0], segments[1]),
+            None => panic!("no segment starts at the event"),
+        };
+        assert_eq!(before.end, event.mjd);
+        let global = |segment: &Segment| {
+            let state = segment.orbit.state_at(event.mjd).unwrap();
+            if segment.body == bodies.moon {
+                state + bodies.moon_state(event.mjd)
+            } else {
+                state
+            }
+        };
+        let (before, after) = (global(&before), global(&after));
+        assert!((before.position - after.position).norm() < 1e-3);
+        assert!((before.velocity - after.velocity).norm() < 1e-6);
+    }
+
+    #[test]
+    fn enters_moon_soi() {
+        let bodies = EarthMoon::new();
+        let soi = bodies.moon_soi();
+        let orbit = bodies.flyby(
+            Vector3::new(0.0, 0.0, 0.2 * soi),
+            Vector3::new(-1000.0, 0.0, 0.0),
+        );
+        let trajectory = bodies.predict(bodies.earth, orbit);
+
+        let entry = trajectory.events[0];
+        assert_eq!(entry.kind, EventKind::SoiEntry);
+        assert_eq!(entry.body, bodies.moon);
+        assert!(entry.mjd < EPOCH + 1.0);
+        assert!((entry.state.position.norm() - soi).abs() < 10.0);
+        assert!(entry.state.position.dot(&entry.state.velocity) < 0.0);
+        assert_eq!(trajectory.segments[1].body, bodies.moon);
+        assert_continuous(&bodies, &trajectory, &entry);
+
+        // The vessel flies by the Moon and leaves its SOI
+        assert_eq!(
+            transitions(&trajectory),
+            vec![
+                (EventKind::SoiEntry, bodies.moon),
+                (EventKind::SoiExit, bodies.moon)
+            ]
+        );
+    }
+
+    #[test]
+    fn detects_grazing_entry_between_samples() {
+        let bodies = EarthMoon::new();
+        let soi = bodies.moon_soi();
+        // Retrograde flyby on an orbit bound to the Earth, inside the SOI for
+        // about 50 min
+        let moon_velocity = bodies.moon_state(EPOCH + 1.0).velocity;
+        let orbit = bodies.flyby(
+            Vector3::new(0.0, 0.0, 0.999 * soi),
+            -2000.0 * moon_velocity.normalize(),
+        );
+
+        // The SOI is sampled every 1/360 of the shortest period, about 1.7 h.
+        // The prediction starts so that the closest approach is halfway
+        // between two samples.
+        let moon_period = bodies
+            .system
+            .body(bodies.moon)
+            .orbit
+            .unwrap()
+            .elements()
+            .period();
+        let period = orbit.elements().period().unwrap().min(moon_period.unwrap());
+        let step = period / SAMPLES_PER_ORBIT / SECONDS_PER_DAY;
+        let orbit = orbit.at(EPOCH + 1.0 - 14.5 * step).unwrap();
+        let trajectory = Predictor::new(&bodies.system)
+            .predict(bodies.earth, orbit)
+            .unwrap();
+
+        let entry = trajectory.events[0];
+        assert_eq!(entry.kind, EventKind::SoiEntry);
+        assert_eq!(entry.body, bodies.moon);
+        assert!((entry.state.position.norm() - soi).abs() < 10.0);
+        assert_continuous(&bodies, &trajectory, &entry);
+    }
+
+    #[test]
+    fn leaves_moon_soi() {
+        let bodies = EarthMoon::new();
+        let soi = bodies.moon_soi();
+        let escape_speed = (2.0 * MU_MOON / 5000e3).sqrt();
+        let state = StateVector::new(
+            Vector3::new(5000e3, 0.0, 0.0),
+            Vector3::new(0.0, 1.2 * escape_speed, 0.0),
+        );
+        let orbit = KeplerOrbit::new(state, MU_MOON, EPOCH);
+        let trajectory = bodies.predict(bodies.moon, orbit);
+
+        let exit = trajectory
+            .events
+            .iter()
+            .find(|event| event.kind == EventKind::SoiExit)
+            .unwrap();
+        assert_eq!(exit.body, bodies.moon);
+        assert!((exit.state.position.norm() - soi).abs() < 10.0);
+        assert!(exit.state.position.dot(&exit.state.velocity) > 0.0);
+        assert_eq!(trajectory.segments[0].end, exit.mjd);
+        assert_eq!(trajectory.segments[1].body, bodies.earth);
+        assert_continuous(&bodies, &trajectory, exit);
+    }
+
+    #[test]
+    fn counts_periapsis_passages() {
+        let bodies = EarthMoon::new();
+        // Starts at the apoapsis of an orbit between 7000 and 9000 km
+        let apoapsis = 9000e3;
+        let a: f64 = 8000e3;
+        let speed = (MU_EARTH * (2.0 / apoapsis - 1.0 / a)).sqrt();
+        let state = StateVector::new(
+            Vector3::new(apoapsis, 0.0, 0.0),
+            Vector3::new(0.0, speed, 0.0),
+        );
+        let orbit = KeplerOrbit::new(state, MU_EARTH, EPOCH);
+        let period = orbit.elements().period().unwrap() / SECONDS_PER_DAY;
+        let trajectory = Predictor::new(&bodies.system)
+            .orbits(3)
+            .predict(bodies.earth, orbit)
+            .unwrap();
+
+        assert_eq!(trajectory.segments.len(), 1);
+        assert!((trajectory.end().unwrap() - (EPOCH + 3.0 * period)).abs() < 1e-9);
+        assert_eq!(trajectory.events.len(), 3);
+        for (i, event) in trajectory.events.iter().enumerate() {
+            assert_eq!(event.kind, EventKind::Periapsis);
+            assert_eq!(event.body, bodies.earth);
+            let expected = EPOCH + (i as f64 + 0.5) * period;
+            assert!((event.mjd - expected).abs() < 1e-9);
+            assert!((event.state.position.norm() - 7000e3).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn stops_at_impact() {
+        let bodies = EarthMoon::new();
+        // Suborbital arc from 100 km
+        let state = StateVector::new(
+            Vector3::new(6471e3, 0.0, 0.0),
+            Vector3::new(0.0, 5000.0, 0.0),
+        );
+        let orbit = KeplerOrbit::new(state, MU_EARTH, EPOCH);
+        let trajectory = Predictor::new(&bodies.system)
+            .orbits(10)
+            .predict(bodies.earth, orbit)
+            .unwrap();
+
+        let impact = trajectory.impact().unwrap();
+        assert_eq!(impact.body, bodies.earth);
+        assert!((impact.state.position.norm() - 6371e3).abs() < 1e-3);
+        assert!(impact.state.position.dot(&impact.state.velocity) < 0.0);
+        assert_eq!(trajectory.events.last(), Some(impact));
+        assert_eq!(trajectory.segments.len(), 1);
+        assert_eq!(trajectory.end(), Some(impact.mjd));
+    }
+
+    #[test]
+    fn stops_after_max_segments() {
+        let mut bodies = EarthMoon::new();
+        // A light Moon hardly deflects a vessel on a retrograde copy of its
+        // orbit, which crosses its SOI twice per revolution
+        bodies.system.bodies[bodies.moon].mu = 1e10;
+        let speed = (MU_EARTH / MOON_DISTANCE).sqrt();
+        let inclination = 150.0_f64.to_radians();
+        let state = StateVector::new(
+            Vector3::new(MOON_DISTANCE, 0.0, 0.0),
+            Vector3::new(0.0, speed * inclination.cos(), speed * inclination.sin()),
+        );
+        let orbit = KeplerOrbit::new(state, MU_EARTH, EPOCH + 0.1)
+            .at(EPOCH + 5.0)
+            .unwrap();
+        let trajectory = Predictor::new(&bodies.system)
+            .orbits(1000)
+            .max_duration(10.0 * 365.0 * SECONDS_PER_DAY)
+            .predict(bodies.earth, orbit)
+            .unwrap();
+
+        assert_eq!(trajectory.segments.len(), MAX_SEGMENTS);
+        // The transition ending the last segment is still reported
+        assert_eq!(transitions(&trajectory).len(), MAX_SEGMENTS);
+        assert!(trajectory.end().unwrap() < EPOCH + 5.0 + 10.0 * 365.0);
+    }
+
+    #[test]
+    fn closest_approach_to_body() {
+        let bodies = EarthMoon::new();
+        let distance = 2.0 * bodies.moon_soi();
+        let orbit = bodies.flyby(
+            Vector3::new(0.0, 0.0, distance),
+            Vector3::new(-2000.0, 0.0, 0.0),
+        );
+        let trajectory = bodies.predict(bodies.earth, orbit);
+        assert_eq!(transitions(&trajectory), vec![]);
+
+        let approach = trajectory
+            .closest_approach(&bodies.system, &Target::Body(bodies.moon))
+            .unwrap()
+            .unwrap();
+        assert!((approach.mjd - (EPOCH + 1.0)).abs() < 0.01);
+        assert!((approach.distance - distance).abs() < 0.01 * distance);
+        assert!((approach.relative_speed - 2000.0).abs() < 20.0);
+    }
+
+    #[test]
+    fn closest_approach_to_orbit() {
+        let bodies = EarthMoon::new();
+        let radius = 7000e3;
+        let orbit = KeplerOrbit::new(circular(MU_EARTH, radius), MU_EARTH, EPOCH);
+        let trajectory = Predictor::new(&bodies.system)
+            .orbits(2)
+            .predict(bodies.earth, orbit)
+            .unwrap();
+
+        // Coplanar circular orbit 10 km higher and 1° ahead
+        let target_radius = radius + 10e3;
+        let phase = 1.0_f64.to_radians();
+        let target_state = circular(MU_EARTH, target_radius);
+        let rotation = Rotation3::from_axis_angle(&Vector3::z_axis(), phase);
+        let target_orbit = KeplerOrbit::new(
+            StateVector::new(
+                rotation * target_state.position,
+                rotation * target_state.velocity,
+            ),
+            MU_EARTH,
+            EPOCH,
+        );
+        let approach = trajectory
+            .closest_approach(
+                &bodies.system,
+                &Target::Orbit {
+                    body: bodies.earth,
+                    orbit: target_orbit,
+                },
+            )
+            .unwrap()
+            .unwrap();
+
+        let relative_motion =
+            (MU_EARTH / radius.powi(3)).sqrt() - (MU_EARTH / target_radius.powi(3)).sqrt();
+        let expected = EPOCH + phase / relative_motion / SECONDS_PER_DAY;
+        assert!((approach.mjd - expected).abs() < 1e-4);
+        assert!((approach.distance - 10e3).abs() < 1.0);
+        let speed_difference = (MU_EARTH / radius).sqrt() - (MU_EARTH / target_radius).sqrt();
+        assert!((approach.relative_speed - speed_difference).abs() < 0.1);
+    }
+}
@@ -0,0 +1,144 @@
+//! Physical quantities for API values.
+//!
+//! Only available with the `units` feature. The `units()` accessors of
+//! [`ObjectTrait`] and [`Module`] return [`uom`] quantities instead of raw
+//! `f64` values, so that unit mistakes become compile errors.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use orbiter::units::{length::kilometer, Length};
+//! use orbiter::*;
+//!
+//! fn print_size(planet: &Planet) {
+//!     let size: Length = planet.units().size();
+//!     println!("{} km", size.get::<kilometer>());
+//! }
+//! ```
+//!
+//! [`ObjectTrait`]: ../trait.ObjectTrait.html
+//! [`Module`]: ../struct.Module.html
+//! [`uom`]: https://docs.rs/uom
+
+use crate::{AltitudeMode, CelestialBodyTrait, Module, ObjectTrait, Vector3, VesselTrait};
+use uom::si::angle::radian;
+use uom::si::length::meter;
+use uom::si::mass::kilogram;
+use uom::si::time::second;
+use uom::si::velocity::meter_per_second;
+
+pub use uom::si::f64::{Angle, Length, Mass, Time, Velocity};
+pub use uom::si::{angle, length, mass, time, velocity};
+
+/// Vector of lengths, e.g. a position
+pub type LengthVector = nalgebra::Vector3<Length>;
+
+/// Vector of velocities
+pub type VelocityVector = nalgebra::Vector3<Velocity>;
+
+fn length_vector(v: Vector3) -> LengthVector {
+    v.map(Length::new::<meter>)
+}
+
+fn velocity_vector(v: Vector3) -> VelocityVector {
+    v.map(Velocity::new::<meter_per_second>)
+}
+
+/// Accessors of an object returning physical quantities
+///
+/// Returned by [`ObjectTrait::units`].
+///
+/// [`ObjectTrait::units`]: ../trait.ObjectTrait.html#method.units
+pub struct Units<'a, T: ObjectTrait> {
+    object: &'a T,
+}
+
+impl<'a, T: ObjectTrait> Units<'a, T> {
+    pub(crate) fn new(object: &'a T) -> Self {
+        Self { object }
+    }
+
+    /// Returns the size (mean radius) of the object.
+    pub fn size(&self) -> Length {
+        Length::new::<meter>(self.object.size())
+    }
+
+    /// Returns the mass of the object.
+    pub fn mass(&self) -> Mass {
+        Mass::new::<kilogram>(self.object.mass())
+    }
+
+    /// Returns the position of the object in the global reference frame.
+    pub fn global_pos(&self) -> LengthVector {
+        length_vector(self.object.global_pos())
+    }
+
+    /// Returns the velocity of the object in the global reference frame.
+    pub fn global_velocity(&self) -> VelocityVector {
+        velocity_vector(self.object.global_velocity())
+    }
+
+    /// Returns the distance vector from the reference object to the object.
+    pub fn relative_position(&self, reference: &dyn ObjectTrait) -> LengthVector {
+        length_vector(self.object.relative_position(reference))
+    }
+
+    /// Returns the velocity of the object relative to the reference object.
+    pub fn relative_velocity(&self, reference: &dyn ObjectTrait) -> VelocityVector {
+        velocity_vector(self.object.relative_velocity(reference))
+    }
+}
+
+impl<'a, T: VesselTrait> Units<'a, T> {
+    /// Returns the empty mass of the vessel, excluding fuel.
+    pub fn empty_mass(&self) -> Mass {
+        Mass::new::<kilogram>(self.object.empty_mass())
+    }
+
+    /// Returns the altitude of the vessel above its reference planet.
+    pub fn altitude(&self, mode: AltitudeMode) -> Option<Length> {
+        self.object.altitude(mode).map(Length::new::<meter>)
+    }
+}
+
+impl<'a, T: CelestialBodyTrait> Units<'a, T> {
+    /// Returns the sidereal rotation period of the body.
+    pub fn rotation_period(&self) -> Time {
+        Time::new::<second>(self.object.rotation_period())
+    }
+
+    /// Returns the obliquity of the rotation axis of the body.
+    pub fn obliquity(&self) -> Angle {
+        Angle::new::<radian>(self.object.obliquity())
+    }
+
+    /// Returns the period of the orbit of the body around its parent.
+    pub fn orbital_period(&self) -> Option<Time> {
+        self.object.orbital_period().map(Time::new::<second>)
+    }
+}
+
+/// Accessors of a module returning physical quantities
+///
+/// Returned by [`Module::units`].
+///
+/// [`Module::units`]: ../struct.Module.html#method.units
+pub struct ModuleUnits<'a> {
+    module: &'a Module,
+}
+
+impl<'a> ModuleUnits<'a> {
+    pub(crate) fn new(module: &'a Module) -> Self {
+        Self { module }
+    }
+
+    /// Returns the simulation time since the start of the session.
+    pub fn sim_time(&self) -> Time {
+        Time::new::<second>(self.module.sim_time())
+    }
+
+    /// Returns the length of the last simulation time step.
+    pub fn sim_step(&self) -> Time {
+        Time::new::<second>(self.module.sim_step())
+    }
+}
@@ -1,5 +1,13 @@
-use crate::ObjectTrait;
-use crate::OBJHANDLE;
+use crate::win::minwindef::{DWORD, UINT};
+use crate::{
+    oapic_VECTOR3, AltitudeMode, AttitudeMode, InstanceHandle, Module, ModuleCallbacks, NavSignal,
+    NavTransmitter, Object, ObjectTrait, ThrusterGroup, Vector3, VesselAtmosphere, NAVHANDLE,
+    OBJHANDLE,
+};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int};
 
 #[doc(hidden)]
 pub enum _VESSEL {}
@@ -17,6 +25,27 @@ pub trait VesselTrait: ObjectTrait {
         Vessel::from_obj(handle)
     }
 
+    /// Returns the class name of the vessel, e.g. `"DeltaGlider"`.
+    ///
+    /// Returns `None` if the vessel was created without a class.
+    fn class_name(&self) -> Option<String> {
+        let name = unsafe { oapic_VESSEL_GetClassName(self.vessel_handle()) };
+        if name.is_null() {
+            return None;
+        }
+        Some(
+            unsafe { CStr::from_ptr(name) }
+                .to_string_lossy()
+                .to_string(),
+        )
+    }
+
+    /// Returns the celestial body with the dominant gravitational influence
+    /// on the vessel.
+    fn gravity_ref(&self) -> Option<Object> {
+        Object::from(unsafe { oapic_VESSEL_GetGravityRef(self.vessel_handle()) })
+    }
+
     /// Returns the empty mass (in kg) of the vessel, excluding fuel.
     ///
     /// Do not rely on a constant empty mass. Structural changes (e.g. discarding a
@@ -33,6 +62,253 @@ pub trait VesselTrait: ObjectTrait {
         unsafe { oapic_oapiSetEmptyMass(self.handle(), mass) };
     }
 
+    /// Returns the altitude (in m) of the vessel above its reference planet.
+    ///
+    /// Returns `None` if the altitude is not available.
+    fn altitude(&self, mode: AltitudeMode) -> Option<f64> {
+        let mut altitude = 0.0;
+        if unsafe { oapic_oapiGetAltitude(self.handle(), mode.to_raw(), &mut altitude) } {
+            Some(altitude)
+        } else {
+            None
+        }
+    }
+
+    /// Converts a position in the local frame of the vessel to the global frame.
+    ///
+    /// Both frames use Orbiter's left-handed convention.
+    fn local_to_global(&self, local: Vector3) -> Vector3 {
+        let local = oapic_VECTOR3::from(local);
+        let mut global = oapic_VECTOR3::new();
+        unsafe { oapic_VESSEL_Local2Global(self.vessel_handle(), &local, &mut global) };
+        global.into()
+    }
+
+    /// Converts a position in the global frame to the local frame of the vessel.
+    ///
+    /// Both frames use Orbiter's left-handed convention.
+    fn global_to_local(&self, global: Vector3) -> Vector3 {
+        let global = oapic_VECTOR3::from(global);
+        let mut local = oapic_VECTOR3::new();
+        unsafe { oapic_VESSEL_Global2Local(self.vessel_handle(), &global, &mut local) };
+        local.into()
+    }
+
+    /// Rotates a direction from the local frame of the vessel to the global frame.
+    ///
+    /// Unlike [`local_to_global`], the position of the vessel is not added.
+    ///
+    /// [`local_to_global`]: #method.local_to_global
+    fn local_to_global_direction(&self, local: Vector3) -> Vector3 {
+        let local = oapic_VECTOR3::from(local);
+        let mut global = oapic_VECTOR3::new();
+        unsafe { oapic_VESSEL_GlobalRot(self.vessel_handle(), &local, &mut global) };
+        global.into()
+    }
+
+    /// Rotates a direction from the global frame to the local frame of the vessel.
+    ///
+    /// Unlike [`global_to_local`], the position of the vessel is not subtracted.
+    ///
+    /// [`global_to_local`]: #method.global_to_local
+    fn global_to_local_direction(&self, global: Vector3) -> Vector3 {
+        self.rotation_matrix().transpose() * global
+    }
+
+    /// Returns the angular velocity (in rad/s) of the vessel around its
+    /// local axes.
+    ///
+    /// The vector uses Orbiter's left-handed convention, convert it with
+    /// [`pseudovector_to_right_handed`] for right-handed math.
+    ///
+    /// [`pseudovector_to_right_handed`]: ./fn.pseudovector_to_right_handed.html
+    fn angular_velocity(&self) -> Vector3 {
+        let mut avel = oapic_VECTOR3::new();
+        unsafe { oapic_VESSEL_GetAngularVel(self.vessel_handle(), &mut avel) };
+        avel.into()
+    }
+
+    /// Returns the atmospheric conditions around the vessel.
+    ///
+    /// Returns `None` if the vessel is outside of any atmosphere.
+    fn atmosphere(&self) -> Option<VesselAtmosphere> {
+        VesselAtmosphere::from_vessel(self.handle())
+    }
+
+    /// Creates navigation radio receivers for the vessel.
+    ///
+    /// Existing receivers are replaced. Receivers are tuned to channel 0 by default.
+    fn init_nav_radios(&mut self, count: usize) {
+        unsafe { oapic_VESSEL_InitNavRadios(self.vessel_handle(), count as DWORD) };
+    }
+
+    /// Returns the number of navigation radio receivers of the vessel.
+    fn nav_count(&self) -> usize {
+        unsafe { oapic_VESSEL_GetNavCount(self.vessel_handle()) as usize }
+    }
+
+    /// Tunes a navigation radio receiver to a channel (0 to 639).
+    ///
+    /// Returns `false` if the receiver or the channel does not exist.
+    fn set_nav_channel(&mut self, receiver: usize, channel: u32) -> bool {
+        unsafe { oapic_VESSEL_SetNavRecv(self.vessel_handle(), receiver as DWORD, channel) }
+    }
+
+    /// Returns the channel of a navigation radio receiver.
+    fn nav_channel(&self, receiver: usize) -> Option<u32> {
+        if receiver < self.nav_count() {
+            Some(unsafe { oapic_VESSEL_GetNavRecv(self.vessel_handle(), receiver as DWORD) })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the frequency (in MHz) of a navigation radio receiver.
+    fn nav_frequency(&self, receiver: usize) -> Option<f32> {
+        if receiver < self.nav_count() {
+            Some(unsafe { oapic_VESSEL_GetNavRecvFreq(self.vessel_handle(), receiver as DWORD) })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the transmitter received by a navigation radio receiver, if any.
+    fn nav_source(&self, receiver: usize) -> Option<NavTransmitter> {
+        if receiver >= self.nav_count() {
+            return None;
+        }
+        NavTransmitter::from(unsafe {
+            oapic_VESSEL_GetNavSource(self.vessel_handle(), receiver as DWORD)
+        })
+    }
+
+    /// Returns the signal received by a navigation radio receiver, if any.
+    fn nav_signal(&self, receiver: usize) -> Option<NavSignal> {
+        let transmitter = self.nav_source(receiver)?;
+        Some(NavSignal::new(transmitter, self.global_pos()))
+    }
+
+    /// Enables or disables the transponder of the vessel.
+    ///
+    /// Returns `false` if the state of the transponder did not change.
+    fn enable_transponder(&mut self, enable: bool) -> bool {
+        unsafe { oapic_VESSEL_EnableTransponder(self.vessel_handle(), enable) }
+    }
+
+    /// Returns the transponder of the vessel, or `None` if it is disabled.
+    fn transponder(&self) -> Option<NavTransmitter> {
+        NavTransmitter::from(unsafe { oapic_VESSEL_GetTransponder(self.vessel_handle()) })
+    }
+
+    /// Sets the channel (0 to 639) of the transponder.
+    ///
+    /// Returns `false` if the transponder is disabled.
+    fn set_transponder_channel(&mut self, channel: u32) -> bool {
+        unsafe { oapic_VESSEL_SetTransponderChannel(self.vessel_handle(), channel) }
+    }
+
+    /// Returns the number of docking ports of the vessel.
+    fn dock_count(&self) -> usize {
+        unsafe { oapic_VESSEL_DockCount(self.vessel_handle()) as usize }
+    }
+
+    /// Enables or disables the IDS transmitter of a docking port.
+    ///
+    /// Returns `false` if the docking port does not exist or if the state of
+    /// the transmitter did not change.
+    fn enable_ids(&mut self, dock: usize, enable: bool) -> bool {
+        match dock_handle(self.vessel_handle(), dock) {
+            Some(dock) => unsafe { oapic_VESSEL_EnableIDS(self.vessel_handle(), dock, enable) },
+            None => false,
+        }
+    }
+
+    /// Returns the IDS transmitter of a docking port, or `None` if it is disabled.
+    fn ids(&self, dock: usize) -> Option<NavTransmitter> {
+        let dock = dock_handle(self.vessel_handle(), dock)?;
+        NavTransmitter::from(unsafe { oapic_VESSEL_GetIDS(self.vessel_handle(), dock) })
+    }
+
+    /// Sets the channel (0 to 639) of the IDS transmitter of a docking port.
+    ///
+    /// Returns `false` if the docking port does not exist.
+    fn set_ids_channel(&mut self, dock: usize, channel: u32) -> bool {
+        match dock_handle(self.vessel_handle(), dock) {
+            Some(dock) => {
+                unsafe { oapic_VESSEL_SetIDSChannel(self.vessel_handle(), dock, channel) };
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the thrust level of a thruster group, between 0 and 1.
+    fn thruster_group_level(&self, group: ThrusterGroup) -> f64 {
+        unsafe { oapic_VESSEL_GetThrusterGroupLevel(self.vessel_handle(), group.to_raw()) }
+    }
+
+    /// Sets the thrust level of all the thrusters of a group, between 0 and 1.
+    fn set_thruster_group_level(&mut self, group: ThrusterGroup, level: f64) {
+        unsafe { oapic_VESSEL_SetThrusterGroupLevel(self.vessel_handle(), group.to_raw(), level) };
+    }
+
+    /// Returns the combined thrust (in N) of a thruster group at full level
+    /// in vacuum, as a vector in the local frame of the vessel.
+    ///
+    /// The vector is null if the group has no thrusters.
+    fn thruster_group_max_thrust(&self, group: ThrusterGroup) -> Vector3 {
+        let vessel = self.vessel_handle();
+        group_thrusters(vessel, group)
+            .map(|thruster| {
+                let mut dir = oapic_VECTOR3::new();
+                unsafe { oapic_VESSEL_GetThrusterDir(vessel, thruster, &mut dir) };
+                Vector3::from(dir) * unsafe { oapic_VESSEL_GetThrusterMax0(vessel, thruster) }
+            })
+            .sum()
+    }
+
+    /// Returns the combined specific impulse of a thruster group in vacuum,
+    /// as an effective exhaust velocity in m/s.
+    ///
+    /// Returns `None` if the group has no thrusters.
+    fn thruster_group_isp(&self, group: ThrusterGroup) -> Option<f64> {
+        let vessel = self.vessel_handle();
+        let (thrust, flow) =
+            group_thrusters(vessel, group).fold((0.0, 0.0), |(thrust, flow), thruster| {
+                let max = unsafe { oapic_VESSEL_GetThrusterMax0(vessel, thruster) };
+                let isp = unsafe { oapic_VESSEL_GetThrusterIsp0(vessel, thruster) };
+                (thrust + max, flow + max / isp)
+            });
+        if flow > 0.0 {
+            Some(thrust / flow)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the mode of the attitude thrusters, or `None` if Orbiter
+    /// returns an unknown mode.
+    fn attitude_mode(&self) -> Option<AttitudeMode> {
+        AttitudeMode::from_raw(unsafe { oapic_VESSEL_GetAttitudeMode(self.vessel_handle()) })
+    }
+
+    /// Sets the mode of the attitude thrusters.
+    ///
+    /// Returns `false` if the mode did not change.
+    fn set_attitude_mode(&mut self, mode: AttitudeMode) -> bool {
+        unsafe { oapic_VESSEL_SetAttitudeMode(self.vessel_handle(), mode.to_raw()) }
+    }
+
+    /// Sets the levels of the attitude thrusters around the axes of the
+    /// vessel, each between -1 and 1.
+    ///
+    /// Like angular velocities, the levels use Orbiter's left-handed
+    /// convention.
+    fn set_attitude_rot_level(&mut self, level: Vector3) {
+        let level = oapic_VECTOR3::from(level);
+        unsafe { oapic_VESSEL_SetAttitudeRotLevel(self.vessel_handle(), &level) };
+    }
+
     /// Returns current fuel mass (in kg) of the first propellant resource of a vessel.
     #[deprecated]
     fn fuel_mass(&self) -> f64 {
@@ -46,18 +322,74 @@ pub trait VesselTrait: ObjectTrait {
     }
 }
 
+enum _DOCKHANDLE {}
+#[allow(clippy::upper_case_acronyms)]
+type DOCKHANDLE = *mut _DOCKHANDLE;
+
+fn dock_handle(vessel: VESSEL, index: usize) -> Option<DOCKHANDLE> {
+    if index >= unsafe { oapic_VESSEL_DockCount(vessel) as usize } {
+        return None;
+    }
+    let handle = unsafe { oapic_VESSEL_GetDockHandle(vessel, index as UINT) };
+    if handle.is_null() {
+        None
+    } else {
+        Some(handle)
+    }
+}
+
+enum _THRUSTERHANDLE {}
+#[allow(clippy::upper_case_acronyms)]
+type THRUSTERHANDLE = *mut _THRUSTERHANDLE;
+
+fn group_thrusters(vessel: VESSEL, group: ThrusterGroup) -> impl Iterator<Item = THRUSTERHANDLE> {
+    let count = unsafe { oapic_VESSEL_GetGroupThrusterCount(vessel, group.to_raw()) };
+    (0..count)
+        .map(move |index| unsafe { oapic_VESSEL_GetGroupThruster(vessel, group.to_raw(), index) })
+        .filter(|thruster| !thruster.is_null())
+}
+
 impl<T: VesselTrait> ObjectTrait for T {
     fn handle(&self) -> OBJHANDLE {
         unsafe { oapic_VESSEL_GetHandle(self.vessel_handle()) }
     }
 }
 
+/// A vessel of the simulation.
+///
+/// A `Vessel` is only guaranteed to be valid during the callback that provided
+/// it. Use [`is_valid`] or [`check`] before using a vessel stored across
+/// frames, or store a [`WeakVessel`] instead.
+///
+/// # Panics
+///
+/// The methods of [`VesselTrait`] panic if the vessel was deleted, instead of
+/// accessing the freed vessel. The panic is caught before reaching Orbiter:
+/// the callback or plugin function using the vessel is aborted and the error
+/// is written to the Orbiter log. Call [`check`] first to handle the error
+/// instead.
+///
+/// Vessels are compared by identity, so they can be used as map keys.
+///
+/// [`is_valid`]: #method.is_valid
+/// [`check`]: #method.check
+/// [`WeakVessel`]: ./struct.WeakVessel.html
+/// [`VesselTrait`]: ./trait.VesselTrait.html
 pub struct Vessel {
     handle: VESSEL,
+    object: OBJHANDLE,
+    generation: u64,
+    /// Whether the vessel is checked before each use. Interfaces of the vessel
+    /// class of the plugin are not, as they are not in the simulation yet when
+    /// `ovcInit` is called.
+    checked: bool,
 }
 
 impl VesselTrait for Vessel {
     fn vessel_handle(&self) -> VESSEL {
+        if self.checked && !self.is_valid() {
+            panic!("{}", StaleHandleError);
+        }
         self.handle
     }
 }
@@ -70,12 +402,95 @@ impl Vessel {
 
         let handle = unsafe { oapic_oapiGetVesselInterface(obj) };
         if !handle.is_null() {
-            Some(Vessel { handle })
+            Some(Vessel {
+                handle,
+                object: obj,
+                generation: generation(obj),
+                checked: true,
+            })
         } else {
             None
         }
     }
 
+    /// Creates the interface of a vessel of the class defined by the plugin,
+    /// for `ovcInit`.
+    ///
+    /// The vessel uses the default behavior of Orbiter, configured by the
+    /// class file of the vessel and by the `#[vessel_init]` function.
+    ///
+    /// # Safety
+    ///
+    /// The handle must be the one given by Orbiter to `ovcInit`.
+    #[doc(hidden)]
+    pub unsafe fn create_instance(obj: OBJHANDLE, flight_model: c_int) -> Vessel {
+        Vessel {
+            handle: oapic_VESSEL_Create(obj, flight_model),
+            object: obj,
+            generation: generation(obj),
+            checked: false,
+        }
+    }
+
+    /// Wraps an interface created by [`create_instance`], for `ovcExit`.
+    ///
+    /// # Safety
+    ///
+    /// The interface must have been returned by `ovcInit` and not destroyed.
+    ///
+    /// [`create_instance`]: #method.create_instance
+    #[doc(hidden)]
+    pub unsafe fn from_instance(handle: VESSEL) -> Vessel {
+        let object = oapic_VESSEL_GetHandle(handle);
+        Vessel {
+            handle,
+            object,
+            generation: generation(object),
+            checked: false,
+        }
+    }
+
+    /// Destroys an interface created by [`create_instance`], for `ovcExit`.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`from_instance`]. The interface cannot be used afterwards.
+    ///
+    /// [`create_instance`]: #method.create_instance
+    /// [`from_instance`]: #method.from_instance
+    #[doc(hidden)]
+    pub unsafe fn destroy_instance(self) {
+        oapic_VESSEL_Destroy(self.handle);
+    }
+
+    /// Checks if the vessel still exists in the simulation.
+    ///
+    /// A vessel is invalid once it was deleted, even if Orbiter reused its
+    /// handle for a new vessel, and once the simulation session ended. It is
+    /// still valid in [`ModuleCallbacks::on_delete_vessel`].
+    ///
+    /// [`ModuleCallbacks::on_delete_vessel`]: ./trait.ModuleCallbacks.html#method.on_delete_vessel
+    pub fn is_valid(&self) -> bool {
+        generation(self.object) == self.generation && unsafe { oapic_oapiIsVessel(self.object) }
+    }
+
+    /// Returns an error if the vessel was deleted.
+    pub fn check(&self) -> Result<&Self, StaleHandleError> {
+        if self.is_valid() {
+            Ok(self)
+        } else {
+            Err(StaleHandleError)
+        }
+    }
+
+    /// Creates a weak reference to the vessel that can be kept across frames.
+    pub fn downgrade(&self) -> WeakVessel {
+        WeakVessel {
+            object: self.object,
+            generation: self.generation,
+        }
+    }
+
     /// Returns the current focus vessel.
     ///
     /// The focus object is the user-controlled vessel which receives keyboard
@@ -88,15 +503,218 @@ impl Vessel {
     }
 }
 
-#[link(name = "orbiter_c")]
+impl PartialEq for Vessel {
+    fn eq(&self, other: &Self) -> bool {
+        self.object == other.object && self.generation == other.generation
+    }
+}
+
+impl Eq for Vessel {}
+
+impl std::hash::Hash for Vessel {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.object.hash(state);
+        self.generation.hash(state);
+    }
+}
+
+/// Weak reference to a vessel.
+///
+/// Unlike [`Vessel`], a weak reference can safely be kept across frames. It
+/// must be upgraded before using the vessel, which fails if the vessel was
+/// deleted in the meantime.
+///
+/// [`Vessel`]: ./struct.Vessel.html
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct WeakVessel {
+    object: OBJHANDLE,
+    generation: u64,
+}
+
+impl WeakVessel {
+    /// Returns the vessel, or an error if it was deleted.
+    pub fn upgrade(&self) -> Result<Vessel, StaleHandleError> {
+        if !self.is_valid() {
+            return Err(StaleHandleError);
+        }
+
+        let handle = unsafe { oapic_oapiGetVesselInterface(self.object) };
+        if handle.is_null() {
+            return Err(StaleHandleError);
+        }
+
+        Ok(Vessel {
+            handle,
+            object: self.object,
+            generation: self.generation,
+            checked: true,
+        })
+    }
+
+    /// Checks if the vessel still exists in the simulation.
+    ///
+    /// See [`Vessel::is_valid`] for the limitations of this check.
+    ///
+    /// [`Vessel::is_valid`]: ./struct.Vessel.html#method.is_valid
+    pub fn is_valid(&self) -> bool {
+        generation(self.object) == self.generation && unsafe { oapic_oapiIsVessel(self.object) }
+    }
+}
+
+/// Error returned when using a vessel that was deleted.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct StaleHandleError;
+
+impl std::fmt::Display for StaleHandleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("the vessel was deleted")
+    }
+}
+
+impl std::error::Error for StaleHandleError {}
+
+thread_local! {
+    static DELETIONS: RefCell<Deletions> = RefCell::new(Deletions::default());
+}
+
+/// Deletions seen in the current simulation session, to detect handles reused
+/// by Orbiter
+#[derive(Default)]
+struct Deletions {
+    session: u64,
+    /// Number of deletions of each handle
+    counts: HashMap<usize, u64>,
+    /// Handles of the vessels being deleted. Orbiter notifies the modules
+    /// before the deletion, so the vessel is still valid until it is gone.
+    pending: Vec<usize>,
+}
+
+impl Deletions {
+    fn generation(&mut self, obj: usize) -> u64 {
+        self.resolve(|pending| pending == obj);
+        (self.session << 32) | self.counts.get(&obj).copied().unwrap_or(0)
+    }
+
+    /// Counts the pending deletions accepted by `filter` whose vessel is gone.
+    fn resolve(&mut self, filter: impl Fn(usize) -> bool) {
+        let counts = &mut self.counts;
+        self.pending.retain(|&obj| {
+            let deleted = filter(obj) && !unsafe { oapic_oapiIsVessel(obj as OBJHANDLE) };
+            if deleted {
+                *counts.entry(obj).or_insert(0) += 1;
+            }
+            !deleted
+        });
+    }
+}
+
+fn generation(obj: OBJHANDLE) -> u64 {
+    DELETIONS.with(|deletions| deletions.borrow_mut().generation(obj as usize))
+}
+
+/// Module tracking the deletions of vessels, registered once per DLL before
+/// the modules of the user so that it sees the deletions first
+struct DeletionTracker;
+
+impl ModuleCallbacks for DeletionTracker {
+    fn on_pre_step(&mut self, _module: &mut Module, _simt: f64, _simdt: f64, _mjd: f64) {
+        DELETIONS.with(|deletions| deletions.borrow_mut().resolve(|_| true));
+    }
+
+    fn on_simulation_end(&mut self, _module: &mut Module) {
+        DELETIONS.with(|deletions| {
+            let mut deletions = deletions.borrow_mut();
+            deletions.counts.clear();
+            deletions.pending.clear();
+            deletions.session += 1;
+        });
+    }
+
+    fn on_new_vessel(&mut self, _module: &mut Module, vessel: Vessel) {
+        // A new vessel reusing the handle of a deleted one
+        let obj = vessel.object as usize;
+        DELETIONS.with(|deletions| {
+            let mut deletions = deletions.borrow_mut();
+            if let Some(index) = deletions.pending.iter().position(|&pending| pending == obj) {
+                deletions.pending.remove(index);
+                *deletions.counts.entry(obj).or_insert(0) += 1;
+            }
+        });
+    }
+
+    fn on_delete_vessel(&mut self, _module: &mut Module, vessel: Vessel) {
+        DELETIONS.with(|deletions| deletions.borrow_mut().pending.push(vessel.object as usize));
+    }
+}
+
+pub(crate) fn track_deletions(instance: &mut InstanceHandle) {
+    thread_local! {
+        static REGISTERED: Cell<bool> = const { Cell::new(false) };
+    }
+    if !REGISTERED.with(|registered| registered.replace(true)) {
+        instance.register_module(DeletionTracker);
+    }
+}
+
+#[cfg_attr(windows, link(name = "orbiter_c"))]
 extern "C" {
     fn oapic_oapiGetVesselInterface(obj: OBJHANDLE) -> VESSEL;
+    fn oapic_VESSEL_Create(obj: OBJHANDLE, flight_model: c_int) -> VESSEL;
+    fn oapic_VESSEL_Destroy(vessel: VESSEL);
+    fn oapic_oapiIsVessel(obj: OBJHANDLE) -> bool;
     fn oapic_oapiGetFocusObject() -> OBJHANDLE;
     fn oapic_oapiSetFocusObject(vessel: OBJHANDLE) -> OBJHANDLE;
     fn oapic_oapiGetEmptyMass(vessel: OBJHANDLE) -> f64;
     fn oapic_oapiSetEmptyMass(vessel: OBJHANDLE, mass: f64);
+    fn oapic_oapiGetAltitude(vessel: OBJHANDLE, mode: c_int, altitude: *mut f64) -> bool;
     fn oapic_oapiGetFuelMass(vessel: OBJHANDLE) -> f64;
     fn oapic_oapiGetMaxFuelMass(vessel: OBJHANDLE) -> f64;
 
     fn oapic_VESSEL_GetHandle(vessel: VESSEL) -> OBJHANDLE;
+    fn oapic_VESSEL_GetClassName(vessel: VESSEL) -> *const c_char;
+    fn oapic_VESSEL_GetGravityRef(vessel: VESSEL) -> OBJHANDLE;
+    fn oapic_VESSEL_Local2Global(
+        vessel: VESSEL,
+        local: *const oapic_VECTOR3,
+        global: *mut oapic_VECTOR3,
+    );
+    fn oapic_VESSEL_Global2Local(
+        vessel: VESSEL,
+        global: *const oapic_VECTOR3,
+        local: *mut oapic_VECTOR3,
+    );
+    fn oapic_VESSEL_GlobalRot(
+        vessel: VESSEL,
+        local: *const oapic_VECTOR3,
+        global: *mut oapic_VECTOR3,
+    );
+    fn oapic_VESSEL_GetAngularVel(vessel: VESSEL, avel: *mut oapic_VECTOR3);
+    fn oapic_VESSEL_InitNavRadios(vessel: VESSEL, count: DWORD);
+    fn oapic_VESSEL_GetNavCount(vessel: VESSEL) -> DWORD;
+    fn oapic_VESSEL_SetNavRecv(vessel: VESSEL, n: DWORD, channel: DWORD) -> bool;
+    fn oapic_VESSEL_GetNavRecv(vessel: VESSEL, n: DWORD) -> DWORD;
+    fn oapic_VESSEL_GetNavRecvFreq(vessel: VESSEL, n: DWORD) -> f32;
+    fn oapic_VESSEL_GetNavSource(vessel: VESSEL, n: DWORD) -> NAVHANDLE;
+    fn oapic_VESSEL_EnableTransponder(vessel: VESSEL, enable: bool) -> bool;
+    fn oapic_VESSEL_GetTransponder(vessel: VESSEL) -> NAVHANDLE;
+    fn oapic_VESSEL_SetTransponderChannel(vessel: VESSEL, channel: DWORD) -> bool;
+    fn oapic_VESSEL_DockCount(vessel: VESSEL) -> UINT;
+    fn oapic_VESSEL_GetDockHandle(vessel: VESSEL, n: UINT) -> DOCKHANDLE;
+    fn oapic_VESSEL_EnableIDS(vessel: VESSEL, dock: DOCKHANDLE, enable: bool) -> bool;
+    fn oapic_VESSEL_GetIDS(vessel: VESSEL, dock: DOCKHANDLE) -> NAVHANDLE;
+    fn oapic_VESSEL_SetIDSChannel(vessel: VESSEL, dock: DOCKHANDLE, channel: DWORD);
+    fn oapic_VESSEL_GetThrusterGroupLevel(vessel: VESSEL, group: c_int) -> f64;
+    fn oapic_VESSEL_SetThrusterGroupLevel(vessel: VESSEL, group: c_int, level: f64);
+    fn oapic_VESSEL_GetGroupThrusterCount(vessel: VESSEL, group: c_int) -> DWORD;
+    fn oapic_VESSEL_GetGroupThruster(vessel: VESSEL, group: c_int, n: DWORD) -> THRUSTERHANDLE;
+    fn oapic_VESSEL_GetThrusterMax0(vessel: VESSEL, thruster: THRUSTERHANDLE) -> f64;
+    fn oapic_VESSEL_GetThrusterIsp0(vessel: VESSEL, thruster: THRUSTERHANDLE) -> f64;
+    fn oapic_VESSEL_GetThrusterDir(
+        vessel: VESSEL,
+        thruster: THRUSTERHANDLE,
+        dir: *mut oapic_VECTOR3,
+    );
+    fn oapic_VESSEL_GetAttitudeMode(vessel: VESSEL) -> c_int;
+    fn oapic_VESSEL_SetAttitudeMode(vessel: VESSEL, mode: c_int) -> bool;
+    fn oapic_VESSEL_SetAttitudeRotLevel(vessel: VESSEL, level: *const oapic_VECTOR3);
 }
@@ -0,0 +1,62 @@
+//! Windows API definitions used by the bindings.
+//!
+//! Orbiter only runs on Windows, but the pure Rust parts of the crate are also
+//! built on other hosts to run their tests. `winapi` is empty there, so this
+//! module provides the few definitions the bindings need.
+
+#[cfg(windows)]
+pub use winapi::{shared::minwindef, um::winuser};
+
+#[cfg(not(windows))]
+#[allow(non_camel_case_types, non_snake_case, clippy::upper_case_acronyms)]
+pub mod minwindef {
+    pub type DWORD = u32;
+    pub type UINT = u32;
+    pub type WORD = u16;
+    pub type WPARAM = usize;
+    pub enum HINSTANCE__ {}
+    pub type HINSTANCE = *mut HINSTANCE__;
+}
+
+#[cfg(not(windows))]
+#[allow(non_snake_case)]
+pub mod winuser {
+    use super::minwindef::{UINT, WORD, WPARAM};
+
+    pub const WM_MOUSEMOVE: UINT = 0x0200;
+    pub const WM_LBUTTONDOWN: UINT = 0x0201;
+    pub const WM_LBUTTONUP: UINT = 0x0202;
+    pub const WM_LBUTTONDBLCLK: UINT = 0x0203;
+    pub const WM_RBUTTONDOWN: UINT = 0x0204;
+    pub const WM_RBUTTONUP: UINT = 0x0205;
+    pub const WM_RBUTTONDBLCLK: UINT = 0x0206;
+    pub const WM_MBUTTONDOWN: UINT = 0x0207;
+    pub const WM_MBUTTONUP: UINT = 0x0208;
+    pub const WM_MBUTTONDBLCLK: UINT = 0x0209;
+    pub const WM_MOUSEWHEEL: UINT = 0x020A;
+    pub const WM_XBUTTONDOWN: UINT = 0x020B;
+    pub const WM_XBUTTONUP: UINT = 0x020C;
+    pub const WM_XBUTTONDBLCLK: UINT = 0x020D;
+    pub const WM_MOUSEHWHEEL: UINT = 0x020E;
+    pub const MK_LBUTTON: WPARAM = 0x0001;
+    pub const MK_RBUTTON: WPARAM = 0x0002;
+    pub const MK_SHIFT: WPARAM = 0x0004;
+    pub const MK_CONTROL: WPARAM = 0x0008;
+    pub const MK_MBUTTON: WPARAM = 0x0010;
+    pub const MK_XBUTTON1: WPARAM = 0x0020;
+    pub const MK_XBUTTON2: WPARAM = 0x0040;
+    pub const XBUTTON1: WORD = 0x0001;
+    pub const XBUTTON2: WORD = 0x0002;
+
+    pub fn GET_WHEEL_DELTA_WPARAM(wParam: WPARAM) -> i16 {
+        (wParam >> 16) as u16 as i16
+    }
+
+    pub fn GET_KEYSTATE_WPARAM(wParam: WPARAM) -> WORD {
+        wParam as WORD
+    }
+
+    pub fn GET_XBUTTON_WPARAM(wParam: WPARAM) -> WORD {
+        (wParam >> 16) as WORD
+    }
+}
@@ -0,0 +1,4 @@
+#[orbiter::plugin(vessel)]
+mod plugin {}
+
+fn main() {}
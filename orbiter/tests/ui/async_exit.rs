@@ -0,0 +1,7 @@
+#[orbiter::plugin]
+mod plugin {
+    #[exit]
+    async fn exit(_instance: &mut orbiter::InstanceHandle) {}
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+#[orbiter::plugin]
+mod plugin {
+    use orbiter::InstanceHandle;
+
+    #[init]
+    fn init(_instance: &mut InstanceHandle) {}
+
+    #[init]
+    fn init_again(_instance: &mut InstanceHandle) {}
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+#[orbiter::plugin]
+mod plugin {
+    #[init]
+    fn init() {}
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+#[orbiter::plugin]
+mod plugin {
+    #[init]
+    fn init(_instance: &mut orbiter::InstanceHandle) -> bool {
+        true
+    }
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+#[orbiter::plugin]
+mod plugin {
+    #[init(early)]
+    fn init(_instance: &mut orbiter::InstanceHandle) {}
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+#[orbiter::plugin]
+mod plugin {
+    #[module]
+    fn module() {}
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+#[orbiter::plugin]
+mod plugin {
+    #[init]
+    #[exit]
+    fn init(_instance: &mut orbiter::InstanceHandle) {}
+}
+
+fn main() {}
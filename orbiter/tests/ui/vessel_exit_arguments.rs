@@ -0,0 +1,12 @@
+#[orbiter::plugin]
+mod plugin {
+    use orbiter::Vessel;
+
+    #[vessel_init]
+    fn vessel_init(_vessel: &mut Vessel, _flight_model: i32) {}
+
+    #[vessel_exit]
+    fn vessel_exit() {}
+}
+
+fn main() {}
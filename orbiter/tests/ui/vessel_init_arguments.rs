@@ -0,0 +1,12 @@
+#[orbiter::plugin]
+mod plugin {
+    use orbiter::Vessel;
+
+    #[vessel_init]
+    fn vessel_init(_vessel: &mut Vessel) {}
+
+    #[vessel_exit]
+    fn vessel_exit(_vessel: &mut Vessel) {}
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+#[orbiter::plugin]
+mod plugin {
+    use orbiter::{OBJHANDLE, VESSEL};
+
+    #[vessel_init]
+    fn vessel_init(_vessel: OBJHANDLE, _flight_model: i32) -> VESSEL {
+        std::ptr::null_mut()
+    }
+
+    #[vessel_exit]
+    fn vessel_exit(_vessel: VESSEL) {}
+}
+
+fn main() {}
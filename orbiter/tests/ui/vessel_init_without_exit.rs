@@ -0,0 +1,7 @@
+#[orbiter::plugin]
+mod plugin {
+    #[vessel_init]
+    fn vessel_init(_vessel: &mut orbiter::Vessel, _flight_model: i32) {}
+}
+
+fn main() {}